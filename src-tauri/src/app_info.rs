@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tauri::State;
+use tokio::sync::Mutex;
+
+use crate::vector_store::{self, VectorStoreState};
+
+/// Build and platform details for the About dialog and bug reports
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AppInfo {
+    pub version: String,
+    pub tauri_version: String,
+    pub os: String,
+    pub arch: String,
+    /// `debug` or `release`, to tell which binary a user is running
+    pub build_profile: String,
+    /// Vector store location, `None` until the store has been initialized
+    pub storage_path: Option<String>,
+}
+
+fn app_info(storage_path: Option<String>) -> AppInfo {
+    AppInfo {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        tauri_version: tauri::VERSION.to_string(),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        build_profile: if cfg!(debug_assertions) {
+            "debug"
+        } else {
+            "release"
+        }
+        .to_string(),
+        storage_path,
+    }
+}
+
+#[tauri::command]
+pub async fn get_app_info(
+    vector_store: State<'_, Arc<Mutex<VectorStoreState>>>,
+) -> Result<AppInfo, String> {
+    Ok(app_info(
+        vector_store::initialized_storage_path(&vector_store).await,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_app_info() {
+        let info = app_info(Some("/tmp/vectors".to_string()));
+        assert_eq!(info.version, env!("CARGO_PKG_VERSION"));
+        assert!(!info.tauri_version.is_empty());
+        assert_eq!(info.os, std::env::consts::OS);
+        assert!(["debug", "release"].contains(&info.build_profile.as_str()));
+        assert_eq!(info.storage_path.as_deref(), Some("/tmp/vectors"));
+    }
+}
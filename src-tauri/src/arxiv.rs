@@ -1,28 +1,228 @@
+use futures::stream::{self, StreamExt, TryStreamExt};
 use lazy_static::lazy_static;
 use quick_xml::events::Event;
 use quick_xml::Reader;
+use regex::Regex;
+use reqwest::header::{HeaderMap, HeaderValue, FROM, USER_AGENT};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::error::Error;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, State};
+use tauri_plugin_opener::OpenerExt;
+use tokio::sync::Mutex as AsyncMutex;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, error, info, warn};
 
 lazy_static! {
     static ref CATEGORY_MAP: HashMap<String, String> = {
         let categories_json = include_str!("categories.json");
         serde_json::from_str(categories_json).expect("Failed to parse categories.json")
     };
+    /// Acronym expansions for `expand_synonyms`, keyed by lowercased acronym
+    static ref SYNONYM_MAP: HashMap<String, Vec<String>> = {
+        let synonyms_json = include_str!("synonyms.json");
+        let synonyms: HashMap<String, Vec<String>> =
+            serde_json::from_str(synonyms_json).expect("Failed to parse synonyms.json");
+        synonyms
+            .into_iter()
+            .map(|(acronym, expansions)| (acronym.to_lowercase(), expansions))
+            .collect()
+    };
+    /// Shared across commands so the connection pool and TLS sessions are reused
+    static ref HTTP_CLIENT: reqwest::Client = reqwest::Client::builder()
+        .connect_timeout(DEFAULT_REQUEST_TIMEOUT)
+        .build()
+        .expect("Failed to create HTTP client");
+    /// Current scheme (`YYMM.NNNNN`, four digits before 2015) or the old
+    /// `archive[.SUBJ]/YYMMNNN` one, each with an optional version
+    static ref ARXIV_ID_PATTERN: Regex = Regex::new(
+        r"^(?:\d{2}(?:0[1-9]|1[0-2])\.\d{4,5}|[a-z]+(?:-[a-z]+)*(?:\.[A-Z]{2})?/\d{2}(?:0[1-9]|1[0-2])\d{3})(?:v[1-9]\d*)?$"
+    )
+    .expect("Invalid arXiv id pattern");
+    static ref SEARCH_CACHE: Mutex<SearchCache> = Mutex::new(SearchCache::default());
+    /// Separate from `SEARCH_CACHE` so a burst of searches can't evict the featured feed
+    static ref FEATURED_CACHE: Mutex<SearchCache> = Mutex::new(SearchCache::default());
+    static ref RATE_LIMITER: AsyncMutex<RateLimiter> =
+        AsyncMutex::new(RateLimiter::new(DEFAULT_MIN_REQUEST_INTERVAL));
+    static ref RATE_HISTORY: Mutex<RateHistory> =
+        Mutex::new(RateHistory::new(DEFAULT_MIN_REQUEST_INTERVAL));
+    static ref IN_FLIGHT_SEARCHES: Mutex<InFlightSearches> =
+        Mutex::new(InFlightSearches::default());
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// One `<author>` of an entry
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Author {
+    pub name: String,
+    /// `arxiv:affiliation`, when the submitter provided one
+    pub affiliation: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ArxivPaper {
     pub id: String,
     pub title: String,
     pub authors: String,
+    /// Authors in feed order with their affiliations; `authors` joins their names
+    #[serde(default)]
+    pub authors_detailed: Vec<Author>,
     pub category: String,
     pub published_date: String,
+    /// Date of the latest revision; equals `published_date` for unrevised papers
+    pub updated_date: String,
     pub abstract_text: String,
     pub download_url: String,
     pub pdf_url: String,
     pub categories: Vec<String>,
+    /// Version of the paper returned by arXiv (the `N` in `vN`)
+    pub version: Option<u32>,
+    pub doi: Option<String>,
+    pub journal_ref: Option<String>,
+    pub comment: Option<String>,
+    /// Zero-based position in the full result list (page `start` plus the
+    /// position within the page), so pages can be merged in a stable order
+    #[serde(default)]
+    pub rank: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArxivError {
+    Timeout,
+    Connection,
+    RateLimited,
+    HttpStatus(u16),
+    Network(String),
+    Parse(String),
+    EmptyResponse,
+    /// Rejected locally before any request was sent
+    InvalidQuery(String),
+    /// Aborted through `cancel_arxiv_search`
+    Cancelled,
+}
+
+impl ArxivError {
+    fn from_status(status: u16) -> Self {
+        match status {
+            429 => ArxivError::RateLimited,
+            _ => ArxivError::HttpStatus(status),
+        }
+    }
+}
+
+impl fmt::Display for ArxivError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArxivError::Timeout => write!(
+                f,
+                "Request timed out. Please check your internet connection and try again."
+            ),
+            ArxivError::Connection => write!(
+                f,
+                "Unable to connect to ArXiv. Please check your internet connection."
+            ),
+            ArxivError::RateLimited => write!(
+                f,
+                "Too many requests to ArXiv. Please wait a moment and try again."
+            ),
+            ArxivError::HttpStatus(status @ 500..=599) => write!(
+                f,
+                "ArXiv service is temporarily unavailable ({}). Please try again later.",
+                status
+            ),
+            ArxivError::HttpStatus(status) => write!(
+                f,
+                "ArXiv API error: {} ({})",
+                reqwest::StatusCode::from_u16(*status)
+                    .ok()
+                    .and_then(|s| s.canonical_reason())
+                    .unwrap_or("Unknown"),
+                status
+            ),
+            ArxivError::Network(message) => write!(f, "Network error: {}", message),
+            ArxivError::Parse(message) => write!(f, "Failed to parse ArXiv response: {}", message),
+            ArxivError::EmptyResponse => {
+                write!(f, "Received empty response from ArXiv. Please try again.")
+            }
+            ArxivError::InvalidQuery(message) => write!(f, "Invalid search: {}", message),
+            ArxivError::Cancelled => write!(f, "{}", ARXIV_CANCELLED_MESSAGE),
+        }
+    }
+}
+
+impl std::error::Error for ArxivError {}
+
+impl From<reqwest::Error> for ArxivError {
+    fn from(e: reqwest::Error) -> Self {
+        if e.is_timeout() {
+            ArxivError::Timeout
+        } else if e.is_connect() {
+            ArxivError::Connection
+        } else {
+            ArxivError::Network(e.to_string())
+        }
+    }
+}
+
+impl From<quick_xml::Error> for ArxivError {
+    fn from(e: quick_xml::Error) -> Self {
+        ArxivError::Parse(e.to_string())
+    }
+}
+
+/// Result of `check_arxiv_status`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArxivStatus {
+    pub reachable: bool,
+    /// Round trip of the probe request, measured after rate limiting
+    pub latency_ms: u64,
+    pub message: String,
+}
+
+/// Payload of the `arxiv://done` event that ends a streamed search
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ArxivStreamDone {
+    /// Number of `arxiv://paper` events emitted
+    pub count: usize,
+    /// Number of matches across all pages
+    pub total_results: u64,
+    /// Malformed entries left out across all pages
+    pub skipped_entries: usize,
+}
+
+/// One page of search results together with the feed's opensearch totals
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ArxivSearchResponse {
+    pub papers: Vec<ArxivPaper>,
+    /// Number of matches across all pages
+    pub total_results: u64,
+    pub start_index: u32,
+    pub items_per_page: u32,
+    /// Malformed `<entry>` elements left out of `papers`
+    #[serde(default)]
+    pub skipped_entries: usize,
+}
+
+/// Result of `get_new_submissions`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NewSubmissions {
+    /// Submission day (`YYYY-MM-DD`, UTC) of `papers`. Earlier than today when
+    /// nothing was submitted today yet, e.g. over weekends and holidays.
+    pub date: String,
+    pub papers: Vec<ArxivPaper>,
+}
+
+/// Orderings applied locally after the feed has been fetched and parsed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClientSort {
+    /// Rough "impact" proxy combining how widely a paper is cross-listed
+    /// (number of categories) with how recently it was published.
+    /// arXiv exposes no citation data, so this is a heuristic only and
+    /// must not be presented as a citation count.
+    Impact,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -30,6 +230,25 @@ pub struct ArxivSearchOptions {
     pub max_results: Option<u32>,
     pub sort_by: Option<String>,
     pub sort_order: Option<String>,
+    /// Zero-based offset of the first result, for "load more" pagination
+    pub start: Option<u32>,
+    /// Retries for rate-limited (429), 5xx and timed-out requests
+    pub max_retries: Option<u32>,
+    pub client_sort: Option<ClientSort>,
+    /// Per-request timeout, the configured `timeout_secs` when omitted
+    pub timeout_secs: Option<u64>,
+    /// How long a cached result may be reused, 10 minutes when omitted; 0 bypasses the cache
+    pub cache_ttl_secs: Option<u64>,
+    /// Earliest submission date, `YYYY-MM-DD` or RFC 3339
+    pub from_date: Option<String>,
+    /// Latest submission date, `YYYY-MM-DD` or RFC 3339
+    pub to_date: Option<String>,
+    /// Leave inline LaTeX in titles and abstracts as-is for a math renderer;
+    /// simplified to plain text when omitted
+    pub keep_latex: Option<bool>,
+    /// Also match the spelled-out form of common acronyms (`LLM` finds
+    /// "large language model"); off when omitted
+    pub expand_synonyms: Option<bool>,
 }
 
 impl Default for ArxivSearchOptions {
@@ -38,11 +257,340 @@ impl Default for ArxivSearchOptions {
             max_results: Some(20),
             sort_by: Some("relevance".to_string()),
             sort_order: Some("descending".to_string()),
+            start: None,
+            max_retries: None,
+            client_sort: None,
+            timeout_secs: None,
+            cache_ttl_secs: None,
+            from_date: None,
+            to_date: None,
+            keep_latex: None,
+            expand_synonyms: None,
+        }
+    }
+}
+
+/// Boolean operator used to join the fields of an `ArxivQuery`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum QueryCombinator {
+    #[default]
+    And,
+    Or,
+    AndNot,
+}
+
+impl QueryCombinator {
+    fn as_str(self) -> &'static str {
+        match self {
+            QueryCombinator::And => "AND",
+            QueryCombinator::Or => "OR",
+            QueryCombinator::AndNot => "ANDNOT",
+        }
+    }
+}
+
+/// Structured search using arXiv's field prefixes (`ti:`, `au:`, `abs:`, `cat:`, `all:`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ArxivQuery {
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub abstract_text: Option<String>,
+    pub category: Option<String>,
+    pub all: Option<String>,
+    /// Operator placed between the fields, `AND` when omitted
+    pub combinator: Option<QueryCombinator>,
+}
+
+impl ArxivQuery {
+    /// Assemble the `search_query` expression, e.g. `au:Hinton AND cat:cs.LG`.
+    /// Multi-word values are quoted so arXiv matches them as a phrase.
+    fn to_search_query(&self) -> String {
+        let fields = [
+            ("ti", &self.title),
+            ("au", &self.author),
+            ("abs", &self.abstract_text),
+            ("cat", &self.category),
+            ("all", &self.all),
+        ];
+
+        let separator = format!(" {} ", self.combinator.unwrap_or_default().as_str());
+        fields
+            .iter()
+            .filter_map(|(prefix, value)| {
+                let value = value.as_deref()?.replace('"', "");
+                let value = value.trim();
+                if value.is_empty() {
+                    None
+                } else if value.contains(char::is_whitespace) {
+                    Some(format!("{}:\"{}\"", prefix, value))
+                } else {
+                    Some(format!("{}:{}", prefix, value))
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(&separator)
+    }
+}
+
+/// Encode a `search_query` value the way arXiv expects: spaces become `+`
+/// and phrase quotes become `%22`
+fn encode_search_query(query: &str) -> String {
+    query.replace(' ', "+").replace('"', "%22")
+}
+
+/// Endpoint and limits shared by all arXiv commands, held as managed state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArxivConfig {
+    pub base_url: String,
+    /// Default per-request timeout, overridable per search via `timeout_secs`
+    pub timeout_secs: u64,
+    /// Upper bound applied to `max_results`, at most `HARD_MAX_RESULTS`
+    pub max_results_cap: u32,
+    /// `User-Agent` sent with every request, `Redink/<version>` when omitted
+    #[serde(default)]
+    pub user_agent: Option<String>,
+    /// Contact address sent as the `From` header, as arXiv's API usage
+    /// policy asks of clients; not sent when omitted
+    #[serde(default)]
+    pub contact_email: Option<String>,
+    /// Categories searched when the query is empty (the home screen feed)
+    #[serde(default = "default_featured_categories")]
+    pub featured_categories: Vec<String>,
+}
+
+fn default_featured_categories() -> Vec<String> {
+    DEFAULT_FEATURED_CATEGORIES
+        .iter()
+        .map(|category| category.to_string())
+        .collect()
+}
+
+impl Default for ArxivConfig {
+    fn default() -> Self {
+        Self {
+            base_url: ARXIV_API_BASE.to_string(),
+            timeout_secs: DEFAULT_REQUEST_TIMEOUT.as_secs(),
+            max_results_cap: 100,
+            user_agent: None,
+            contact_email: None,
+            featured_categories: default_featured_categories(),
         }
     }
 }
 
+/// Headers identifying this client to arXiv, built from `config`
+fn request_headers(config: &ArxivConfig) -> Result<HeaderMap, ArxivError> {
+    let user_agent = config
+        .user_agent
+        .clone()
+        .unwrap_or_else(|| format!("Redink/{}", env!("CARGO_PKG_VERSION")));
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        USER_AGENT,
+        HeaderValue::from_str(&user_agent)
+            .map_err(|_| ArxivError::InvalidQuery(format!("Invalid User-Agent: {}", user_agent)))?,
+    );
+    if let Some(contact_email) = &config.contact_email {
+        headers.insert(
+            FROM,
+            HeaderValue::from_str(contact_email).map_err(|_| {
+                ArxivError::InvalidQuery(format!("Invalid contact email: {}", contact_email))
+            })?,
+        );
+    }
+    Ok(headers)
+}
+
 const ARXIV_API_BASE: &str = "https://export.arxiv.org/api/query";
+/// Site hosting the abstract, PDF and HTML pages of each paper
+const ARXIV_SITE_BASE: &str = "https://arxiv.org";
+const DEFAULT_MAX_RETRIES: u32 = 3;
+/// arXiv rejects single requests above this many results
+const HARD_MAX_RESULTS: u32 = 2000;
+/// Large slices are slow for arXiv to generate and tend to time out, so
+/// bigger requests are split into pages of this size
+const MAX_RESULTS_PER_REQUEST: u32 = 500;
+const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(10 * 60);
+const SEARCH_CACHE_CAPACITY: usize = 100;
+/// The featured feed changes slowly and is requested on every visit to the
+/// home screen, so it is kept longer than search results
+const FEATURED_CACHE_TTL: Duration = Duration::from_secs(30 * 60);
+const DEFAULT_FEATURED_CATEGORIES: [&str; 4] = ["cs.AI", "cs.LG", "cs.CL", "cs.CV"];
+/// arXiv's API guidelines ask for roughly 3 seconds between requests
+const DEFAULT_MIN_REQUEST_INTERVAL: Duration = Duration::from_secs(3);
+/// Window `arxiv_rate_status` counts recent requests over
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+/// The status probe should fail fast rather than hang the connectivity indicator
+const STATUS_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+/// First backoff delay, doubled after every retry
+const RETRY_BASE_DELAY: Duration = Duration::from_secs(1);
+const SORT_BY_VALUES: [&str; 3] = ["relevance", "lastUpdatedDate", "submittedDate"];
+const SORT_ORDER_VALUES: [&str; 2] = ["ascending", "descending"];
+/// `ArxivPaper` fields that `search_arxiv_papers` can project onto; `id` and
+/// `rank` are always kept
+const PAPER_FIELDS: [&str; 16] = [
+    "id",
+    "title",
+    "authors",
+    "authors_detailed",
+    "category",
+    "published_date",
+    "updated_date",
+    "abstract_text",
+    "download_url",
+    "pdf_url",
+    "categories",
+    "version",
+    "doi",
+    "journal_ref",
+    "comment",
+    "rank",
+];
+/// Category queries `get_papers_grouped_by_category` keeps in flight at once;
+/// the rate limiter still spaces out the requests themselves
+const GROUPED_CATEGORY_CONCURRENCY: usize = 4;
+/// Exact error string of a cancelled search, so the frontend can ignore it
+const ARXIV_CANCELLED_MESSAGE: &str = "ArXiv search cancelled";
+/// Events emitted by `stream_arxiv_papers`
+const ARXIV_PAPER_EVENT: &str = "arxiv://paper";
+const ARXIV_DONE_EVENT: &str = "arxiv://done";
+
+/// Parsed search responses keyed by request URL, which already encodes the
+/// normalized query, paging, sorting and date range
+#[derive(Default)]
+struct SearchCache {
+    entries: HashMap<String, (Instant, ArxivSearchResponse)>,
+}
+
+impl SearchCache {
+    fn get(&mut self, key: &str, ttl: Duration) -> Option<ArxivSearchResponse> {
+        match self.entries.get(key) {
+            Some((stored_at, response)) if stored_at.elapsed() < ttl => Some(response.clone()),
+            Some(_) => {
+                self.entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Store `response`, evicting the oldest entry once the cache is full
+    fn insert(&mut self, key: String, response: ArxivSearchResponse) {
+        if !self.entries.contains_key(&key) && self.entries.len() >= SEARCH_CACHE_CAPACITY {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, (stored_at, _))| *stored_at)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                self.entries.remove(&oldest);
+            }
+        }
+        self.entries.insert(key, (Instant::now(), response));
+    }
+
+    fn clear(&mut self) -> usize {
+        let count = self.entries.len();
+        self.entries.clear();
+        count
+    }
+}
+
+/// Spaces outbound requests at least `min_interval` apart
+struct RateLimiter {
+    min_interval: Duration,
+    last_request: Option<Instant>,
+}
+
+impl RateLimiter {
+    fn new(min_interval: Duration) -> Self {
+        Self {
+            min_interval,
+            last_request: None,
+        }
+    }
+
+    /// Wait until the interval since the previous request has passed, then
+    /// claim the slot. The lock is held while sleeping, so concurrent callers
+    /// are released one at a time instead of all firing together.
+    async fn acquire(limiter: &AsyncMutex<RateLimiter>) {
+        let mut state = limiter.lock().await;
+        if let Some(last_request) = state.last_request {
+            let elapsed = last_request.elapsed();
+            if elapsed < state.min_interval {
+                let wait = state.min_interval - elapsed;
+                debug!(
+                    "Waiting {:?} to keep requests {:?} apart",
+                    wait, state.min_interval
+                );
+                tokio::time::sleep(wait).await;
+            }
+        }
+        let now = Instant::now();
+        state.last_request = Some(now);
+        RATE_HISTORY.lock().unwrap().record(now, state.min_interval);
+    }
+}
+
+/// Remaining wait and recent usage of the arXiv rate limit, for a UI hint
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RateStatus {
+    /// How long a request made now would wait before being sent
+    pub ms_until_next_allowed: u64,
+    /// `None` when no request has been made yet
+    pub last_request_ago_ms: Option<u64>,
+    pub requests_in_last_minute: usize,
+}
+
+/// Copy of the limiter's timestamps behind a plain mutex. `RateLimiter` is
+/// held while callers sleep, so reading it would wait for the whole queue;
+/// this is only locked for a moment per request.
+struct RateHistory {
+    min_interval: Duration,
+    /// Requests within the last minute, oldest first
+    recent: VecDeque<Instant>,
+}
+
+impl RateHistory {
+    fn new(min_interval: Duration) -> Self {
+        Self {
+            min_interval,
+            recent: VecDeque::new(),
+        }
+    }
+
+    fn record(&mut self, at: Instant, min_interval: Duration) {
+        self.min_interval = min_interval;
+        self.recent.push_back(at);
+        while self
+            .recent
+            .front()
+            .is_some_and(|&oldest| at.duration_since(oldest) >= RATE_WINDOW)
+        {
+            self.recent.pop_front();
+        }
+    }
+
+    fn status(&self, now: Instant) -> RateStatus {
+        let last_request_ago = self
+            .recent
+            .back()
+            .map(|&last| now.saturating_duration_since(last));
+        RateStatus {
+            ms_until_next_allowed: last_request_ago
+                .map_or(Duration::ZERO, |ago| self.min_interval.saturating_sub(ago))
+                .as_millis() as u64,
+            last_request_ago_ms: last_request_ago.map(|ago| ago.as_millis() as u64),
+            requests_in_last_minute: self
+                .recent
+                .iter()
+                .filter(|&&at| now.saturating_duration_since(at) < RATE_WINDOW)
+                .count(),
+        }
+    }
+}
 
 fn get_category_map() -> &'static HashMap<String, String> {
     &CATEGORY_MAP
@@ -62,424 +610,3726 @@ fn format_category(category: &str) -> String {
         })
 }
 
-fn parse_arxiv_xml(xml_content: &str) -> Result<Vec<ArxivPaper>, Box<dyn Error>> {
-    let mut reader = Reader::from_str(xml_content);
-    let mut buf = Vec::new();
-    let mut papers = Vec::new();
-    let mut current_paper: Option<ArxivPaper> = None;
-    let mut current_text = String::new();
-    let mut in_entry = false;
-    let mut authors = Vec::new();
-    let mut categories = Vec::new();
-    let mut primary_category = String::new();
-    let mut pdf_url = String::new();
+/// Heuristic impact score: category breadth, decayed by age in years
+fn impact_score(paper: &ArxivPaper, today: chrono::NaiveDate) -> f64 {
+    let breadth = paper.categories.len() as f64;
+    let recency = chrono::NaiveDate::parse_from_str(&paper.published_date, "%Y-%m-%d")
+        .map(|published| {
+            let age_days = (today - published).num_days().max(0) as f64;
+            1.0 / (1.0 + age_days / 365.0)
+        })
+        .unwrap_or(0.0);
 
-    loop {
-        match reader.read_event_into(&mut buf)? {
-            Event::Start(ref e) => {
-                let element_name = String::from_utf8_lossy(e.name().as_ref()).to_string();
-                current_text.clear();
-
-                if element_name.as_str() == "entry" {
-                    in_entry = true;
-                    current_paper = Some(ArxivPaper {
-                        id: String::new(),
-                        title: String::new(),
-                        authors: String::new(),
-                        category: String::new(),
-                        published_date: String::new(),
-                        abstract_text: String::new(),
-                        download_url: String::new(),
-                        pdf_url: String::new(),
-                        categories: Vec::new(),
-                    });
-                    authors.clear();
-                    categories.clear();
-                    primary_category.clear();
-                    pdf_url.clear();
-                }
+    breadth * recency
+}
+
+fn apply_client_sort(papers: &mut [ArxivPaper], client_sort: ClientSort, today: chrono::NaiveDate) {
+    match client_sort {
+        ClientSort::Impact => {
+            // Stable sort keeps arXiv's own order for equal scores
+            papers.sort_by(|a, b| {
+                impact_score(b, today)
+                    .partial_cmp(&impact_score(a, today))
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+fn parse_arxiv_xml(xml_content: &str) -> Result<Vec<ArxivPaper>, ArxivError> {
+    parse_arxiv_feed(xml_content).map(|response| response.papers)
+}
+
+/// Element name with any namespace prefix (`arxiv:`, `atom:`, ...) removed,
+/// so matching doesn't depend on how the feed declares its namespaces
+fn local_name(name: quick_xml::name::LocalName) -> String {
+    String::from_utf8_lossy(name.as_ref()).to_string()
+}
+
+/// Trim and replace every run of whitespace (newlines, tabs, spaces) with one space
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Greek letter commands spelled out by `simplify_latex`
+const GREEK_LETTERS: [(&str, char); 34] = [
+    ("alpha", 'α'),
+    ("beta", 'β'),
+    ("gamma", 'γ'),
+    ("delta", 'δ'),
+    ("epsilon", 'ε'),
+    ("varepsilon", 'ε'),
+    ("zeta", 'ζ'),
+    ("eta", 'η'),
+    ("theta", 'θ'),
+    ("iota", 'ι'),
+    ("kappa", 'κ'),
+    ("lambda", 'λ'),
+    ("mu", 'μ'),
+    ("nu", 'ν'),
+    ("xi", 'ξ'),
+    ("pi", 'π'),
+    ("rho", 'ρ'),
+    ("sigma", 'σ'),
+    ("tau", 'τ'),
+    ("phi", 'φ'),
+    ("varphi", 'φ'),
+    ("chi", 'χ'),
+    ("psi", 'ψ'),
+    ("omega", 'ω'),
+    ("Gamma", 'Γ'),
+    ("Delta", 'Δ'),
+    ("Theta", 'Θ'),
+    ("Lambda", 'Λ'),
+    ("Xi", 'Ξ'),
+    ("Pi", 'Π'),
+    ("Sigma", 'Σ'),
+    ("Phi", 'Φ'),
+    ("Psi", 'Ψ'),
+    ("Omega", 'Ω'),
+];
+
+/// Character for an entity name such as `amp`, `#956` or `#x3BC`
+fn decode_entity(name: &str) -> Option<char> {
+    match name {
+        "amp" => Some('&'),
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        _ => {
+            let code = name.strip_prefix('#')?;
+            let value = match code.strip_prefix(['x', 'X']) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => code.parse().ok()?,
+            };
+            char::from_u32(value)
+        }
+    }
+}
+
+/// Decode the character references left in text that arXiv escaped twice
+/// (`&amp;#956;` reads as `&#956;` after XML unescaping). Anything that isn't
+/// a recognizable reference is kept verbatim.
+fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        decoded.push_str(&rest[..amp]);
+        let tail = &rest[amp..];
+        let entity = tail
+            .find(';')
+            .and_then(|end| decode_entity(&tail[1..end]).map(|c| (c, end)));
+        match entity {
+            Some((c, end)) => {
+                decoded.push(c);
+                rest = &tail[end + 1..];
             }
-            Event::Empty(ref e) => {
-                let element_name = String::from_utf8_lossy(e.name().as_ref()).to_string();
-
-                match element_name.as_str() {
-                    "category" => {
-                        if let Ok(Some(term)) = e.try_get_attribute("term") {
-                            let term_str = String::from_utf8_lossy(&term.value).to_string();
-                            categories.push(term_str);
-                        }
-                    }
-                    name if name.ends_with("primary_category") => {
-                        if let Ok(Some(term)) = e.try_get_attribute("term") {
-                            primary_category = String::from_utf8_lossy(&term.value).to_string();
-                        }
+            None => {
+                decoded.push('&');
+                rest = &tail[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+/// Plain-text approximation of inline LaTeX for display without a math
+/// renderer: drops `$` delimiters, unescapes `\%`-style specials and spells
+/// out Greek letters. Other commands are left alone.
+fn simplify_latex(text: &str) -> String {
+    let mut simplified = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '$' => {}
+            '\\' => {
+                let mut command = String::new();
+                while let Some(&next) = chars.peek() {
+                    if !next.is_ascii_alphabetic() {
+                        break;
                     }
-                    "link" => {
-                        if let Ok(Some(title)) = e.try_get_attribute("title") {
-                            let title_str = String::from_utf8_lossy(&title.value);
-                            if title_str == "pdf" {
-                                if let Ok(Some(href)) = e.try_get_attribute("href") {
-                                    pdf_url = String::from_utf8_lossy(&href.value).to_string();
-                                }
-                            }
+                    command.push(next);
+                    chars.next();
+                }
+
+                if command.is_empty() {
+                    match chars.peek() {
+                        Some(&special @ ('%' | '&' | '_' | '#' | '$' | '{' | '}')) => {
+                            simplified.push(special);
+                            chars.next();
                         }
+                        _ => simplified.push('\\'),
                     }
-                    _ => {}
+                } else if let Some((_, letter)) =
+                    GREEK_LETTERS.iter().find(|(name, _)| *name == command)
+                {
+                    simplified.push(*letter);
+                } else {
+                    simplified.push('\\');
+                    simplified.push_str(&command);
                 }
             }
-            Event::Text(ref e) => {
-                let text = e.unescape()?.to_string();
-                current_text.push_str(&text);
+            _ => simplified.push(c),
+        }
+    }
+    simplified
+}
+
+/// Normalize title/abstract text: decode leftover character references and
+/// collapse whitespace (including line breaks inside math). Unless
+/// `keep_latex` is set, inline LaTeX is also simplified to plain text.
+fn clean_text(text: &str, keep_latex: bool) -> String {
+    let cleaned = collapse_whitespace(&decode_entities(text));
+    if keep_latex {
+        cleaned
+    } else {
+        simplify_latex(&cleaned)
+    }
+}
+
+/// Apply `simplify_latex` to a paper's title and abstract
+fn simplify_paper_latex(paper: &mut ArxivPaper) {
+    paper.title = simplify_latex(&paper.title);
+    paper.abstract_text = simplify_latex(&paper.abstract_text);
+}
+
+/// Date portion (`YYYY-MM-DD`) of an Atom timestamp like `2024-01-15T09:15:30Z`
+fn date_part(timestamp: &str) -> String {
+    timestamp
+        .trim()
+        .split('T')
+        .next()
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Atom feed parser that keeps its state between calls, so a feed can be
+/// parsed piece by piece (see `FeedStream`). `on_paper` is called as soon as
+/// an entry's closing tag has been read.
+#[derive(Default)]
+struct FeedParser {
+    response: ArxivSearchResponse,
+    current_paper: Option<ArxivPaper>,
+    current_text: String,
+    in_entry: bool,
+    authors: Vec<Author>,
+    /// Author whose `<author>` element is being read
+    current_author: Option<Author>,
+    categories: Vec<String>,
+    primary_category: String,
+    pdf_url: String,
+    /// Entries reported through `on_paper` so far
+    parsed_entries: usize,
+}
+
+/// Byte offset of the next `<entry>` start tag (or `</entry>` end tag when
+/// `closing`) at or after `from`, whatever namespace prefix it uses
+fn find_entry_tag(xml: &str, from: usize, closing: bool) -> Option<usize> {
+    let opener = if closing { "</" } else { "<" };
+    let mut pos = from;
+    while let Some(found) = xml.get(pos..)?.find(opener) {
+        let start = pos + found;
+        let name_start = start + opener.len();
+        let name = xml[name_start..]
+            .split(|c: char| c.is_whitespace() || c == '>' || c == '/')
+            .next()
+            .unwrap_or_default();
+        if name.rsplit(':').next() == Some("entry") {
+            return Some(start);
+        }
+        pos = name_start;
+    }
+    None
+}
+
+impl FeedParser {
+    /// Parse `xml_content`, leaving out any `<entry>` that isn't well-formed
+    /// instead of discarding the whole feed. Errors outside an entry skip
+    /// ahead to the next one; the feed only fails when an error leaves nothing
+    /// to resume from and no entry has been read.
+    fn parse(
+        &mut self,
+        xml_content: &str,
+        on_paper: &mut impl FnMut(ArxivPaper),
+    ) -> Result<(), ArxivError> {
+        let mut offset = 0;
+        while let Err((error, position)) =
+            self.read_events(&xml_content[offset..], offset > 0, on_paper)
+        {
+            let position = offset + position;
+            let resume = if self.in_entry {
+                warn!("Skipping malformed entry: {}", error);
+                self.response.skipped_entries += 1;
+                self.current_paper = None;
+                self.in_entry = false;
+                find_entry_tag(xml_content, position, true)
+                    .and_then(|end| xml_content[end..].find('>').map(|close| end + close + 1))
+            } else {
+                find_entry_tag(xml_content, position, false)
+            };
+
+            match resume {
+                Some(next) if next > offset => offset = next,
+                _ if self.parsed_entries > 0 => {
+                    warn!("Ignoring unreadable end of feed: {}", error);
+                    return Ok(());
+                }
+                _ => return Err(error),
             }
-            Event::End(ref e) => {
-                let element_name = String::from_utf8_lossy(e.name().as_ref()).to_string();
+        }
+        Ok(())
+    }
+
+    /// Read events until the end of `xml_content`. On failure returns the
+    /// error and the offset to resume searching from. A `resumed` read starts
+    /// after the feed's start tag, so its closing tag is unmatched.
+    fn read_events(
+        &mut self,
+        xml_content: &str,
+        resumed: bool,
+        on_paper: &mut impl FnMut(ArxivPaper),
+    ) -> Result<(), (ArxivError, usize)> {
+        let mut reader = Reader::from_str(xml_content);
+        reader.config_mut().allow_unmatched_ends = resumed;
+        let mut buf = Vec::new();
+        // End of the last event read successfully; errors are never reported before it
+        let mut last_good = 0;
+
+        loop {
+            let event = match reader.read_event_into(&mut buf) {
+                Ok(event) => event,
+                Err(e) => {
+                    let position = reader.error_position().max(last_good);
+                    return Err((e.into(), position as usize));
+                }
+            };
+            last_good = reader.buffer_position();
+            match event {
+                Event::Start(ref e) => {
+                    let element_name = local_name(e.local_name());
+                    self.current_text.clear();
 
-                if in_entry && current_paper.is_some() {
-                    let paper = current_paper.as_mut().unwrap();
+                    if element_name.as_str() == "author" && self.in_entry {
+                        self.current_author = Some(Author::default());
+                    } else if element_name.as_str() == "entry" {
+                        self.in_entry = true;
+                        self.current_paper = Some(ArxivPaper {
+                            id: String::new(),
+                            title: String::new(),
+                            authors: String::new(),
+                            authors_detailed: Vec::new(),
+                            category: String::new(),
+                            published_date: String::new(),
+                            updated_date: String::new(),
+                            abstract_text: String::new(),
+                            download_url: String::new(),
+                            pdf_url: String::new(),
+                            categories: Vec::new(),
+                            version: None,
+                            doi: None,
+                            journal_ref: None,
+                            comment: None,
+                            rank: 0,
+                        });
+                        self.authors.clear();
+                        self.current_author = None;
+                        self.categories.clear();
+                        self.primary_category.clear();
+                        self.pdf_url.clear();
+                    }
+                }
+                Event::Empty(ref e) => {
+                    let element_name = local_name(e.local_name());
 
                     match element_name.as_str() {
-                        "id" => {
-                            // Extract arXiv ID from URL
-                            if let Some(id_part) = current_text.split("/abs/").nth(1) {
-                                paper.id = id_part.split('v').next().unwrap_or(id_part).to_string();
+                        "category" => {
+                            if let Ok(Some(term)) = e.try_get_attribute("term") {
+                                let term_str = String::from_utf8_lossy(&term.value).to_string();
+                                self.categories.push(term_str);
                             }
                         }
-                        "title" => {
-                            paper.title = current_text.trim().replace('\n', " ").replace("  ", " ");
-                        }
-                        "summary" => {
-                            paper.abstract_text =
-                                current_text.trim().replace('\n', " ").replace("  ", " ");
-                        }
-                        "published" => {
-                            paper.published_date = current_text
-                                .split('T')
-                                .next()
-                                .unwrap_or(&current_text)
-                                .to_string();
+                        "primary_category" => {
+                            if let Ok(Some(term)) = e.try_get_attribute("term") {
+                                self.primary_category =
+                                    String::from_utf8_lossy(&term.value).to_string();
+                            }
                         }
-                        "name" => {
-                            // This is an author name inside an author element
-                            authors.push(current_text.trim().to_string());
+                        "link" => {
+                            if let Ok(Some(title)) = e.try_get_attribute("title") {
+                                let title_str = String::from_utf8_lossy(&title.value);
+                                if title_str == "pdf" {
+                                    if let Ok(Some(href)) = e.try_get_attribute("href") {
+                                        self.pdf_url =
+                                            String::from_utf8_lossy(&href.value).to_string();
+                                    }
+                                }
+                            }
                         }
-                        "entry" => {
-                            // Finalize the paper
-                            paper.authors = authors.join(", ");
-                            if paper.authors.is_empty() {
-                                paper.authors = "Unknown".to_string();
+                        _ => {}
+                    }
+                }
+                Event::Text(ref e) => {
+                    // Keep text with an unknown entity as-is rather than failing the entry
+                    let text = match e.unescape() {
+                        Ok(text) => text.to_string(),
+                        Err(_) => String::from_utf8_lossy(e).to_string(),
+                    };
+                    self.current_text.push_str(&text);
+                }
+                Event::End(ref e) => {
+                    let element_name = local_name(e.local_name());
+
+                    // Feed-level opensearch totals (<opensearch:totalResults> etc.)
+                    if !self.in_entry {
+                        match element_name.as_str() {
+                            "totalResults" => {
+                                self.response.total_results =
+                                    self.current_text.trim().parse().unwrap_or(0);
+                            }
+                            "startIndex" => {
+                                self.response.start_index =
+                                    self.current_text.trim().parse().unwrap_or(0);
+                            }
+                            "itemsPerPage" => {
+                                self.response.items_per_page =
+                                    self.current_text.trim().parse().unwrap_or(0);
                             }
+                            _ => {}
+                        }
+                    }
 
-                            let main_category = if !primary_category.is_empty() {
-                                primary_category.clone()
-                            } else if !categories.is_empty() {
-                                categories[0].clone()
-                            } else {
-                                "Unknown".to_string()
-                            };
-
-                            paper.category = format_category(&main_category);
-                            paper.categories = categories.clone();
-
-                            // Set PDF URL
-                            if !pdf_url.is_empty() {
-                                paper.pdf_url = pdf_url.clone();
-                                paper.download_url = pdf_url.clone();
-                            } else {
-                                let pdf_url_fallback =
-                                    format!("https://arxiv.org/pdf/{}.pdf", paper.id);
-                                paper.pdf_url = pdf_url_fallback.clone();
-                                paper.download_url = pdf_url_fallback;
+                    if self.in_entry && self.current_paper.is_some() {
+                        let paper = self.current_paper.as_mut().unwrap();
+
+                        match element_name.as_str() {
+                            "id" => {
+                                // Extract arXiv ID from URL
+                                if let Some(id_part) = self.current_text.split("/abs/").nth(1) {
+                                    let (id, version) = split_version(id_part.trim());
+                                    paper.id = id.to_string();
+                                    paper.version = version;
+                                }
+                            }
+                            "title" => {
+                                paper.title = clean_text(&self.current_text, true);
+                            }
+                            "summary" => {
+                                paper.abstract_text = clean_text(&self.current_text, true);
+                            }
+                            "published" => {
+                                paper.published_date = date_part(&self.current_text);
+                            }
+                            "updated" => {
+                                paper.updated_date = date_part(&self.current_text);
+                            }
+                            "doi" => {
+                                paper.doi = Some(self.current_text.trim().to_string());
+                            }
+                            "journal_ref" => {
+                                paper.journal_ref = Some(self.current_text.trim().to_string());
+                            }
+                            "comment" => {
+                                paper.comment = Some(self.current_text.trim().to_string());
+                            }
+                            "name" => {
+                                let name = collapse_whitespace(&self.current_text);
+                                match self.current_author.as_mut() {
+                                    Some(author) => author.name = name,
+                                    None => self.authors.push(Author {
+                                        name,
+                                        affiliation: None,
+                                    }),
+                                }
+                            }
+                            "affiliation" => {
+                                let affiliation = collapse_whitespace(&self.current_text);
+                                if let Some(author) = self.current_author.as_mut() {
+                                    if !affiliation.is_empty() {
+                                        // Several affiliations are listed as separate elements
+                                        author.affiliation =
+                                            Some(match author.affiliation.take() {
+                                                Some(previous) => {
+                                                    format!("{}; {}", previous, affiliation)
+                                                }
+                                                None => affiliation,
+                                            });
+                                    }
+                                }
+                            }
+                            "author" => {
+                                if let Some(author) = self.current_author.take() {
+                                    if !author.name.is_empty() {
+                                        self.authors.push(author);
+                                    }
+                                }
                             }
+                            "entry" => {
+                                // Finalize the paper
+                                paper.authors = self
+                                    .authors
+                                    .iter()
+                                    .map(|author| author.name.as_str())
+                                    .collect::<Vec<_>>()
+                                    .join(", ");
+                                paper.authors_detailed = self.authors.clone();
+                                if paper.authors.is_empty() {
+                                    paper.authors = "Unknown".to_string();
+                                }
+
+                                let main_category = if !self.primary_category.is_empty() {
+                                    self.primary_category.clone()
+                                } else if !self.categories.is_empty() {
+                                    self.categories[0].clone()
+                                } else {
+                                    "Unknown".to_string()
+                                };
+
+                                paper.category = format_category(&main_category);
+                                paper.categories = self.categories.clone();
 
-                            papers.push(paper.clone());
-                            current_paper = None;
-                            in_entry = false;
+                                // Set PDF URL
+                                if !self.pdf_url.is_empty() {
+                                    paper.pdf_url = self.pdf_url.clone();
+                                    paper.download_url = self.pdf_url.clone();
+                                } else {
+                                    // Point at the exact version this entry describes
+                                    let versioned_id = match paper.version {
+                                        Some(version) => format!("{}v{}", paper.id, version),
+                                        None => paper.id.clone(),
+                                    };
+                                    let pdf_url_fallback =
+                                        format!("https://arxiv.org/pdf/{}.pdf", versioned_id);
+                                    paper.pdf_url = pdf_url_fallback.clone();
+                                    paper.download_url = pdf_url_fallback;
+                                }
+
+                                paper.rank = self.parsed_entries as u32;
+                                on_paper(paper.clone());
+                                self.parsed_entries += 1;
+                                self.current_paper = None;
+                                self.in_entry = false;
+                            }
+                            _ => {}
                         }
-                        _ => {}
                     }
+                    self.current_text.clear();
                 }
-                current_text.clear();
+                Event::Eof => break,
+                _ => {}
             }
-            Event::Eof => break,
-            _ => {}
+            buf.clear();
         }
-        buf.clear();
-    }
 
-    Ok(papers)
+        Ok(())
+    }
 }
 
-async fn fetch_arxiv_papers(
-    query: &str,
-    options: &ArxivSearchOptions,
-) -> Result<Vec<ArxivPaper>, Box<dyn Error>> {
-    let max_results = options.max_results.unwrap_or(20);
-    let sort_by = options.sort_by.as_deref().unwrap_or("relevance");
-    let sort_order = options.sort_order.as_deref().unwrap_or("descending");
-
-    // Handle empty query - fetch featured papers instead
-    let actual_query = if query.trim().is_empty() {
-        "cat:cs.AI+OR+cat:cs.LG+OR+cat:cs.CL+OR+cat:cs.CV"
-    } else {
-        query
-    };
+fn parse_arxiv_feed(xml_content: &str) -> Result<ArxivSearchResponse, ArxivError> {
+    let mut parser = FeedParser::default();
+    let mut papers = Vec::new();
+    parser.parse(xml_content, &mut |paper| papers.push(paper))?;
+    Ok(ArxivSearchResponse {
+        papers,
+        ..parser.response
+    })
+}
 
-    let encoded_query = actual_query.replace(' ', "+");
+/// Splits a feed arriving in network chunks after its last complete
+/// `</entry>` and hands each finished piece to a `FeedParser`, so papers are
+/// reported while the rest of the body is still downloading
+#[derive(Default)]
+struct FeedStream {
+    pending: Vec<u8>,
+    received: usize,
+    parsed_pieces: usize,
+    parser: FeedParser,
+}
 
-    let url = format!(
-        "{}?search_query={}&start=0&max_results={}&sortBy={}&sortOrder={}",
-        ARXIV_API_BASE, encoded_query, max_results, sort_by, sort_order
-    );
+impl FeedStream {
+    const ENTRY_END: &'static [u8] = b"</entry>";
 
-    println!("[ArXiv Rust] Fetching from URL: {}", url);
+    fn push(
+        &mut self,
+        chunk: &[u8],
+        on_paper: &mut impl FnMut(ArxivPaper),
+    ) -> Result<(), ArxivError> {
+        self.received += chunk.len();
+        self.pending.extend_from_slice(chunk);
 
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(30))
-        .build()
-        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+        let Some(pos) = self
+            .pending
+            .windows(Self::ENTRY_END.len())
+            .rposition(|window| window == Self::ENTRY_END)
+        else {
+            return Ok(());
+        };
+        let complete: Vec<u8> = self.pending.drain(..pos + Self::ENTRY_END.len()).collect();
+        self.parse_piece(&complete, on_paper)
+    }
 
-    let response = client.get(&url).send().await.map_err(|e| {
-        if e.is_timeout() {
-            "Request timed out. Please check your internet connection and try again.".to_string()
-        } else if e.is_connect() {
-            "Failed to connect to ArXiv. Please check your internet connection.".to_string()
+    fn parse_piece(
+        &mut self,
+        piece: &[u8],
+        on_paper: &mut impl FnMut(ArxivPaper),
+    ) -> Result<(), ArxivError> {
+        let piece = String::from_utf8_lossy(piece);
+        // Only the first piece contains the real <feed> start tag; re-open it
+        // for later pieces so the closing </feed> still has a match
+        let result = if self.parsed_pieces == 0 {
+            self.parser.parse(&piece, on_paper)
         } else {
-            format!("Network error: {}", e)
-        }
-    })?;
-
-    if !response.status().is_success() {
-        let status = response.status();
-        let error_msg = match status.as_u16() {
-            429 => "Too many requests to ArXiv. Please wait a moment and try again.".to_string(),
-            500..=599 => {
-                "ArXiv service is temporarily unavailable. Please try again later.".to_string()
-            }
-            _ => format!(
-                "ArXiv API error: {} ({})",
-                status.canonical_reason().unwrap_or("Unknown"),
-                status
-            ),
+            self.parser.parse(&format!("<feed>{}", piece), on_paper)
         };
-        return Err(error_msg.into());
+        self.parsed_pieces += 1;
+        result
     }
 
-    let xml_content = response
-        .text()
-        .await
-        .map_err(|e| format!("Failed to read response from ArXiv: {}", e))?;
-    println!("[ArXiv Rust] Received XML, length: {}", xml_content.len());
-
-    if xml_content.is_empty() {
-        return Err("Received empty response from ArXiv. Please try again.".into());
+    /// Parse whatever is left and return the feed totals (without papers)
+    fn finish(
+        mut self,
+        on_paper: &mut impl FnMut(ArxivPaper),
+    ) -> Result<ArxivSearchResponse, ArxivError> {
+        if self.received == 0 {
+            return Err(ArxivError::EmptyResponse);
+        }
+        let rest = std::mem::take(&mut self.pending);
+        self.parse_piece(&rest, on_paper)?;
+        Ok(self.parser.response)
     }
-
-    let papers = parse_arxiv_xml(&xml_content)
-        .map_err(|e| format!("Failed to parse ArXiv response: {}", e))?;
-    println!("[ArXiv Rust] Parsed {} papers", papers.len());
-
-    Ok(papers)
 }
 
-#[tauri::command]
+/// Convert an ISO date into arXiv's `YYYYMMDDHHMM` form. Plain dates cover the
+/// whole day, so `end_of_day` picks 23:59 instead of 00:00.
+fn format_submitted_date(value: &str, end_of_day: bool) -> Result<String, ArxivError> {
+    let value = value.trim();
+    if let Ok(datetime) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Ok(datetime
+            .with_timezone(&chrono::Utc)
+            .format("%Y%m%d%H%M")
+            .to_string());
+    }
+
+    chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map(|date| {
+            let time = if end_of_day { "2359" } else { "0000" };
+            format!("{}{}", date.format("%Y%m%d"), time)
+        })
+        .map_err(|_| {
+            ArxivError::InvalidQuery(format!(
+                "'{}' is not a valid date (expected YYYY-MM-DD or RFC 3339)",
+                value
+            ))
+        })
+}
+
+/// `submittedDate:[from TO to]` clause for the options' date range, if any
+fn submitted_date_clause(options: &ArxivSearchOptions) -> Result<Option<String>, ArxivError> {
+    if options.from_date.is_none() && options.to_date.is_none() {
+        return Ok(None);
+    }
+
+    let from = match options.from_date.as_deref() {
+        Some(date) => format_submitted_date(date, false)?,
+        None => "000101010000".to_string(),
+    };
+    let to = match options.to_date.as_deref() {
+        Some(date) => format_submitted_date(date, true)?,
+        None => chrono::Utc::now().format("%Y%m%d%H%M").to_string(),
+    };
+
+    if from > to {
+        return Err(ArxivError::InvalidQuery(
+            "from_date must not be after to_date".to_string(),
+        ));
+    }
+
+    Ok(Some(format!("submittedDate:[{} TO {}]", from, to)))
+}
+
+/// Map `value` onto one of arXiv's accepted spellings (case-insensitively),
+/// rejecting anything else with the list of valid options
+fn validate_choice(
+    field: &str,
+    value: &str,
+    allowed: &[&'static str],
+) -> Result<&'static str, ArxivError> {
+    allowed
+        .iter()
+        .find(|candidate| candidate.eq_ignore_ascii_case(value.trim()))
+        .copied()
+        .ok_or_else(|| {
+            ArxivError::InvalidQuery(format!(
+                "unsupported {} '{}' (expected one of: {})",
+                field,
+                value,
+                allowed.join(", ")
+            ))
+        })
+}
+
+/// Number of results to fetch for a request of `requested`, after applying
+/// the configured cap and arXiv's hard limit
+fn clamp_max_results(config: &ArxivConfig, requested: u32) -> Result<u32, ArxivError> {
+    if requested == 0 {
+        return Err(ArxivError::InvalidQuery(
+            "max_results must be greater than zero".to_string(),
+        ));
+    }
+    Ok(requested.min(config.max_results_cap).min(HARD_MAX_RESULTS))
+}
+
+/// Split `total` results starting at `start` into `(start, max_results)` pages
+fn page_ranges(start: u32, total: u32, page_size: u32) -> Vec<(u32, u32)> {
+    (0..total)
+        .step_by(page_size as usize)
+        .map(|offset| (start + offset, page_size.min(total - offset)))
+        .collect()
+}
+
+/// Expand one query term into an `OR` of itself and its spelled-out forms,
+/// keeping any field prefix (`ti:LLM`) and surrounding parentheses
+fn expand_term(term: &str) -> Option<String> {
+    let inner = term.trim_start_matches('(');
+    let open = &term[..term.len() - inner.len()];
+    let word = inner.trim_end_matches(')');
+    let close = &inner[word.len()..];
+    let (field, word) = match word.split_once(':') {
+        Some((field, word))
+            if !field.is_empty() && field.chars().all(|c| c.is_ascii_alphabetic()) =>
+        {
+            (format!("{}:", field), word)
+        }
+        _ => (String::new(), word),
+    };
+    if word.is_empty() || !word.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return None;
+    }
+
+    let expansions = SYNONYM_MAP.get(&word.to_lowercase())?;
+    let alternatives: Vec<String> = std::iter::once(format!("{}{}", field, word))
+        .chain(
+            expansions
+                .iter()
+                .map(|expansion| format!("{}\"{}\"", field, expansion)),
+        )
+        .collect();
+    Some(format!("{}({}){}", open, alternatives.join(" OR "), close))
+}
+
+/// Rewrite known acronyms in `query` as an `OR` of the acronym and its
+/// expansions. Only whole bare terms are touched: operators, quoted phrases
+/// and terms with punctuation (ids, category codes) are left alone, and the
+/// built-in list sticks to unambiguous acronyms to keep results focused.
+fn expand_synonyms(query: &str) -> String {
+    let mut expanded = String::with_capacity(query.len());
+    let mut in_quotes = false;
+    let mut term_start = 0;
+    let separators = query
+        .match_indices([' ', '+'])
+        .map(|(i, sep)| (i, Some(sep)));
+    for (end, separator) in separators.chain(std::iter::once((query.len(), None))) {
+        let term = &query[term_start..end];
+        match expand_term(term) {
+            Some(alternatives) if !in_quotes && !term.contains('"') => {
+                expanded.push_str(&alternatives)
+            }
+            _ => expanded.push_str(term),
+        }
+        if term.matches('"').count() % 2 == 1 {
+            in_quotes = !in_quotes;
+        }
+        if let Some(separator) = separator {
+            expanded.push_str(separator);
+            term_start = end + separator.len();
+        }
+    }
+    expanded
+}
+
+fn build_search_url(
+    config: &ArxivConfig,
+    query: &str,
+    options: &ArxivSearchOptions,
+) -> Result<String, ArxivError> {
+    let max_results = options.max_results.unwrap_or(20);
+    let sort_by = validate_choice(
+        "sort_by",
+        options.sort_by.as_deref().unwrap_or("relevance"),
+        &SORT_BY_VALUES,
+    )?;
+    let sort_order = validate_choice(
+        "sort_order",
+        options.sort_order.as_deref().unwrap_or("descending"),
+        &SORT_ORDER_VALUES,
+    )?;
+    let start = options.start.unwrap_or(0);
+
+    // Handle empty query - fetch featured papers instead
+    let featured_query;
+    let actual_query = if query.trim().is_empty() {
+        featured_query = categories_query(&config.featured_categories, false);
+        featured_query.as_str()
+    } else {
+        query
+    };
+
+    let expanded_query;
+    let actual_query = if options.expand_synonyms.unwrap_or(false) {
+        expanded_query = expand_synonyms(actual_query);
+        if expanded_query != actual_query {
+            debug!("Expanded query: {}", expanded_query);
+        }
+        expanded_query.as_str()
+    } else {
+        actual_query
+    };
+
+    let search_query = match submitted_date_clause(options)? {
+        Some(clause) => format!("({}) AND {}", actual_query, clause),
+        None => actual_query.to_string(),
+    };
+    let encoded_query = encode_search_query(&search_query);
+
+    Ok(format!(
+        "{}?search_query={}&start={}&max_results={}&sortBy={}&sortOrder={}",
+        config.base_url, encoded_query, start, max_results, sort_by, sort_order
+    ))
+}
+
+fn is_retryable(error: &ArxivError) -> bool {
+    matches!(
+        error,
+        ArxivError::RateLimited | ArxivError::Timeout | ArxivError::HttpStatus(500..=599)
+    )
+}
+
+/// Delay requested by the server via a `Retry-After: <seconds>` header
+fn retry_after(response: &reqwest::Response) -> Option<Duration> {
+    response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+/// GET `url`, backing off exponentially on 429/5xx responses and timeouts.
+/// Other failures (e.g. 400 for a bad query) are returned immediately.
+async fn send_with_retry(
+    client: &reqwest::Client,
+    url: &str,
+    headers: &HeaderMap,
+    timeout: Duration,
+    max_retries: u32,
+    base_delay: Duration,
+) -> Result<reqwest::Response, ArxivError> {
+    let mut attempt = 0;
+    loop {
+        let (error, server_delay) = match client
+            .get(url)
+            .headers(headers.clone())
+            .timeout(timeout)
+            .send()
+            .await
+        {
+            Ok(response) if response.status().is_success() => return Ok(response),
+            Ok(response) => (
+                ArxivError::from_status(response.status().as_u16()),
+                retry_after(&response),
+            ),
+            Err(e) => (ArxivError::from(e), None),
+        };
+
+        if attempt >= max_retries || !is_retryable(&error) {
+            return Err(error);
+        }
+
+        let delay = server_delay.unwrap_or(base_delay * 2u32.pow(attempt));
+        attempt += 1;
+        warn!(
+            "{} Retrying in {:?} (attempt {}/{})",
+            error, delay, attempt, max_retries
+        );
+        tokio::time::sleep(delay).await;
+    }
+}
+
+fn build_id_list_url(config: &ArxivConfig, arxiv_ids: &[String]) -> String {
+    format!(
+        "{}?id_list={}&max_results={}",
+        config.base_url,
+        arxiv_ids.join(","),
+        arxiv_ids.len()
+    )
+}
+
+/// Split a trailing version suffix such as `v2` off an arXiv id
+fn split_version(id: &str) -> (&str, Option<u32>) {
+    match id.rfind('v') {
+        Some(pos) if pos + 1 < id.len() && id[pos + 1..].chars().all(|c| c.is_ascii_digit()) => {
+            (&id[..pos], id[pos + 1..].parse().ok())
+        }
+        _ => (id, None),
+    }
+}
+
+/// Strip a trailing version suffix such as `v2` from an arXiv id
+fn strip_version(id: &str) -> &str {
+    split_version(id).0
+}
+
+/// Arrange papers in the order they were requested, dropping ids arXiv didn't return
+fn order_by_requested_ids(papers: Vec<ArxivPaper>, arxiv_ids: &[String]) -> Vec<ArxivPaper> {
+    let mut by_id: HashMap<String, ArxivPaper> = papers
+        .into_iter()
+        .map(|paper| (paper.id.clone(), paper))
+        .collect();
+
+    arxiv_ids
+        .iter()
+        .filter_map(|id| by_id.remove(strip_version(id.trim())))
+        .collect()
+}
+
+/// Fetch `options.max_results` papers (clamped), paging through the feed when
+/// more than `MAX_RESULTS_PER_REQUEST` are requested
+async fn fetch_arxiv_papers(
+    config: &ArxivConfig,
+    query: &str,
+    options: &ArxivSearchOptions,
+) -> Result<ArxivSearchResponse, ArxivError> {
+    let requested = options.max_results.unwrap_or(20);
+    let total = clamp_max_results(config, requested)?;
+    if total < requested {
+        warn!("Clamping max_results from {} to {}", requested, total);
+    }
+
+    let mut combined: Option<ArxivSearchResponse> = None;
+    for (page_start, page_size) in
+        page_ranges(options.start.unwrap_or(0), total, MAX_RESULTS_PER_REQUEST)
+    {
+        let page_options = ArxivSearchOptions {
+            start: Some(page_start),
+            max_results: Some(page_size),
+            ..options.clone()
+        };
+        let page = fetch_search_page(config, query, &page_options).await?;
+        let exhausted = (page.papers.len() as u32) < page_size;
+
+        match combined.as_mut() {
+            Some(response) => response.papers.extend(page.papers),
+            None => combined = Some(page),
+        }
+        if exhausted {
+            break;
+        }
+    }
+
+    Ok(combined.unwrap_or_default())
+}
+
+async fn fetch_search_page(
+    config: &ArxivConfig,
+    query: &str,
+    options: &ArxivSearchOptions,
+) -> Result<ArxivSearchResponse, ArxivError> {
+    let url = build_search_url(config, query, options)?;
+    let max_retries = options.max_retries.unwrap_or(DEFAULT_MAX_RETRIES);
+    let timeout = Duration::from_secs(options.timeout_secs.unwrap_or(config.timeout_secs));
+    let (cache, default_ttl) = if query.trim().is_empty() {
+        (&*FEATURED_CACHE, FEATURED_CACHE_TTL)
+    } else {
+        (&*SEARCH_CACHE, DEFAULT_CACHE_TTL)
+    };
+    let cache_ttl = options
+        .cache_ttl_secs
+        .map(Duration::from_secs)
+        .unwrap_or(default_ttl);
+
+    if let Some(cached) = cache.lock().unwrap().get(&url, cache_ttl) {
+        debug!("Serving cached results for URL: {}", url);
+        return Ok(cached);
+    }
+
+    let headers = request_headers(config)?;
+    let mut response = fetch_arxiv_url(&url, &headers, timeout, max_retries).await?;
+    // The parser ranks entries within the page
+    let start = options.start.unwrap_or(0);
+    response
+        .papers
+        .iter_mut()
+        .for_each(|paper| paper.rank += start);
+    if !cache_ttl.is_zero() {
+        cache.lock().unwrap().insert(url, response.clone());
+    }
+    Ok(response)
+}
+
+async fn fetch_arxiv_url(
+    url: &str,
+    headers: &HeaderMap,
+    timeout: Duration,
+    max_retries: u32,
+) -> Result<ArxivSearchResponse, ArxivError> {
+    RateLimiter::acquire(&RATE_LIMITER).await;
+    info!("Fetching from URL: {}", url);
+
+    let response = send_with_retry(
+        &HTTP_CLIENT,
+        url,
+        headers,
+        timeout,
+        max_retries,
+        RETRY_BASE_DELAY,
+    )
+    .await?;
+
+    let xml_content = response.text().await?;
+    debug!("Received XML, length: {}", xml_content.len());
+
+    if xml_content.is_empty() {
+        return Err(ArxivError::EmptyResponse);
+    }
+
+    let response = parse_arxiv_feed(&xml_content)?;
+    info!(
+        "Parsed {} papers ({} total results)",
+        response.papers.len(),
+        response.total_results
+    );
+
+    Ok(response)
+}
+
+/// Like `fetch_arxiv_url`, but parses the body as it arrives and reports each
+/// paper through `on_paper`. The returned response only carries the totals.
+async fn stream_arxiv_url(
+    url: &str,
+    headers: &HeaderMap,
+    timeout: Duration,
+    max_retries: u32,
+    on_paper: &mut impl FnMut(ArxivPaper),
+) -> Result<ArxivSearchResponse, ArxivError> {
+    RateLimiter::acquire(&RATE_LIMITER).await;
+    info!("Streaming from URL: {}", url);
+
+    let mut response = send_with_retry(
+        &HTTP_CLIENT,
+        url,
+        headers,
+        timeout,
+        max_retries,
+        RETRY_BASE_DELAY,
+    )
+    .await?;
+
+    let mut stream = FeedStream::default();
+    while let Some(chunk) = response.chunk().await? {
+        stream.push(&chunk, on_paper)?;
+    }
+    stream.finish(on_paper)
+}
+
+/// Streaming counterpart of `fetch_arxiv_papers`. Streamed pages bypass the
+/// search cache and `client_sort`, since papers are reported before the
+/// whole result set is known.
+async fn stream_arxiv_papers_to(
+    config: &ArxivConfig,
+    query: &str,
+    options: &ArxivSearchOptions,
+    on_paper: &mut impl FnMut(ArxivPaper),
+) -> Result<ArxivStreamDone, ArxivError> {
+    let total = clamp_max_results(config, options.max_results.unwrap_or(20))?;
+    let max_retries = options.max_retries.unwrap_or(DEFAULT_MAX_RETRIES);
+    let timeout = Duration::from_secs(options.timeout_secs.unwrap_or(config.timeout_secs));
+    let headers = request_headers(config)?;
+
+    let mut done = ArxivStreamDone::default();
+    for (page_start, page_size) in
+        page_ranges(options.start.unwrap_or(0), total, MAX_RESULTS_PER_REQUEST)
+    {
+        let page_options = ArxivSearchOptions {
+            start: Some(page_start),
+            max_results: Some(page_size),
+            ..options.clone()
+        };
+        let url = build_search_url(config, query, &page_options)?;
+
+        let mut page_count = 0;
+        let page = stream_arxiv_url(&url, &headers, timeout, max_retries, &mut |mut paper| {
+            page_count += 1;
+            paper.rank += page_start;
+            if !options.keep_latex.unwrap_or(false) {
+                simplify_paper_latex(&mut paper);
+            }
+            on_paper(paper);
+        })
+        .await?;
+
+        done.count += page_count;
+        done.total_results = page.total_results;
+        done.skipped_entries += page.skipped_entries;
+        if (page_count as u32) < page_size {
+            break;
+        }
+    }
+
+    Ok(done)
+}
+
+/// Cancellation tokens of searches started with a `request_id`. The
+/// generation tells a finished search apart from a newer one reusing its id.
+#[derive(Default)]
+struct InFlightSearches {
+    next_generation: u64,
+    tokens: HashMap<String, (u64, CancellationToken)>,
+}
+
+impl InFlightSearches {
+    fn register(&mut self, request_id: &str) -> (u64, CancellationToken) {
+        let generation = self.next_generation;
+        self.next_generation += 1;
+        let token = CancellationToken::new();
+        // Reusing an id supersedes the search that still holds it
+        if let Some((_, previous)) = self
+            .tokens
+            .insert(request_id.to_string(), (generation, token.clone()))
+        {
+            previous.cancel();
+        }
+        (generation, token)
+    }
+
+    fn finish(&mut self, request_id: &str, generation: u64) {
+        if matches!(self.tokens.get(request_id), Some((current, _)) if *current == generation) {
+            self.tokens.remove(request_id);
+        }
+    }
+
+    fn cancel(&mut self, request_id: &str) -> bool {
+        match self.tokens.remove(request_id) {
+            Some((_, token)) => {
+                token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Run `search`, aborting it as soon as `cancel_arxiv_search(request_id)` is
+/// called. The search future is dropped, which drops any in-flight request.
+async fn cancellable<T>(
+    request_id: Option<String>,
+    search: impl Future<Output = Result<T, String>>,
+) -> Result<T, String> {
+    let Some(request_id) = request_id else {
+        return search.await;
+    };
+
+    let (generation, token) = IN_FLIGHT_SEARCHES.lock().unwrap().register(&request_id);
+    let result = tokio::select! {
+        _ = token.cancelled() => {
+            info!("Search {} cancelled", request_id);
+            Err(ArxivError::Cancelled.to_string())
+        }
+        result = search => result,
+    };
+    IN_FLIGHT_SEARCHES
+        .lock()
+        .unwrap()
+        .finish(&request_id, generation);
+    result
+}
+
+/// Probe the API with a query asking for zero results, without retries
+async fn check_status(config: &ArxivConfig) -> ArxivStatus {
+    let url = format!(
+        "{}?search_query=all:electron&max_results=0",
+        config.base_url
+    );
+    let headers = match request_headers(config) {
+        Ok(headers) => headers,
+        Err(e) => {
+            return ArxivStatus {
+                reachable: false,
+                latency_ms: 0,
+                message: e.to_string(),
+            }
+        }
+    };
+    RateLimiter::acquire(&RATE_LIMITER).await;
+
+    let started = Instant::now();
+    let result = send_with_retry(
+        &HTTP_CLIENT,
+        &url,
+        &headers,
+        STATUS_CHECK_TIMEOUT,
+        0,
+        RETRY_BASE_DELAY,
+    )
+    .await;
+    let latency_ms = started.elapsed().as_millis() as u64;
+
+    match result {
+        Ok(_) => ArxivStatus {
+            reachable: true,
+            latency_ms,
+            message: "ArXiv is reachable".to_string(),
+        },
+        Err(e) => ArxivStatus {
+            reachable: false,
+            latency_ms,
+            message: e.to_string(),
+        },
+    }
+}
+
+async fn search_papers(
+    config: &ArxivConfig,
+    query: &str,
+    options: ArxivSearchOptions,
+) -> Result<ArxivSearchResponse, String> {
+    info!("Searching papers with query: '{}'", query);
+    debug!("Options: {:?}", options);
+
+    match fetch_arxiv_papers(config, query, &options).await {
+        Ok(mut response) => {
+            info!("Successfully fetched {} papers", response.papers.len());
+            if let Some(client_sort) = options.client_sort {
+                apply_client_sort(
+                    &mut response.papers,
+                    client_sort,
+                    chrono::Utc::now().date_naive(),
+                );
+            }
+            if !options.keep_latex.unwrap_or(false) {
+                response.papers.iter_mut().for_each(simplify_paper_latex);
+            }
+            Ok(response)
+        }
+        Err(e) => {
+            error!("Error fetching papers: {:?}", e);
+            Err(e.to_string())
+        }
+    }
+}
+
+async fn fetch_papers_by_ids(
+    config: &ArxivConfig,
+    arxiv_ids: &[String],
+    keep_latex: bool,
+) -> Result<Vec<ArxivPaper>, String> {
+    if arxiv_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    info!("Fetching papers by IDs: {:?}", arxiv_ids);
+
+    let url = build_id_list_url(config, arxiv_ids);
+    let timeout = Duration::from_secs(config.timeout_secs);
+    let headers = request_headers(config).map_err(|e| e.to_string())?;
+    match fetch_arxiv_url(&url, &headers, timeout, DEFAULT_MAX_RETRIES).await {
+        Ok(mut response) => {
+            if !keep_latex {
+                response.papers.iter_mut().for_each(simplify_paper_latex);
+            }
+            Ok(order_by_requested_ids(response.papers, arxiv_ids))
+        }
+        Err(e) => {
+            error!("Error getting papers by IDs: {:?}", e);
+            Err(e.to_string())
+        }
+    }
+}
+
+/// Resolve the requested field names against `PAPER_FIELDS`
+fn validate_fields(fields: &[String]) -> Result<Vec<&'static str>, ArxivError> {
+    fields
+        .iter()
+        .map(|field| validate_choice("field", field, &PAPER_FIELDS))
+        .collect()
+}
+
+/// Keep only `fields` of `paper` (plus `id` and `rank`), leaving the rest at
+/// their empty defaults
+fn project_paper(mut paper: ArxivPaper, fields: &[&str]) -> ArxivPaper {
+    let mut projected = ArxivPaper {
+        id: paper.id,
+        rank: paper.rank,
+        ..Default::default()
+    };
+    for field in fields {
+        match *field {
+            "title" => projected.title = std::mem::take(&mut paper.title),
+            "authors" => projected.authors = std::mem::take(&mut paper.authors),
+            "authors_detailed" => {
+                projected.authors_detailed = std::mem::take(&mut paper.authors_detailed)
+            }
+            "category" => projected.category = std::mem::take(&mut paper.category),
+            "published_date" => {
+                projected.published_date = std::mem::take(&mut paper.published_date)
+            }
+            "updated_date" => projected.updated_date = std::mem::take(&mut paper.updated_date),
+            "abstract_text" => projected.abstract_text = std::mem::take(&mut paper.abstract_text),
+            "download_url" => projected.download_url = std::mem::take(&mut paper.download_url),
+            "pdf_url" => projected.pdf_url = std::mem::take(&mut paper.pdf_url),
+            "categories" => projected.categories = std::mem::take(&mut paper.categories),
+            "version" => projected.version = paper.version.take(),
+            "doi" => projected.doi = paper.doi.take(),
+            "journal_ref" => projected.journal_ref = paper.journal_ref.take(),
+            "comment" => projected.comment = paper.comment.take(),
+            _ => {}
+        }
+    }
+    projected
+}
+
+/// Search arXiv. `fields` (e.g. `["id", "title", "authors"]`) limits which
+/// paper fields are filled in, to keep large list-view payloads small; the
+/// others come back empty. All fields are returned when it is omitted.
+#[tauri::command]
 pub async fn search_arxiv_papers(
+    config: State<'_, Arc<AsyncMutex<ArxivConfig>>>,
+    query: String,
+    options: Option<ArxivSearchOptions>,
+    fields: Option<Vec<String>>,
+    request_id: Option<String>,
+) -> Result<Vec<ArxivPaper>, String> {
+    let fields = fields
+        .as_deref()
+        .map(validate_fields)
+        .transpose()
+        .map_err(|e| e.to_string())?;
+
+    let config = config.lock().await.clone();
+    let papers = cancellable(
+        request_id,
+        search_papers(&config, &query, options.unwrap_or_default()),
+    )
+    .await?
+    .papers;
+
+    match fields {
+        Some(fields) => Ok(papers
+            .into_iter()
+            .map(|paper| project_paper(paper, &fields))
+            .collect()),
+        None => Ok(papers),
+    }
+}
+
+/// Like `search_arxiv_papers`, but also reports the feed's total result count
+/// and start index so the UI can paginate
+#[tauri::command]
+pub async fn search_arxiv_papers_paged(
+    config: State<'_, Arc<AsyncMutex<ArxivConfig>>>,
+    query: String,
+    options: Option<ArxivSearchOptions>,
+    request_id: Option<String>,
+) -> Result<ArxivSearchResponse, String> {
+    let config = config.lock().await.clone();
+    cancellable(
+        request_id,
+        search_papers(&config, &query, options.unwrap_or_default()),
+    )
+    .await
+}
+
+/// Stream a search: every paper is emitted as an `arxiv://paper` event as
+/// soon as its entry has been parsed, followed by one `arxiv://done` event
+/// with the totals (which is also the command's return value)
+#[tauri::command]
+pub async fn stream_arxiv_papers(
+    app: AppHandle,
+    config: State<'_, Arc<AsyncMutex<ArxivConfig>>>,
     query: String,
     options: Option<ArxivSearchOptions>,
+    request_id: Option<String>,
+) -> Result<ArxivStreamDone, String> {
+    let config = config.lock().await.clone();
+    let options = options.unwrap_or_default();
+    info!("Streaming papers with query: '{}'", query);
+
+    let mut emit_paper = |paper: ArxivPaper| {
+        if let Err(e) = app.emit(ARXIV_PAPER_EVENT, &paper) {
+            warn!("Failed to emit paper {}: {}", paper.id, e);
+        }
+    };
+    let stream = async {
+        stream_arxiv_papers_to(&config, &query, &options, &mut emit_paper)
+            .await
+            .map_err(|e| {
+                error!("Error streaming papers: {:?}", e);
+                e.to_string()
+            })
+    };
+    let done = cancellable(request_id, stream).await?;
+
+    if let Err(e) = app.emit(ARXIV_DONE_EVENT, &done) {
+        warn!("Failed to emit done event: {}", e);
+    }
+    Ok(done)
+}
+
+/// Search with a structured field query instead of free text
+#[tauri::command]
+pub async fn search_arxiv_advanced(
+    config: State<'_, Arc<AsyncMutex<ArxivConfig>>>,
+    query: ArxivQuery,
+    options: Option<ArxivSearchOptions>,
+    request_id: Option<String>,
 ) -> Result<Vec<ArxivPaper>, String> {
-    let search_options = options.unwrap_or_default();
+    let search_query = query.to_search_query();
+    if search_query.is_empty() {
+        return Err("Advanced search needs at least one non-empty field".to_string());
+    }
+
+    info!("Advanced search query: {}", search_query);
+    let config = config.lock().await.clone();
+    cancellable(
+        request_id,
+        search_papers(&config, &search_query, options.unwrap_or_default()),
+    )
+    .await
+    .map(|response| response.papers)
+}
+
+/// Options for browsing a category or an author: newest submissions first
+/// unless another order is requested
+fn browse_options(
+    max_results: Option<u32>,
+    start: Option<u32>,
+    sort_by: Option<&str>,
+    sort_order: Option<&str>,
+) -> Result<ArxivSearchOptions, ArxivError> {
+    let sort_by = validate_choice(
+        "sort_by",
+        sort_by.unwrap_or("submittedDate"),
+        &SORT_BY_VALUES,
+    )?;
+    let sort_order = validate_choice(
+        "sort_order",
+        sort_order.unwrap_or("descending"),
+        &SORT_ORDER_VALUES,
+    )?;
+
+    Ok(ArxivSearchOptions {
+        max_results: Some(max_results.unwrap_or(20)),
+        sort_by: Some(sort_by.to_string()),
+        sort_order: Some(sort_order.to_string()),
+        start,
+        ..Default::default()
+    })
+}
+
+/// ASCII spelling of a name, since arXiv indexes author names without
+/// diacritics (`Schrödinger` is found as `Schrodinger`)
+fn fold_diacritics(text: &str) -> String {
+    let mut folded = String::with_capacity(text.len());
+    for c in text.chars() {
+        let replacement = match c {
+            'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' | 'ą' => "a",
+            'À' | 'Á' | 'Â' | 'Ã' | 'Ä' | 'Å' | 'Ā' | 'Ă' | 'Ą' => "A",
+            'ç' | 'ć' | 'č' => "c",
+            'Ç' | 'Ć' | 'Č' => "C",
+            'ď' | 'đ' => "d",
+            'Ď' | 'Đ' => "D",
+            'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ė' | 'ę' | 'ě' => "e",
+            'È' | 'É' | 'Ê' | 'Ë' | 'Ē' | 'Ė' | 'Ę' | 'Ě' => "E",
+            'ğ' => "g",
+            'Ğ' => "G",
+            'ì' | 'í' | 'î' | 'ï' | 'ī' | 'ı' => "i",
+            'Ì' | 'Í' | 'Î' | 'Ï' | 'Ī' | 'İ' => "I",
+            'ł' | 'ľ' => "l",
+            'Ł' | 'Ľ' => "L",
+            'ñ' | 'ń' | 'ň' => "n",
+            'Ñ' | 'Ń' | 'Ň' => "N",
+            'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ő' => "o",
+            'Ò' | 'Ó' | 'Ô' | 'Õ' | 'Ö' | 'Ø' | 'Ō' | 'Ő' => "O",
+            'ř' => "r",
+            'Ř' => "R",
+            'ś' | 'š' | 'ş' | 'ș' => "s",
+            'Ś' | 'Š' | 'Ş' | 'Ș' => "S",
+            'ť' | 'ţ' | 'ț' => "t",
+            'Ť' | 'Ţ' | 'Ț' => "T",
+            'ù' | 'ú' | 'û' | 'ü' | 'ū' | 'ů' | 'ű' => "u",
+            'Ù' | 'Ú' | 'Û' | 'Ü' | 'Ū' | 'Ů' | 'Ű' => "U",
+            'ý' | 'ÿ' => "y",
+            'Ý' | 'Ÿ' => "Y",
+            'ź' | 'ż' | 'ž' => "z",
+            'Ź' | 'Ż' | 'Ž' => "Z",
+            'ß' => "ss",
+            'æ' => "ae",
+            'Æ' => "AE",
+            'œ' => "oe",
+            'Œ' => "OE",
+            _ => {
+                folded.push(c);
+                continue;
+            }
+        };
+        folded.push_str(replacement);
+    }
+    folded
+}
+
+/// `au:` query for an author given as `First Last` or `Last, First`, in
+/// arXiv's `Last_First` form. Each part is quoted so multi-word and hyphenated
+/// names (`Del Maestro`, `Jean-Pierre`) stay one phrase. arXiv matches the
+/// last name most reliably; first names are often stored as initials, so a
+/// full first name can narrow the results more than expected.
+fn author_query(author: &str) -> Result<String, ArxivError> {
+    let author = fold_diacritics(author).replace(['"', '\\'], "");
+    let (last, first) = match author.split_once(',') {
+        Some((last, first)) => (collapse_whitespace(last), collapse_whitespace(first)),
+        None => {
+            let words: Vec<&str> = author.split_whitespace().collect();
+            match words.split_last() {
+                Some((last, first)) => (last.to_string(), first.join(" ")),
+                None => (String::new(), String::new()),
+            }
+        }
+    };
+
+    if last.is_empty() {
+        return Err(ArxivError::InvalidQuery(
+            "author name must not be empty".to_string(),
+        ));
+    }
+    if first.is_empty() {
+        Ok(format!("au:\"{}\"", last))
+    } else {
+        Ok(format!("au:\"{}_{}\"", last, first))
+    }
+}
+
+/// Recent papers by one author
+#[tauri::command]
+pub async fn search_by_author(
+    config: State<'_, Arc<AsyncMutex<ArxivConfig>>>,
+    author: String,
+    max_results: Option<u32>,
+    sort_by: Option<String>,
+    sort_order: Option<String>,
+    request_id: Option<String>,
+) -> Result<Vec<ArxivPaper>, String> {
+    let query = author_query(&author).map_err(|e| e.to_string())?;
+    let options = browse_options(max_results, None, sort_by.as_deref(), sort_order.as_deref())
+        .map_err(|e| e.to_string())?;
+
+    info!("Author search query: {}", query);
+    let config = config.lock().await.clone();
+    cancellable(request_id, search_papers(&config, &query, options))
+        .await
+        .map(|response| response.papers)
+}
+
+/// Collapse papers sharing a base id (cross-listed papers match several
+/// categories), keeping the first occurrence and merging in the categories
+/// of the later ones
+fn dedup_by_base_id(papers: Vec<ArxivPaper>) -> Vec<ArxivPaper> {
+    let mut positions: HashMap<String, usize> = HashMap::new();
+    let mut unique: Vec<ArxivPaper> = Vec::with_capacity(papers.len());
+    for paper in papers {
+        match positions.get(strip_version(&paper.id)) {
+            Some(&position) => {
+                let kept = &mut unique[position];
+                for category in paper.categories {
+                    if !kept.categories.contains(&category) {
+                        kept.categories.push(category);
+                    }
+                }
+            }
+            None => {
+                positions.insert(strip_version(&paper.id).to_string(), unique.len());
+                unique.push(paper);
+            }
+        }
+    }
+    unique
+}
+
+/// Papers in any of `categories`, or only those cross-listed in all of them
+/// when `match_all` is `true`. Cross-listed papers are merged into one entry
+/// unless `dedupe` is `false`.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn get_papers_by_categories(
+    config: State<'_, Arc<AsyncMutex<ArxivConfig>>>,
+    categories: Vec<String>,
+    max_results: Option<u32>,
+    start: Option<u32>,
+    sort_by: Option<String>,
+    sort_order: Option<String>,
+    dedupe: Option<bool>,
+    match_all: Option<bool>,
+    request_id: Option<String>,
+) -> Result<Vec<ArxivPaper>, String> {
+    let config = config.lock().await.clone();
+    let options = browse_options(
+        max_results,
+        start,
+        sort_by.as_deref(),
+        sort_order.as_deref(),
+    )
+    .map_err(|e| e.to_string())?;
+
+    // Build query for multiple categories; an empty query returns featured papers
+    let query = categories_query(&categories, match_all.unwrap_or(false));
+
+    if !categories.is_empty() {
+        info!("Searching by categories: {:?}", categories);
+        debug!("Generated query: {}", query);
+    }
+
+    let papers = cancellable(request_id, search_papers(&config, &query, options))
+        .await?
+        .papers;
+    if dedupe.unwrap_or(true) {
+        Ok(dedup_by_base_id(papers))
+    } else {
+        Ok(papers)
+    }
+}
+
+/// Query matching papers in any of `categories`, or in all of them when
+/// `match_all` is set. arXiv only recognizes the operators in upper case, and
+/// `+` is the encoded space around them.
+fn categories_query(categories: &[String], match_all: bool) -> String {
+    let operator = if match_all { "+AND+" } else { "+OR+" };
+    categories
+        .iter()
+        .map(|cat| format!("cat:{}", cat))
+        .collect::<Vec<_>>()
+        .join(operator)
+}
+
+/// Papers submitted on `today`, or on the latest earlier day present in
+/// `papers` when there are none from today
+fn submissions_for_day(papers: Vec<ArxivPaper>, today: chrono::NaiveDate) -> NewSubmissions {
+    let today = today.format("%Y-%m-%d").to_string();
+    let date = if papers.iter().any(|paper| paper.published_date == today) {
+        today
+    } else {
+        papers
+            .iter()
+            .map(|paper| paper.published_date.clone())
+            .filter(|date| *date < today)
+            .max()
+            .unwrap_or(today)
+    };
+
+    let papers = papers
+        .into_iter()
+        .filter(|paper| paper.published_date == date)
+        .collect();
+    NewSubmissions { date, papers }
+}
+
+/// Newest submissions in one category, limited to the latest submission day
+/// among the `max_results` most recent papers
+#[tauri::command]
+pub async fn get_new_submissions(
+    config: State<'_, Arc<AsyncMutex<ArxivConfig>>>,
+    category: String,
+    max_results: Option<u32>,
+    request_id: Option<String>,
+) -> Result<NewSubmissions, String> {
+    let category = category.trim();
+    if category.is_empty() {
+        return Err(ArxivError::InvalidQuery("category must not be empty".to_string()).to_string());
+    }
+    let options = browse_options(Some(max_results.unwrap_or(100)), None, None, None)
+        .map_err(|e| e.to_string())?;
+
+    let config = config.lock().await.clone();
+    let query = format!("cat:{}", category);
+    let response = cancellable(request_id, search_papers(&config, &query, options)).await?;
+
+    let submissions = submissions_for_day(response.papers, chrono::Utc::now().date_naive());
+    info!(
+        "{} new submission(s) in {} on {}",
+        submissions.papers.len(),
+        category,
+        submissions.date
+    );
+    Ok(submissions)
+}
+
+/// First and last day of a `YYYY-MM` month
+fn month_bounds(year_month: &str) -> Result<(chrono::NaiveDate, chrono::NaiveDate), ArxivError> {
+    let invalid = || {
+        ArxivError::InvalidQuery(format!(
+            "'{}' is not a valid month (expected YYYY-MM)",
+            year_month
+        ))
+    };
+    let (year, month) = year_month.trim().split_once('-').ok_or_else(invalid)?;
+    let is_number =
+        |part: &str, len: usize| part.len() == len && part.chars().all(|c| c.is_ascii_digit());
+    if !is_number(year, 4) || !is_number(month, 2) {
+        return Err(invalid());
+    }
+    let year: i32 = year.parse().map_err(|_| invalid())?;
+    let month: u32 = month.parse().map_err(|_| invalid())?;
+
+    let first = chrono::NaiveDate::from_ymd_opt(year, month, 1).ok_or_else(invalid)?;
+    let next_month = if month == 12 {
+        chrono::NaiveDate::from_ymd_opt(year + 1, 1, 1)
+    } else {
+        chrono::NaiveDate::from_ymd_opt(year, month + 1, 1)
+    };
+    let last = next_month
+        .and_then(|date| date.pred_opt())
+        .ok_or_else(invalid)?;
+    Ok((first, last))
+}
+
+/// One month of submissions to a category, oldest first, like arxiv.org's
+/// `/list/<category>/<YYMM>` pages. `year_month` is `YYYY-MM`.
+#[tauri::command]
+pub async fn get_monthly_listing(
+    config: State<'_, Arc<AsyncMutex<ArxivConfig>>>,
+    category: String,
+    year_month: String,
+    max_results: Option<u32>,
+    start: Option<u32>,
+    request_id: Option<String>,
+) -> Result<ArxivSearchResponse, String> {
+    let category = category.trim();
+    if category.is_empty() {
+        return Err(ArxivError::InvalidQuery("category must not be empty".to_string()).to_string());
+    }
+    let (first, last) = month_bounds(&year_month).map_err(|e| e.to_string())?;
+    let options = ArxivSearchOptions {
+        from_date: Some(first.format("%Y-%m-%d").to_string()),
+        to_date: Some(last.format("%Y-%m-%d").to_string()),
+        ..browse_options(max_results, start, Some("submittedDate"), Some("ascending"))
+            .map_err(|e| e.to_string())?
+    };
+
+    let config = config.lock().await.clone();
+    let query = format!("cat:{}", category);
+    cancellable(request_id, search_papers(&config, &query, options)).await
+}
+
+/// Run `fetch` for each distinct, non-empty category with at most `limit` in
+/// flight, failing on the first error
+async fn fetch_per_category<F, Fut>(
+    categories: Vec<String>,
+    limit: usize,
+    fetch: F,
+) -> Result<HashMap<String, Vec<ArxivPaper>>, String>
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Result<Vec<ArxivPaper>, String>>,
+{
+    let mut unique = Vec::new();
+    for category in categories {
+        let category = category.trim().to_string();
+        if !category.is_empty() && !unique.contains(&category) {
+            unique.push(category);
+        }
+    }
+
+    stream::iter(unique)
+        .map(|category| {
+            let papers = fetch(category.clone());
+            async move { papers.await.map(|papers| (category, papers)) }
+        })
+        .buffer_unordered(limit)
+        .try_collect()
+        .await
+}
+
+/// Newest papers of each category, fetched with one query per category so
+/// they can be shown as separate sections. Keyed by category code.
+#[tauri::command]
+pub async fn get_papers_grouped_by_category(
+    config: State<'_, Arc<AsyncMutex<ArxivConfig>>>,
+    categories: Vec<String>,
+    per_category: u32,
+    request_id: Option<String>,
+) -> Result<HashMap<String, Vec<ArxivPaper>>, String> {
+    let config = config.lock().await.clone();
+    let options =
+        browse_options(Some(per_category), None, None, None).map_err(|e| e.to_string())?;
+
+    info!("Fetching {} categories separately", categories.len());
+    let fetch = |category: String| {
+        let config = &config;
+        let options = options.clone();
+        async move {
+            let query = format!("cat:{}", category);
+            search_papers(config, &query, options)
+                .await
+                .map(|response| response.papers)
+        }
+    };
+    cancellable(
+        request_id,
+        fetch_per_category(categories, GROUPED_CATEGORY_CONCURRENCY, fetch),
+    )
+    .await
+}
+
+async fn fetch_paper_by_id(
+    config: &ArxivConfig,
+    arxiv_id: &str,
+    keep_latex: bool,
+) -> Result<Option<ArxivPaper>, String> {
+    let query = format!("id:{}", arxiv_id);
+    let options = ArxivSearchOptions {
+        max_results: Some(1),
+        sort_by: Some("relevance".to_string()),
+        sort_order: Some("descending".to_string()),
+        keep_latex: Some(keep_latex),
+        ..Default::default()
+    };
+
+    match search_papers(config, &query, options).await {
+        Ok(response) => Ok(response.papers.into_iter().next()),
+        Err(e) => {
+            error!("Error getting paper by ID: {}", e);
+            Err(e)
+        }
+    }
+}
+
+/// Category query for papers related to `paper`, `None` when it lists no categories
+fn related_papers_query(paper: &ArxivPaper) -> Option<String> {
+    if paper.categories.is_empty() {
+        return None;
+    }
+    Some(
+        paper
+            .categories
+            .iter()
+            .map(|cat| format!("cat:{}", cat))
+            .collect::<Vec<_>>()
+            .join("+OR+"),
+    )
+}
+
+/// Drop the source paper (any version of it) from its own related list
+fn without_paper(papers: Vec<ArxivPaper>, arxiv_id: &str, max_results: usize) -> Vec<ArxivPaper> {
+    let source = strip_version(arxiv_id);
+    papers
+        .into_iter()
+        .filter(|paper| strip_version(&paper.id) != source)
+        .take(max_results)
+        .collect()
+}
+
+#[tauri::command]
+pub async fn get_paper_by_id(
+    config: State<'_, Arc<AsyncMutex<ArxivConfig>>>,
+    arxiv_id: String,
+    keep_latex: Option<bool>,
+) -> Result<Option<ArxivPaper>, String> {
+    let config = config.lock().await.clone();
+    fetch_paper_by_id(&config, &arxiv_id, keep_latex.unwrap_or(false)).await
+}
+
+/// Recent papers sharing a category with `arxiv_id`, excluding the paper itself.
+/// An unknown id yields an empty list rather than an error.
+#[tauri::command]
+pub async fn get_related_papers(
+    config: State<'_, Arc<AsyncMutex<ArxivConfig>>>,
+    arxiv_id: String,
+    max_results: Option<u32>,
+) -> Result<Vec<ArxivPaper>, String> {
+    let config = config.lock().await.clone();
+    let Some(paper) = fetch_paper_by_id(&config, &arxiv_id, false).await? else {
+        warn!("Paper {} not found, no related papers", arxiv_id);
+        return Ok(Vec::new());
+    };
+    let Some(query) = related_papers_query(&paper) else {
+        warn!("Paper {} has no categories, no related papers", arxiv_id);
+        return Ok(Vec::new());
+    };
+
+    // One extra result so the list is still full after dropping the source paper
+    let max_results = max_results.unwrap_or(10);
+    let options = ArxivSearchOptions {
+        max_results: Some(max_results + 1),
+        sort_by: Some("submittedDate".to_string()),
+        sort_order: Some("descending".to_string()),
+        ..Default::default()
+    };
+
+    let response = search_papers(&config, &query, options).await?;
+    Ok(without_paper(
+        response.papers,
+        &paper.id,
+        max_results as usize,
+    ))
+}
+
+/// Fetch several papers in one round trip using arXiv's `id_list` parameter
+#[tauri::command]
+pub async fn get_papers_by_ids(
+    config: State<'_, Arc<AsyncMutex<ArxivConfig>>>,
+    arxiv_ids: Vec<String>,
+    keep_latex: Option<bool>,
+) -> Result<Vec<ArxivPaper>, String> {
+    let config = config.lock().await.clone();
+    fetch_papers_by_ids(&config, &arxiv_ids, keep_latex.unwrap_or(false)).await
+}
+
+/// Check whether the configured arXiv endpoint answers, for a connectivity indicator
+#[tauri::command]
+pub async fn check_arxiv_status(
+    config: State<'_, Arc<AsyncMutex<ArxivConfig>>>,
+) -> Result<ArxivStatus, String> {
+    let config = config.lock().await.clone();
+    let status = check_status(&config).await;
+    info!(
+        "Status check: {} ({} ms)",
+        status.message, status.latency_ms
+    );
+    Ok(status)
+}
+
+/// Id with surrounding whitespace and an `arXiv:` prefix removed
+fn strip_id_prefix(id: &str) -> &str {
+    let id = id.trim();
+    match id.get(..6) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("arxiv:") => id[6..].trim_start(),
+        _ => id,
+    }
+}
+
+/// Normalize `id` the way `is_valid_arxiv_id` does before checking it
+#[tauri::command]
+pub fn normalize_arxiv_id(id: String) -> String {
+    strip_id_prefix(&id).to_string()
+}
+
+/// Whether `id` is a well-formed arXiv identifier, new (`2311.18775v2`) or
+/// old style (`hep-th/9901001`), so typos can be caught without a request.
+/// Well-formed ids may still not exist.
+#[tauri::command]
+pub fn is_valid_arxiv_id(id: String) -> bool {
+    ARXIV_ID_PATTERN.is_match(strip_id_prefix(&id))
+}
+
+/// Page of a paper on arxiv.org; `target` is `abstract`, `pdf` or `html`
+fn paper_page_url(arxiv_id: &str, target: &str) -> Result<String, ArxivError> {
+    let arxiv_id = strip_id_prefix(arxiv_id);
+    if !ARXIV_ID_PATTERN.is_match(arxiv_id) {
+        return Err(ArxivError::InvalidQuery(format!(
+            "'{}' is not a valid arXiv id",
+            arxiv_id
+        )));
+    }
+    let path = match target {
+        "abstract" => "abs",
+        "pdf" => "pdf",
+        "html" => "html",
+        other => {
+            return Err(ArxivError::InvalidQuery(format!(
+                "Unknown target '{}': expected abstract, pdf or html",
+                other
+            )))
+        }
+    };
+    Ok(format!("{}/{}/{}", ARXIV_SITE_BASE, path, arxiv_id))
+}
+
+/// Open a paper's abstract, PDF or HTML page in the default browser
+#[tauri::command]
+pub fn open_arxiv_paper(app: AppHandle, arxiv_id: String, target: String) -> Result<(), String> {
+    let url = paper_page_url(&arxiv_id, &target).map_err(|e| e.to_string())?;
+    info!("Opening {}", url);
+    app.opener()
+        .open_url(url, None::<&str>)
+        .map_err(|e| format!("Failed to open browser: {}", e))
+}
+
+/// Abort the search started with `request_id`. It rejects with
+/// "ArXiv search cancelled". Returns whether such a search was still running.
+#[tauri::command]
+pub fn cancel_arxiv_search(request_id: String) -> bool {
+    IN_FLIGHT_SEARCHES.lock().unwrap().cancel(&request_id)
+}
+
+/// Replace the API endpoint, timeout, result cap and identifying headers used
+/// by all arXiv commands
+#[tauri::command]
+pub async fn set_arxiv_config(
+    state: State<'_, Arc<AsyncMutex<ArxivConfig>>>,
+    config: ArxivConfig,
+) -> Result<(), String> {
+    if !config.base_url.starts_with("http://") && !config.base_url.starts_with("https://") {
+        return Err(format!("Invalid arXiv base URL: {}", config.base_url));
+    }
+    if config.timeout_secs == 0 || config.max_results_cap == 0 {
+        return Err("timeout_secs and max_results_cap must be greater than zero".to_string());
+    }
+    if config.max_results_cap > HARD_MAX_RESULTS {
+        return Err(format!(
+            "max_results_cap cannot exceed {} (arXiv's limit)",
+            HARD_MAX_RESULTS
+        ));
+    }
+    request_headers(&config).map_err(|e| e.to_string())?;
+
+    info!("Using config: {:?}", config);
+    *state.lock().await = config;
+    Ok(())
+}
+
+/// Choose the categories shown when searching with an empty query. Every
+/// entry must be a known category code.
+#[tauri::command]
+pub async fn set_featured_categories(
+    state: State<'_, Arc<AsyncMutex<ArxivConfig>>>,
+    categories: Vec<String>,
+) -> Result<(), String> {
+    let categories: Vec<String> = categories
+        .iter()
+        .map(|category| category.trim().to_string())
+        .collect();
+    if categories.is_empty() {
+        return Err("At least one featured category is required".to_string());
+    }
+    if let Some(unknown) = categories
+        .iter()
+        .find(|category| !get_category_map().contains_key(*category))
+    {
+        return Err(format!("Unknown arXiv category: {}", unknown));
+    }
+
+    info!("Featured categories: {:?}", categories);
+    state.lock().await.featured_categories = categories;
+    Ok(())
+}
+
+/// All known categories as `(code, name)` pairs, sorted by code so that each
+/// top-level archive (cs, math, physics, ...) forms a contiguous group
+#[tauri::command]
+pub fn get_arxiv_categories() -> Vec<(String, String)> {
+    let mut categories: Vec<(String, String)> = get_category_map()
+        .iter()
+        .map(|(code, name)| (code.clone(), name.clone()))
+        .collect();
+    categories.sort();
+    categories
+}
+
+/// Relevance of a category to a lowercase search term, lower is better;
+/// `None` when it doesn't match at all
+fn category_match_rank(code: &str, name: &str, term: &str) -> Option<u8> {
+    let code = code.to_lowercase();
+    let name = name.to_lowercase();
+
+    if code == term || name == term {
+        Some(0)
+    } else if code.starts_with(term) {
+        Some(1)
+    } else if name.starts_with(term) || name.split_whitespace().any(|w| w.starts_with(term)) {
+        Some(2)
+    } else if code.contains(term) || name.contains(term) {
+        Some(3)
+    } else {
+        None
+    }
+}
+
+/// Case-insensitive lookup of categories by code or human-readable name,
+/// best matches first. An empty query matches nothing.
+#[tauri::command]
+pub fn find_categories(query: String) -> Vec<(String, String)> {
+    let term = query.trim().to_lowercase();
+    if term.is_empty() {
+        return Vec::new();
+    }
+
+    let mut matches: Vec<(u8, String, String)> = get_category_map()
+        .iter()
+        .filter_map(|(code, name)| {
+            category_match_rank(code, name, &term).map(|rank| (rank, code.clone(), name.clone()))
+        })
+        .collect();
+    matches.sort();
+
+    matches
+        .into_iter()
+        .map(|(_, code, name)| (code, name))
+        .collect()
+}
+
+/// How long until the next arXiv request may go out, without waiting for
+/// requests already queued on the limiter
+#[tauri::command]
+pub fn arxiv_rate_status() -> RateStatus {
+    RATE_HISTORY.lock().unwrap().status(Instant::now())
+}
+
+/// Change the minimum spacing between arXiv requests (3000 ms by default)
+#[tauri::command]
+pub async fn set_arxiv_min_request_interval(interval_ms: u64) {
+    RATE_LIMITER.lock().await.min_interval = Duration::from_millis(interval_ms);
+    RATE_HISTORY.lock().unwrap().min_interval = Duration::from_millis(interval_ms);
+    info!(
+        "Minimum interval between requests set to {} ms",
+        interval_ms
+    );
+}
+
+/// Drop all cached search results, returning how many were removed
+#[tauri::command]
+pub fn clear_arxiv_cache() -> usize {
+    let removed = SEARCH_CACHE.lock().unwrap().clear() + FEATURED_CACHE.lock().unwrap().clear();
+    info!("Cleared {} cached searches", removed);
+    removed
+}
+
+/// Split the comma-joined `authors` field back into individual names
+fn split_authors(authors: &str) -> Vec<String> {
+    authors
+        .split(',')
+        .map(|name| name.trim())
+        .filter(|name| !name.is_empty() && *name != "Unknown")
+        .map(|name| name.to_string())
+        .collect()
+}
+
+fn paper_to_ris(paper: &ArxivPaper) -> String {
+    // Papers with a journal reference have been published; the rest are preprints
+    let record_type = if paper.journal_ref.is_some() {
+        "JOUR"
+    } else {
+        "GEN"
+    };
+    let mut lines = vec![format!("TY  - {}", record_type)];
+
+    for author in split_authors(&paper.authors) {
+        lines.push(format!("AU  - {}", author));
+    }
+
+    lines.push(format!("TI  - {}", paper.title));
+    if !paper.abstract_text.is_empty() {
+        lines.push(format!("AB  - {}", paper.abstract_text));
+    }
+    if !paper.published_date.is_empty() {
+        // RIS dates use slashes: YYYY/MM/DD
+        lines.push(format!("DA  - {}", paper.published_date.replace('-', "/")));
+    }
+    lines.push(format!("UR  - https://arxiv.org/abs/{}", paper.id));
+    if let Some(doi) = &paper.doi {
+        lines.push(format!("DO  - {}", doi));
+    }
+    if let Some(journal_ref) = &paper.journal_ref {
+        lines.push(format!("JO  - {}", journal_ref));
+    }
+    lines.push("ER  - ".to_string());
+
+    lines.join("\n")
+}
+
+/// Export papers as RIS records for reference managers such as EndNote
+#[tauri::command]
+pub fn arxiv_to_ris(papers: Vec<ArxivPaper>) -> String {
+    papers
+        .iter()
+        .map(paper_to_ris)
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    /// Serve one canned HTTP response per connection, recording each raw request
+    async fn spawn_mock_server(responses: Vec<String>) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let recorded = requests.clone();
+
+        tokio::spawn(async move {
+            for response in responses {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut buf = vec![0u8; 8192];
+                let n = socket.read(&mut buf).await.unwrap();
+                recorded
+                    .lock()
+                    .unwrap()
+                    .push(String::from_utf8_lossy(&buf[..n]).to_string());
+                socket.write_all(response.as_bytes()).await.unwrap();
+                let _ = socket.shutdown().await;
+            }
+        });
+
+        (format!("http://{}", addr), requests)
+    }
+
+    fn http_response(status: &str, extra_headers: &str, body: &str) -> String {
+        format!(
+            "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n{}\r\n{}",
+            status,
+            body.len(),
+            extra_headers,
+            body
+        )
+    }
+
+    #[test]
+    fn test_parse_arxiv_xml_with_sample() {
+        let sample_xml = fs::read_to_string(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/mock/data/sample_arxiv.xml"
+        ))
+        .expect("Failed to read sample_arxiv.xml");
+
+        let result = parse_arxiv_xml(&sample_xml);
+        assert!(result.is_ok(), "XML parsing should succeed");
+
+        let papers = result.unwrap();
+        assert_eq!(papers.len(), 3, "Should parse 3 papers");
+
+        // Test first paper
+        let first_paper = &papers[0];
+        assert_eq!(first_paper.id, "2311.18775");
+        assert_eq!(first_paper.version, Some(2));
+        assert_eq!(first_paper.title, "GPT-4 Vision Technical Report");
+        assert_eq!(first_paper.authors, "OpenAI Team, Research Scientist");
+        assert_eq!(first_paper.category, "Computation and Language");
+        assert_eq!(first_paper.published_date, "2023-11-30");
+        assert_eq!(first_paper.updated_date, "2024-01-10");
+        assert!(first_paper.abstract_text.contains("GPT-4V(ision)"));
+        assert_eq!(first_paper.pdf_url, "http://arxiv.org/pdf/2311.18775v2.pdf");
+        assert_eq!(first_paper.categories.len(), 3);
+        assert!(first_paper.categories.contains(&"cs.CL".to_string()));
+        assert!(first_paper.categories.contains(&"cs.AI".to_string()));
+        assert!(first_paper.categories.contains(&"cs.LG".to_string()));
+        assert_eq!(first_paper.doi.as_deref(), Some("10.1234/example"));
+        assert_eq!(
+            first_paper.journal_ref.as_deref(),
+            Some("J. Example Res. 12 (2024) 1-42")
+        );
+        assert_eq!(first_paper.comment.as_deref(), Some("42 pages, 7 figures"));
+
+        // Test second paper
+        let second_paper = &papers[1];
+        assert_eq!(second_paper.id, "2401.12345");
+        assert_eq!(second_paper.version, Some(1));
+        assert_eq!(
+            second_paper.title,
+            "Deep Learning for Computer Vision: A Comprehensive Survey"
+        );
+        assert_eq!(second_paper.authors, "John Doe, Jane Smith");
+        assert_eq!(
+            second_paper.category,
+            "Computer Vision and Pattern Recognition"
+        );
+        assert_eq!(second_paper.published_date, "2024-01-15");
+        assert_eq!(second_paper.updated_date, "2024-01-15");
+        assert!(second_paper.abstract_text.contains("comprehensive survey"));
+        assert_eq!(
+            second_paper.pdf_url,
+            "http://arxiv.org/pdf/2401.12345v1.pdf"
+        );
+        assert_eq!(second_paper.categories.len(), 2);
+        assert!(second_paper.categories.contains(&"cs.CV".to_string()));
+        assert!(second_paper.categories.contains(&"cs.LG".to_string()));
+        assert_eq!(second_paper.doi, None);
+        assert_eq!(second_paper.journal_ref, None);
+        assert_eq!(second_paper.comment, None);
+
+        // The primary category wins even when another one is listed first
+        let third_paper = &papers[2];
+        assert_eq!(third_paper.id, "2402.54321");
+        assert_eq!(third_paper.categories, vec!["cs.LG", "cs.RO"]);
+        assert_eq!(third_paper.category, "Robotics");
+    }
+
+    #[test]
+    fn test_category_formatting() {
+        assert_eq!(format_category("cs.AI"), "Artificial Intelligence");
+        assert_eq!(format_category("cs.CL"), "Computation and Language");
+        assert_eq!(
+            format_category("cs.CV"),
+            "Computer Vision and Pattern Recognition"
+        );
+        assert_eq!(format_category("cs.LG"), "Machine Learning");
+        assert_eq!(format_category("math.CO"), "Combinatorics");
+        assert_eq!(
+            format_category("physics.data-an"),
+            "Data Analysis, Statistics and Probability"
+        );
+        assert_eq!(format_category("quant-ph"), "Quantum Physics");
+        assert_eq!(format_category("stat.ML"), "Machine Learning");
+        assert_eq!(format_category("q-bio.NC"), "Neurons and Cognition");
+        assert_eq!(
+            format_category("astro-ph.CO"),
+            "Cosmology and Nongalactic Astrophysics"
+        );
+        assert_eq!(format_category("cond-mat.supr-con"), "Superconductivity");
+        assert_eq!(format_category("hep-th"), "High Energy Physics - Theory");
+        assert_eq!(format_category("unknown.category"), "UNKNOWN");
+        assert_eq!(format_category(""), "");
+    }
+
+    #[test]
+    fn test_get_arxiv_categories() {
+        let categories = get_arxiv_categories();
+        assert_eq!(categories.len(), get_category_map().len());
+        assert!(categories.contains(&("cs.AI".to_string(), "Artificial Intelligence".to_string())));
+        assert!(categories.windows(2).all(|pair| pair[0].0 <= pair[1].0));
+    }
+
+    #[test]
+    fn test_find_categories() {
+        let codes = |query: &str| -> Vec<String> {
+            find_categories(query.to_string())
+                .into_iter()
+                .map(|(code, _)| code)
+                .collect()
+        };
+
+        assert!(codes("vision").contains(&"cs.CV".to_string()));
+        assert_eq!(codes("Computer Vision")[0], "cs.CV");
+        assert_eq!(codes("CS.ai")[0], "cs.AI");
+
+        // Code prefix matches rank ahead of substring matches elsewhere in a code
+        let cs_matches = codes("cs.c");
+        let first_other = cs_matches
+            .iter()
+            .position(|code| !code.starts_with("cs."))
+            .unwrap();
+        assert!(first_other > 0);
+        assert!(cs_matches[first_other..]
+            .iter()
+            .all(|code| !code.starts_with("cs.")));
+        assert!(cs_matches.contains(&"physics.class-ph".to_string()));
+
+        assert!(codes("").is_empty());
+        assert!(codes("   ").is_empty());
+        assert!(codes("no such category").is_empty());
+    }
+
+    #[test]
+    fn test_arxiv_to_ris_with_sample() {
+        let sample_xml = fs::read_to_string(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/mock/data/sample_arxiv.xml"
+        ))
+        .expect("Failed to read sample_arxiv.xml");
+        let papers = parse_arxiv_xml(&sample_xml).unwrap();
+
+        let ris = arxiv_to_ris(papers);
+        let records: Vec<&str> = ris.split("\n\n").collect();
+        assert_eq!(records.len(), 3, "Should produce one record per paper");
+
+        let first = records[0];
+        assert!(first.starts_with("TY  - JOUR\n"));
+        assert!(first.contains("AU  - OpenAI Team\nAU  - Research Scientist\n"));
+        assert!(first.contains("TI  - GPT-4 Vision Technical Report\n"));
+        assert!(first.contains("DA  - 2023/11/30\n"));
+        assert!(first.contains("UR  - https://arxiv.org/abs/2311.18775\n"));
+        assert!(first.contains("DO  - 10.1234/example\n"));
+        assert!(first.ends_with("ER  - "));
+
+        let second = records[1];
+        assert!(second.starts_with("TY  - GEN\n"));
+        assert!(second.contains("AU  - John Doe\nAU  - Jane Smith\n"));
+        assert!(!second.contains("DO  - "));
+    }
+
+    #[test]
+    fn test_split_authors() {
+        assert_eq!(
+            split_authors("John Doe, Jane Smith"),
+            vec!["John Doe".to_string(), "Jane Smith".to_string()]
+        );
+        assert!(split_authors("Unknown").is_empty());
+        assert!(split_authors("").is_empty());
+    }
+
+    fn paper_with(id: &str, published_date: &str, categories: &[&str]) -> ArxivPaper {
+        ArxivPaper {
+            id: id.to_string(),
+            published_date: published_date.to_string(),
+            categories: categories.iter().map(|c| c.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_impact_client_sort() {
+        let today = chrono::NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        let mut papers = vec![
+            paper_with("old-broad", "2020-01-15", &["cs.AI", "cs.LG", "cs.CL"]),
+            paper_with("new-narrow", "2024-01-15", &["cs.AI"]),
+            paper_with("new-broad", "2024-01-10", &["cs.AI", "cs.LG"]),
+            paper_with(
+                "bad-date",
+                "not-a-date",
+                &["cs.AI", "cs.LG", "cs.CL", "cs.CV"],
+            ),
+        ];
+
+        apply_client_sort(&mut papers, ClientSort::Impact, today);
+
+        let order: Vec<&str> = papers.iter().map(|p| p.id.as_str()).collect();
+        assert_eq!(
+            order,
+            vec!["new-broad", "new-narrow", "old-broad", "bad-date"]
+        );
+    }
+
+    #[test]
+    fn test_build_search_url_start_offset() {
+        let url = build_search_url(
+            &ArxivConfig::default(),
+            "all:electron",
+            &ArxivSearchOptions::default(),
+        )
+        .unwrap();
+        assert!(url.contains("&start=0&"), "{}", url);
+
+        let options = ArxivSearchOptions {
+            start: Some(40),
+            ..Default::default()
+        };
+        let url = build_search_url(&ArxivConfig::default(), "all:electron", &options).unwrap();
+        assert!(
+            url.contains("search_query=all:electron&start=40&max_results=20"),
+            "{}",
+            url
+        );
+    }
+
+    #[test]
+    fn test_advanced_query_assembly() {
+        let query = ArxivQuery {
+            author: Some("Hinton".to_string()),
+            category: Some("cs.LG".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(query.to_search_query(), "au:Hinton AND cat:cs.LG");
+
+        let query = ArxivQuery {
+            title: Some("  attention is \"all\" you need ".to_string()),
+            abstract_text: Some(String::new()),
+            all: Some("transformer".to_string()),
+            combinator: Some(QueryCombinator::AndNot),
+            ..Default::default()
+        };
+        let search_query = query.to_search_query();
+        assert_eq!(
+            search_query,
+            "ti:\"attention is all you need\" ANDNOT all:transformer"
+        );
+
+        let url = build_search_url(
+            &ArxivConfig::default(),
+            &search_query,
+            &ArxivSearchOptions::default(),
+        )
+        .unwrap();
+        assert!(
+            url.contains("search_query=ti:%22attention+is+all+you+need%22+ANDNOT+all:transformer&")
+        );
+
+        assert!(ArxivQuery::default().to_search_query().is_empty());
+    }
+
+    #[test]
+    fn test_submitted_date_range() {
+        let options = ArxivSearchOptions {
+            from_date: Some("2024-01-01".to_string()),
+            to_date: Some("2024-01-31T12:30:00Z".to_string()),
+            ..Default::default()
+        };
+        let url = build_search_url(&ArxivConfig::default(), "cat:cs.LG", &options).unwrap();
+        assert!(url.contains(
+            "search_query=(cat:cs.LG)+AND+submittedDate:[202401010000+TO+202401311230]&"
+        ));
+
+        let options = ArxivSearchOptions {
+            to_date: Some("2024-02-29".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            submitted_date_clause(&options).unwrap().unwrap(),
+            "submittedDate:[000101010000 TO 202402292359]"
+        );
+
+        assert_eq!(
+            submitted_date_clause(&ArxivSearchOptions::default()).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_submitted_date_rejects_malformed_dates() {
+        let options = ArxivSearchOptions {
+            from_date: Some("01/02/2024".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            build_search_url(&ArxivConfig::default(), "all:electron", &options),
+            Err(ArxivError::InvalidQuery(_))
+        ));
+
+        let options = ArxivSearchOptions {
+            from_date: Some("2024-03-01".to_string()),
+            to_date: Some("2024-02-01".to_string()),
+            ..Default::default()
+        };
+        assert!(submitted_date_clause(&options).is_err());
+    }
+
+    #[test]
+    fn test_sort_options_validation() {
+        let options = ArxivSearchOptions {
+            sort_by: Some("submitteddate".to_string()),
+            sort_order: Some("Ascending".to_string()),
+            ..Default::default()
+        };
+        let url = build_search_url(&ArxivConfig::default(), "all:electron", &options).unwrap();
+        assert!(url.contains("sortBy=submittedDate&sortOrder=ascending"));
+
+        let options = ArxivSearchOptions {
+            sort_by: Some("date".to_string()),
+            ..Default::default()
+        };
+        let error =
+            build_search_url(&ArxivConfig::default(), "all:electron", &options).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Invalid search: unsupported sort_by 'date' (expected one of: relevance, lastUpdatedDate, submittedDate)"
+        );
+
+        let options = ArxivSearchOptions {
+            sort_order: Some("desc".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            build_search_url(&ArxivConfig::default(), "all:electron", &options),
+            Err(ArxivError::InvalidQuery(_))
+        ));
+    }
+
+    #[test]
+    fn test_search_cache_ttl_and_eviction() {
+        let mut cache = SearchCache::default();
+        let response = ArxivSearchResponse {
+            total_results: 7,
+            ..Default::default()
+        };
+
+        cache.insert("a".to_string(), response.clone());
+        assert_eq!(
+            cache
+                .get("a", Duration::from_secs(60))
+                .map(|r| r.total_results),
+            Some(7)
+        );
+        assert!(cache.get("missing", Duration::from_secs(60)).is_none());
+
+        // An expired entry is a miss and is dropped
+        assert!(cache.get("a", Duration::ZERO).is_none());
+        assert!(cache.entries.is_empty());
+
+        cache.insert("key-0".to_string(), response.clone());
+        std::thread::sleep(Duration::from_millis(2));
+        for i in 1..=SEARCH_CACHE_CAPACITY {
+            cache.insert(format!("key-{}", i), response.clone());
+        }
+        assert_eq!(cache.entries.len(), SEARCH_CACHE_CAPACITY);
+        assert!(!cache.entries.contains_key("key-0"));
+
+        assert_eq!(cache.clear(), SEARCH_CACHE_CAPACITY);
+        assert!(cache.entries.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_rate_limiter_serializes_concurrent_callers() {
+        let limiter = Arc::new(AsyncMutex::new(RateLimiter::new(Duration::from_millis(50))));
+        let started = Instant::now();
+
+        let handles: Vec<_> = (0..3)
+            .map(|_| {
+                let limiter = limiter.clone();
+                tokio::spawn(async move { RateLimiter::acquire(&limiter).await })
+            })
+            .collect();
+        for handle in handles {
+            handle.await.unwrap();
+        }
+
+        // First caller goes immediately, the other two wait one interval each
+        assert!(started.elapsed() >= Duration::from_millis(100));
+    }
+
+    #[test]
+    fn test_parse_prefixed_elements() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<atom:feed xmlns:atom="http://www.w3.org/2005/Atom" xmlns:opensearch="http://a9.com/-/spec/opensearch/1.1/" xmlns:arxiv="http://arxiv.org/schemas/atom">
+  <opensearch:totalResults>1</opensearch:totalResults>
+  <atom:entry>
+    <atom:id>http://arxiv.org/abs/2401.12345v1</atom:id>
+    <atom:published>2024-01-20T10:00:00Z</atom:published>
+    <atom:title>Prefixed Title</atom:title>
+    <atom:summary>Prefixed abstract.</atom:summary>
+    <atom:author><atom:name>Ada Lovelace</atom:name></atom:author>
+    <arxiv:doi>10.1234/prefixed</arxiv:doi>
+    <atom:link title="pdf" href="http://arxiv.org/pdf/2401.12345v1" rel="related" type="application/pdf"/>
+    <arxiv:primary_category term="cs.LG" scheme="http://arxiv.org/schemas/atom"/>
+    <atom:category term="cs.LG" scheme="http://arxiv.org/schemas/atom"/>
+    <atom:category term="stat.ML" scheme="http://arxiv.org/schemas/atom"/>
+  </atom:entry>
+</atom:feed>"#;
+
+        let response = parse_arxiv_feed(xml).unwrap();
+        assert_eq!(response.total_results, 1);
+        assert_eq!(response.papers.len(), 1);
+
+        let paper = &response.papers[0];
+        assert_eq!(paper.id, "2401.12345");
+        assert_eq!(paper.title, "Prefixed Title");
+        assert_eq!(paper.abstract_text, "Prefixed abstract.");
+        assert_eq!(paper.authors, "Ada Lovelace");
+        assert_eq!(paper.published_date, "2024-01-20");
+        assert_eq!(paper.doi.as_deref(), Some("10.1234/prefixed"));
+        assert_eq!(paper.pdf_url, "http://arxiv.org/pdf/2401.12345v1");
+        assert_eq!(paper.categories, vec!["cs.LG", "stat.ML"]);
+        assert_eq!(paper.category, format_category("cs.LG"));
+    }
+
+    #[test]
+    fn test_collapse_whitespace() {
+        let xml = "<feed><entry>\
+            <id>http://arxiv.org/abs/2401.12345v1</id>\
+            <title>\n  A   Spaced\n\n   Title </title>\
+            <summary>First line,\n\n\n   second\t\tline   with    gaps.\n</summary>\
+            <author><name>  Grace \n  Hopper </name></author>\
+            </entry></feed>";
+
+        let paper = &parse_arxiv_xml(xml).unwrap()[0];
+        assert_eq!(paper.title, "A Spaced Title");
+        assert_eq!(paper.abstract_text, "First line, second line with gaps.");
+        assert_eq!(paper.authors, "Grace Hopper");
+        assert_eq!(collapse_whitespace(" \t\n "), "");
+    }
+
+    #[test]
+    fn test_parse_feed_totals() {
+        let sample_xml = fs::read_to_string(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/mock/data/sample_arxiv.xml"
+        ))
+        .expect("Failed to read sample_arxiv.xml");
+
+        let response = parse_arxiv_feed(&sample_xml).unwrap();
+        assert_eq!(response.total_results, 1234);
+        assert_eq!(response.start_index, 0);
+        assert_eq!(response.items_per_page, 3);
+        assert_eq!(response.papers.len(), 3);
+    }
+
+    #[test]
+    fn test_arxiv_error_messages() {
+        assert_eq!(ArxivError::from_status(429), ArxivError::RateLimited);
+        assert_eq!(ArxivError::from_status(503), ArxivError::HttpStatus(503));
+        assert!(ArxivError::RateLimited
+            .to_string()
+            .starts_with("Too many requests"));
+        assert!(ArxivError::HttpStatus(503)
+            .to_string()
+            .contains("temporarily unavailable"));
+        assert_eq!(
+            ArxivError::HttpStatus(400).to_string(),
+            "ArXiv API error: Bad Request (400)"
+        );
+        assert!(parse_arxiv_xml("<feed><entry></feed>").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_retry_on_rate_limit_then_success() {
+        let (url, requests) = spawn_mock_server(vec![
+            http_response("429 Too Many Requests", "Retry-After: 0\r\n", ""),
+            http_response("429 Too Many Requests", "Retry-After: 0\r\n", ""),
+            http_response("200 OK", "", "<feed></feed>"),
+        ])
+        .await;
+
+        let client = reqwest::Client::new();
+        let response = send_with_retry(
+            &client,
+            &url,
+            &HeaderMap::new(),
+            Duration::from_secs(5),
+            3,
+            Duration::from_millis(1),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.text().await.unwrap(), "<feed></feed>");
+        assert_eq!(requests.lock().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_no_retry_on_bad_request() {
+        let (url, requests) =
+            spawn_mock_server(vec![http_response("400 Bad Request", "", "")]).await;
+
+        let client = reqwest::Client::new();
+        let error = send_with_retry(
+            &client,
+            &url,
+            &HeaderMap::new(),
+            Duration::from_secs(5),
+            3,
+            Duration::from_secs(60),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(error, ArxivError::HttpStatus(400));
+        assert_eq!(requests.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_retry_gives_up_after_max_retries() {
+        let (url, requests) = spawn_mock_server(vec![
+            http_response("503 Service Unavailable", "", ""),
+            http_response("503 Service Unavailable", "", ""),
+        ])
+        .await;
+
+        let client = reqwest::Client::new();
+        let error = send_with_retry(
+            &client,
+            &url,
+            &HeaderMap::new(),
+            Duration::from_secs(5),
+            1,
+            Duration::from_millis(1),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(error, ArxivError::HttpStatus(503));
+        assert_eq!(requests.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_id_list_url_and_ordering() {
+        let ids = vec!["2401.12345".to_string(), "2311.18775v2".to_string()];
+        assert!(build_id_list_url(&ArxivConfig::default(), &ids)
+            .ends_with("?id_list=2401.12345,2311.18775v2&max_results=2"));
+
+        let papers = vec![
+            paper_with("2311.18775", "2023-11-30", &[]),
+            paper_with("2401.12345", "2024-01-15", &[]),
+        ];
+        let requested = vec![
+            "2401.12345".to_string(),
+            "9999.99999".to_string(),
+            "2311.18775v2".to_string(),
+        ];
+        let ordered = order_by_requested_ids(papers, &requested);
+        let order: Vec<&str> = ordered.iter().map(|p| p.id.as_str()).collect();
+        assert_eq!(order, vec!["2401.12345", "2311.18775"]);
+    }
+
+    #[test]
+    fn test_strip_version() {
+        assert_eq!(strip_version("2311.18775v2"), "2311.18775");
+        assert_eq!(strip_version("2311.18775"), "2311.18775");
+        assert_eq!(strip_version("solv-int/9901001v1"), "solv-int/9901001");
+        assert_eq!(strip_version("solv-int/9901001"), "solv-int/9901001");
+    }
+
+    #[test]
+    fn test_split_version() {
+        assert_eq!(split_version("2311.18775v2"), ("2311.18775", Some(2)));
+        assert_eq!(split_version("2311.18775"), ("2311.18775", None));
+        assert_eq!(
+            split_version("hep-th/9901001v12"),
+            ("hep-th/9901001", Some(12))
+        );
+    }
+
+    #[test]
+    fn test_pdf_fallback_uses_versioned_id() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <entry>
+    <id>http://arxiv.org/abs/2311.18775v2</id>
+    <title>No PDF link</title>
+  </entry>
+</feed>"#;
+
+        let papers = parse_arxiv_xml(xml).unwrap();
+        assert_eq!(papers[0].id, "2311.18775");
+        assert_eq!(papers[0].version, Some(2));
+        assert_eq!(papers[0].pdf_url, "https://arxiv.org/pdf/2311.18775v2.pdf");
+    }
+
+    #[tokio::test]
+    async fn test_get_papers_by_ids_empty_input() {
+        assert!(fetch_papers_by_ids(&ArxivConfig::default(), &[], false)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_papers_by_ids_keep_latex() {
+        let xml = r#"<feed xmlns="http://www.w3.org/2005/Atom">
+  <entry>
+    <id>http://arxiv.org/abs/2311.18775v2</id>
+    <title>A $\mu$-law</title>
+  </entry>
+</feed>"#;
+        let (url, _) = spawn_mock_server(vec![
+            http_response("200 OK", "", xml),
+            http_response("200 OK", "", xml),
+        ])
+        .await;
+        let config = ArxivConfig {
+            base_url: format!("{}/api/query", url),
+            ..Default::default()
+        };
+        let ids = vec!["2311.18775".to_string()];
+
+        let papers = fetch_papers_by_ids(&config, &ids, false).await.unwrap();
+        assert_eq!(papers[0].title, "A μ-law");
+        let papers = fetch_papers_by_ids(&config, &ids, true).await.unwrap();
+        assert_eq!(papers[0].title, "A $\\mu$-law");
+    }
+
+    #[tokio::test]
+    async fn test_search_against_configured_endpoint() {
+        let xml_content = std::fs::read_to_string("mock/data/sample_arxiv.xml").unwrap();
+        let (url, requests) =
+            spawn_mock_server(vec![http_response("200 OK", "", &xml_content)]).await;
+
+        let config = ArxivConfig {
+            base_url: format!("{}/api/query", url),
+            max_results_cap: 5,
+            ..Default::default()
+        };
+        let options = ArxivSearchOptions {
+            max_results: Some(50),
+            cache_ttl_secs: Some(0),
+            ..Default::default()
+        };
+
+        let response = search_papers(&config, "all:electron", options)
+            .await
+            .unwrap();
+        assert_eq!(response.papers.len(), 3);
+        assert_eq!(response.total_results, 1234);
+
+        let requests = requests.lock().unwrap();
+        assert!(requests[0].starts_with("GET /api/query?search_query=all:electron&"));
+        assert!(requests[0].contains("max_results=5&"));
+    }
+
+    #[test]
+    fn test_max_results_clamping() {
+        let config = ArxivConfig::default();
+        assert_eq!(clamp_max_results(&config, 20).unwrap(), 20);
+        assert_eq!(clamp_max_results(&config, 5000).unwrap(), 100);
+
+        let config = ArxivConfig {
+            max_results_cap: 10_000,
+            ..Default::default()
+        };
+        assert_eq!(clamp_max_results(&config, 5000).unwrap(), HARD_MAX_RESULTS);
+
+        assert!(matches!(
+            clamp_max_results(&config, 0),
+            Err(ArxivError::InvalidQuery(_))
+        ));
+    }
+
+    #[test]
+    fn test_page_ranges() {
+        assert_eq!(page_ranges(0, 20, 500), vec![(0, 20)]);
+        assert_eq!(page_ranges(40, 500, 500), vec![(40, 500)]);
+        assert_eq!(page_ranges(0, 501, 500), vec![(0, 500), (500, 1)]);
+        assert_eq!(
+            page_ranges(10, 1200, 500),
+            vec![(10, 500), (510, 500), (1010, 200)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_zero_max_results_rejected_before_request() {
+        let options = ArxivSearchOptions {
+            max_results: Some(0),
+            ..Default::default()
+        };
+        let config = ArxivConfig {
+            base_url: "http://127.0.0.1:1/unreachable".to_string(),
+            ..Default::default()
+        };
+        assert!(matches!(
+            fetch_arxiv_papers(&config, "all:electron", &options).await,
+            Err(ArxivError::InvalidQuery(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_empty_xml() {
+        let empty_xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <title>ArXiv Query: no results</title>
+</feed>"#;
+
+        let result = parse_arxiv_xml(empty_xml);
+        assert!(result.is_ok());
+
+        let papers = result.unwrap();
+        assert_eq!(papers.len(), 0);
+    }
+
+    #[test]
+    fn test_feed_stream_reports_papers_incrementally() {
+        let sample_xml = fs::read_to_string(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/mock/data/sample_arxiv.xml"
+        ))
+        .unwrap();
+        let expected = parse_arxiv_feed(&sample_xml).unwrap();
+
+        let mut streamed = Vec::new();
+        let mut stream = FeedStream::default();
+        let first_entry_end = sample_xml.find("</entry>").unwrap() + "</entry>".len();
+        let (head, tail) = sample_xml.as_bytes().split_at(first_entry_end + 3);
+        for chunk in head.chunks(7) {
+            stream
+                .push(chunk, &mut |paper| streamed.push(paper))
+                .unwrap();
+        }
+        // The first paper is out before the rest of the body has arrived
+        assert_eq!(streamed.len(), 1);
+        assert_eq!(streamed[0].id, expected.papers[0].id);
+
+        for chunk in tail.chunks(7) {
+            stream
+                .push(chunk, &mut |paper| streamed.push(paper))
+                .unwrap();
+        }
+        let totals = stream.finish(&mut |paper| streamed.push(paper)).unwrap();
+
+        assert!(totals.papers.is_empty());
+        assert_eq!(totals.total_results, expected.total_results);
+        let ids: Vec<_> = streamed.iter().map(|paper| paper.id.clone()).collect();
+        let expected_ids: Vec<_> = expected
+            .papers
+            .iter()
+            .map(|paper| paper.id.clone())
+            .collect();
+        assert_eq!(ids, expected_ids);
+        assert_eq!(streamed[1].title, expected.papers[1].title);
+
+        assert!(matches!(
+            FeedStream::default().finish(&mut |_| {}),
+            Err(ArxivError::EmptyResponse)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_cancel_in_flight_search() {
+        let pending = tokio::spawn(cancellable(Some("test-cancel".to_string()), async {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+            Ok(())
+        }));
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        assert!(cancel_arxiv_search("test-cancel".to_string()));
+        assert_eq!(
+            pending.await.unwrap(),
+            Err(ARXIV_CANCELLED_MESSAGE.to_string())
+        );
+        assert!(!cancel_arxiv_search("test-cancel".to_string()));
+
+        // Finished searches are forgotten, so a late cancel is a no-op
+        let result = cancellable(Some("test-done".to_string()), async { Ok(42) }).await;
+        assert_eq!(result, Ok(42));
+        assert!(!cancel_arxiv_search("test-done".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_reused_request_id_supersedes_previous_search() {
+        let first = tokio::spawn(cancellable(Some("test-reuse".to_string()), async {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+            Ok("first")
+        }));
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        let second = cancellable(Some("test-reuse".to_string()), async { Ok("second") }).await;
+        assert_eq!(second, Ok("second"));
+        assert_eq!(
+            first.await.unwrap(),
+            Err(ARXIV_CANCELLED_MESSAGE.to_string())
+        );
+    }
 
-    println!("[ArXiv Rust] Searching papers with query: '{}'", query);
-    println!("[ArXiv Rust] Options: {:?}", search_options);
+    #[tokio::test]
+    async fn test_check_arxiv_status() {
+        let (url, requests) = spawn_mock_server(vec![
+            http_response("200 OK", "", "<feed></feed>"),
+            http_response("429 Too Many Requests", "", ""),
+        ])
+        .await;
+        let config = ArxivConfig {
+            base_url: format!("{}/api/query", url),
+            ..Default::default()
+        };
 
-    match fetch_arxiv_papers(&query, &search_options).await {
-        Ok(papers) => {
-            println!("[ArXiv Rust] Successfully fetched {} papers", papers.len());
-            Ok(papers)
-        }
-        Err(e) => {
-            let error_msg = e.to_string();
-            println!("[ArXiv Rust] Error fetching papers: {}", error_msg);
+        let status = check_status(&config).await;
+        assert!(status.reachable);
+        assert_eq!(status.message, "ArXiv is reachable");
+        assert!(requests.lock().unwrap()[0].contains("max_results=0"));
 
-            // Provide more user-friendly error messages
-            let user_error = if error_msg.contains("timeout") {
-                "Request timed out. Please check your internet connection and try again."
-                    .to_string()
-            } else if error_msg.contains("connect") {
-                "Unable to connect to ArXiv. Please check your internet connection.".to_string()
-            } else if error_msg.contains("DNS") {
-                "DNS resolution failed. Please check your network settings.".to_string()
-            } else {
-                error_msg
-            };
+        let status = check_status(&config).await;
+        assert!(!status.reachable);
+        assert_eq!(status.message, ArxivError::RateLimited.to_string());
 
-            Err(user_error)
-        }
+        // Nothing listens on the mock's port once its responses are used up
+        let status = check_status(&config).await;
+        assert!(!status.reachable);
+        assert_eq!(status.message, ArxivError::Connection.to_string());
     }
-}
 
-#[tauri::command]
-pub async fn get_papers_by_categories(
-    categories: Vec<String>,
-    max_results: Option<u32>,
-) -> Result<Vec<ArxivPaper>, String> {
-    let max_results = max_results.unwrap_or(20);
+    #[test]
+    fn test_related_papers_helpers() {
+        let source = paper_with("2401.12345", "2024-01-15", &["cs.LG", "stat.ML"]);
+        assert_eq!(
+            related_papers_query(&source).as_deref(),
+            Some("cat:cs.LG+OR+cat:stat.ML")
+        );
+        assert_eq!(
+            related_papers_query(&paper_with("2401.00001", "2024-01-01", &[])),
+            None
+        );
 
-    if categories.is_empty() {
-        // Return featured papers if no categories specified
-        return search_arxiv_papers(
-            String::new(),
-            Some(ArxivSearchOptions {
-                max_results: Some(max_results),
-                sort_by: Some("submittedDate".to_string()),
-                sort_order: Some("descending".to_string()),
-            }),
+        let papers = vec![
+            paper_with("2402.00001", "2024-02-01", &["cs.LG"]),
+            paper_with("2401.12345", "2024-01-15", &["cs.LG"]),
+            paper_with("2402.00002", "2024-02-01", &["stat.ML"]),
+            paper_with("2402.00003", "2024-02-01", &["cs.LG"]),
+        ];
+        let related = without_paper(papers.clone(), "2401.12345v2", 2);
+        let ids: Vec<&str> = related.iter().map(|p| p.id.as_str()).collect();
+        assert_eq!(ids, vec!["2402.00001", "2402.00002"]);
+        assert_eq!(without_paper(papers, "2401.12345", 10).len(), 3);
+    }
+
+    #[test]
+    fn test_parse_skips_malformed_entries() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <entry>
+    <id>http://arxiv.org/abs/2401.00001v1</id>
+    <title>Broken <i>title</title>
+  </entry>
+  <entry>
+    <id>http://arxiv.org/abs/2401.00002v1</id>
+    <title>Good &unknown; title</title>
+    <author><name>Ada Lovelace</name></author>
+  </entry>
+</feed>"#;
+
+        let response = parse_arxiv_feed(xml).unwrap();
+        assert_eq!(response.skipped_entries, 1);
+        assert_eq!(response.papers.len(), 1);
+        assert_eq!(response.papers[0].id, "2401.00002");
+        assert_eq!(response.papers[0].title, "Good &unknown; title");
+        assert_eq!(response.papers[0].authors, "Ada Lovelace");
+
+        // A feed cut off inside an entry keeps the entries before it
+        let truncated =
+            r#"<feed><entry><id>http://arxiv.org/abs/2401.00003v1</id></entry><entry><titl"#;
+        let response = parse_arxiv_feed(truncated).unwrap();
+        assert_eq!(response.papers.len(), 1);
+        assert_eq!(response.skipped_entries, 1);
+
+        // Junk between entries is skipped over
+        let junk =
+            r#"<feed></bogus><entry><id>http://arxiv.org/abs/2401.00004v1</id></entry></feed>"#;
+        let response = parse_arxiv_feed(junk).unwrap();
+        assert_eq!(response.papers.len(), 1);
+        assert_eq!(response.skipped_entries, 0);
+
+        // Nothing readable at all is still an error
+        assert!(matches!(
+            parse_arxiv_feed("<feed></bogus>"),
+            Err(ArxivError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn test_clean_text() {
+        assert_eq!(clean_text("&#956;m-scale", true), "μm-scale");
+        assert_eq!(clean_text("&#x3BC; and &#X3bc;", true), "μ and μ");
+        assert_eq!(
+            clean_text("a &lt; b &amp;&amp; c &gt; d", true),
+            "a < b && c > d"
+        );
+        assert_eq!(
+            clean_text("R&D &bogus; &#xZZ; & more", true),
+            "R&D &bogus; &#xZZ; & more"
+        );
+        assert_eq!(
+            clean_text("$O(n\n  \\log n)$ time", true),
+            "$O(n \\log n)$ time"
+        );
+
+        // Without keep_latex, inline math is flattened for plain display
+        assert_eq!(
+            clean_text("A $\\mu$-law for $\\Delta$ with 5\\% error", false),
+            "A μ-law for Δ with 5% error"
+        );
+        assert_eq!(
+            clean_text("$\\frac{a}{b}$ costs \\$5", false),
+            "\\frac{a}{b} costs $5"
+        );
+        assert_eq!(simplify_latex("trailing \\"), "trailing \\");
+    }
+
+    #[test]
+    fn test_browse_options() {
+        let options = browse_options(None, None, None, None).unwrap();
+        assert_eq!(options.max_results, Some(20));
+        assert_eq!(options.sort_by.as_deref(), Some("submittedDate"));
+        assert_eq!(options.sort_order.as_deref(), Some("descending"));
+
+        let options = browse_options(
+            Some(5),
+            Some(10),
+            Some("lastupdateddate"),
+            Some("ascending"),
         )
-        .await;
+        .unwrap();
+        assert_eq!(options.max_results, Some(5));
+        assert_eq!(options.start, Some(10));
+        assert_eq!(options.sort_by.as_deref(), Some("lastUpdatedDate"));
+        assert_eq!(options.sort_order.as_deref(), Some("ascending"));
+
+        assert!(matches!(
+            browse_options(None, None, Some("citations"), None),
+            Err(ArxivError::InvalidQuery(_))
+        ));
+        assert!(matches!(
+            browse_options(None, None, None, Some("up")),
+            Err(ArxivError::InvalidQuery(_))
+        ));
     }
 
-    // Build query for multiple categories
-    let query = categories
-        .iter()
-        .map(|cat| format!("cat:{}", cat))
-        .collect::<Vec<_>>()
-        .join("+OR+");
+    #[test]
+    fn test_author_query() {
+        assert_eq!(author_query("Hinton").unwrap(), r#"au:"Hinton""#);
+        assert_eq!(
+            author_query("Geoffrey Hinton").unwrap(),
+            r#"au:"Hinton_Geoffrey""#
+        );
+        assert_eq!(
+            author_query("Del Maestro, Adrian").unwrap(),
+            r#"au:"Del Maestro_Adrian""#
+        );
+        assert_eq!(
+            author_query("  Jean-Pierre   Serre ").unwrap(),
+            r#"au:"Serre_Jean-Pierre""#
+        );
+        assert_eq!(
+            author_query("Erwin Schrödinger").unwrap(),
+            r#"au:"Schrodinger_Erwin""#
+        );
+        assert_eq!(
+            author_query(r#"Ada "Countess" Lovelace"#).unwrap(),
+            r#"au:"Lovelace_Ada Countess""#
+        );
+        assert!(matches!(
+            author_query("  "),
+            Err(ArxivError::InvalidQuery(_))
+        ));
 
-    println!("[ArXiv Rust] Searching by categories: {:?}", categories);
-    println!("[ArXiv Rust] Generated query: {}", query);
+        // Quotes survive into the URL so arXiv treats the name as one phrase
+        let url = build_search_url(
+            &ArxivConfig::default(),
+            &author_query("Del Maestro, Adrian").unwrap(),
+            &ArxivSearchOptions::default(),
+        )
+        .unwrap();
+        assert!(url.contains("search_query=au:%22Del+Maestro_Adrian%22&"));
+    }
 
-    search_arxiv_papers(
-        query,
-        Some(ArxivSearchOptions {
-            max_results: Some(max_results),
-            sort_by: Some("submittedDate".to_string()),
-            sort_order: Some("descending".to_string()),
-        }),
-    )
-    .await
-}
+    #[test]
+    fn test_submissions_for_day() {
+        let paper = |id: &str, published_date: &str| ArxivPaper {
+            id: id.to_string(),
+            published_date: published_date.to_string(),
+            ..Default::default()
+        };
+        let ids = |submissions: &NewSubmissions| {
+            submissions
+                .papers
+                .iter()
+                .map(|paper| paper.id.clone())
+                .collect::<Vec<_>>()
+        };
+        let monday = chrono::NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
 
-#[tauri::command]
-pub async fn get_paper_by_id(arxiv_id: String) -> Result<Option<ArxivPaper>, String> {
-    let query = format!("id:{}", arxiv_id);
+        let submissions = submissions_for_day(
+            vec![
+                paper("1", "2024-01-15"),
+                paper("2", "2024-01-15"),
+                paper("3", "2024-01-12"),
+            ],
+            monday,
+        );
+        assert_eq!(submissions.date, "2024-01-15");
+        assert_eq!(ids(&submissions), vec!["1", "2"]);
 
-    match search_arxiv_papers(
-        query,
-        Some(ArxivSearchOptions {
-            max_results: Some(1),
-            sort_by: Some("relevance".to_string()),
-            sort_order: Some("descending".to_string()),
-        }),
-    )
-    .await
-    {
-        Ok(mut papers) => {
-            if papers.is_empty() {
-                Ok(None)
-            } else {
-                Ok(Some(papers.remove(0)))
+        // Nothing yet today: fall back to Friday's submissions
+        let submissions = submissions_for_day(
+            vec![
+                paper("3", "2024-01-12"),
+                paper("4", "2024-01-12"),
+                paper("5", "2024-01-11"),
+            ],
+            monday,
+        );
+        assert_eq!(submissions.date, "2024-01-12");
+        assert_eq!(ids(&submissions), vec!["3", "4"]);
+
+        let submissions = submissions_for_day(Vec::new(), monday);
+        assert_eq!(submissions.date, "2024-01-15");
+        assert!(submissions.papers.is_empty());
+    }
+
+    #[test]
+    fn test_dedup_by_base_id() {
+        let entry = |version: u32, categories: &[&str]| {
+            let categories: String = categories
+                .iter()
+                .map(|term| format!(r#"<category term="{}"/>"#, term))
+                .collect();
+            format!(
+                "<entry><id>http://arxiv.org/abs/2401.12345v{}</id><title>Cross-listed</title>{}</entry>",
+                version, categories
+            )
+        };
+        let xml = format!(
+            r#"<feed xmlns="http://www.w3.org/2005/Atom">{}<entry><id>http://arxiv.org/abs/2401.00001v1</id><title>Other</title><category term="cs.CL"/></entry>{}</feed>"#,
+            entry(2, &["cs.LG", "stat.ML"]),
+            entry(1, &["stat.ML", "cs.AI"]),
+        );
+        let papers = parse_arxiv_feed(&xml).unwrap().papers;
+        assert_eq!(papers.len(), 3);
+
+        let papers = dedup_by_base_id(papers);
+        let ids: Vec<&str> = papers.iter().map(|p| p.id.as_str()).collect();
+        assert_eq!(ids, vec!["2401.12345", "2401.00001"]);
+        assert_eq!(papers[0].version, Some(2));
+        assert_eq!(papers[0].categories, vec!["cs.LG", "stat.ML", "cs.AI"]);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_per_category_bounds_concurrency() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let in_flight = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+        let fetch = |category: String| {
+            let (in_flight, peak) = (&in_flight, &peak);
+            async move {
+                let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+                Ok(vec![paper_with(&category, "2024-01-15", &[&category])])
             }
+        };
+
+        let categories = [
+            "cs.LG", "cs.AI", " cs.LG ", "", "math.CO", "stat.ML", "cs.CL",
+        ];
+        let grouped =
+            fetch_per_category(categories.iter().map(|c| c.to_string()).collect(), 2, fetch)
+                .await
+                .unwrap();
+
+        let mut keys: Vec<&str> = grouped.keys().map(String::as_str).collect();
+        keys.sort();
+        assert_eq!(keys, vec!["cs.AI", "cs.CL", "cs.LG", "math.CO", "stat.ML"]);
+        assert_eq!(grouped["math.CO"][0].id, "math.CO");
+        assert!(peak.load(Ordering::SeqCst) <= 2);
+
+        let failing = fetch_per_category(vec!["cs.LG".to_string()], 2, |_| async {
+            Err::<Vec<ArxivPaper>, _>("boom".to_string())
+        })
+        .await;
+        assert_eq!(failing.unwrap_err(), "boom");
+    }
+
+    #[test]
+    fn test_arxiv_id_validation() {
+        for id in [
+            "2311.18775",
+            "2311.18775v2",
+            "0704.0001",
+            "1412.7003v1",
+            "hep-th/9901001",
+            "hep-th/9901001v3",
+            "math.GT/0309136",
+            "cond-mat/0011234",
+            "  arXiv:2311.18775 ",
+            "ARXIV: hep-th/9901001",
+        ] {
+            assert!(is_valid_arxiv_id(id.to_string()), "{}", id);
         }
-        Err(e) => {
-            println!("[ArXiv Rust] Error getting paper by ID: {}", e);
-            Err(e)
+
+        for id in [
+            "",
+            "arXiv:",
+            "2311.187",
+            "2311.187755",
+            "2313.18775",
+            "2311.18775v",
+            "2311.18775v0",
+            "2311-18775",
+            "hep-th/990100",
+            "HEP-TH/9901001",
+            "hep-th/9913001",
+            "https://arxiv.org/abs/2311.18775",
+            "not an id",
+        ] {
+            assert!(!is_valid_arxiv_id(id.to_string()), "{}", id);
         }
+
+        assert_eq!(
+            normalize_arxiv_id(" arXiv:2311.18775v2\n".to_string()),
+            "2311.18775v2"
+        );
+        assert_eq!(
+            normalize_arxiv_id("hep-th/9901001".to_string()),
+            "hep-th/9901001"
+        );
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::fs;
+    #[test]
+    fn test_expand_synonyms() {
+        assert_eq!(
+            expand_synonyms("LLM agents"),
+            r#"(LLM OR "large language model") agents"#
+        );
+        assert_eq!(
+            expand_synonyms("ti:llm+AND+(cat:cs.CL+OR+GNN)"),
+            r#"(ti:llm OR ti:"large language model")+AND+(cat:cs.CL+OR+(GNN OR "graph neural network"))"#
+        );
+        // Quoted phrases, operators and unknown terms stay as they are
+        assert_eq!(
+            expand_synonyms(r#""LLM evaluation" OR ViT-B"#),
+            r#""LLM evaluation" OR ViT-B"#
+        );
+        assert_eq!(
+            expand_synonyms("attention is all you need"),
+            "attention is all you need"
+        );
+
+        let options = ArxivSearchOptions {
+            expand_synonyms: Some(true),
+            ..Default::default()
+        };
+        let url = build_search_url(&ArxivConfig::default(), "LLM reasoning", &options).unwrap();
+        assert!(
+            url.contains("search_query=(LLM+OR+%22large+language+model%22)+reasoning&"),
+            "{}",
+            url
+        );
+        let url = build_search_url(
+            &ArxivConfig::default(),
+            "LLM",
+            &ArxivSearchOptions::default(),
+        )
+        .unwrap();
+        assert!(url.contains("search_query=LLM&"), "{}", url);
+    }
+
+    #[tokio::test]
+    async fn test_requests_identify_client() {
+        let (url, requests) = spawn_mock_server(vec![
+            http_response("200 OK", "", "<feed></feed>"),
+            http_response("200 OK", "", "<feed></feed>"),
+        ])
+        .await;
+        let client = reqwest::Client::new();
+
+        let headers = request_headers(&ArxivConfig::default()).unwrap();
+        send_with_retry(
+            &client,
+            &url,
+            &headers,
+            Duration::from_secs(5),
+            0,
+            Duration::from_millis(1),
+        )
+        .await
+        .unwrap();
+
+        let config = ArxivConfig {
+            user_agent: Some("Redink-test/1.0".to_string()),
+            contact_email: Some("me@example.com".to_string()),
+            ..Default::default()
+        };
+        let headers = request_headers(&config).unwrap();
+        send_with_retry(
+            &client,
+            &url,
+            &headers,
+            Duration::from_secs(5),
+            0,
+            Duration::from_millis(1),
+        )
+        .await
+        .unwrap();
+
+        let requests = requests.lock().unwrap();
+        let default_agent = format!("user-agent: Redink/{}\r\n", env!("CARGO_PKG_VERSION"));
+        assert!(
+            requests[0]
+                .to_lowercase()
+                .contains(&default_agent.to_lowercase()),
+            "{}",
+            requests[0]
+        );
+        assert!(!requests[0].to_lowercase().contains("\r\nfrom:"));
+        assert!(
+            requests[1].contains("user-agent: Redink-test/1.0\r\n"),
+            "{}",
+            requests[1]
+        );
+        assert!(
+            requests[1].contains("from: me@example.com\r\n"),
+            "{}",
+            requests[1]
+        );
+
+        let invalid = ArxivConfig {
+            contact_email: Some("line\nbreak".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            request_headers(&invalid),
+            Err(ArxivError::InvalidQuery(_))
+        ));
+    }
 
     #[test]
-    fn test_parse_arxiv_xml_with_sample() {
-        let sample_xml = fs::read_to_string(concat!(
-            env!("CARGO_MANIFEST_DIR"),
-            "/mock/data/sample_arxiv.xml"
-        ))
-        .expect("Failed to read sample_arxiv.xml");
+    fn test_rate_status() {
+        let start = Instant::now();
+        let interval = Duration::from_secs(3);
+        let mut history = RateHistory::new(interval);
+        assert_eq!(
+            history.status(start),
+            RateStatus {
+                ms_until_next_allowed: 0,
+                last_request_ago_ms: None,
+                requests_in_last_minute: 0,
+            }
+        );
 
-        let result = parse_arxiv_xml(&sample_xml);
-        assert!(result.is_ok(), "XML parsing should succeed");
+        history.record(start, interval);
+        let status = history.status(start + Duration::from_millis(1200));
+        assert_eq!(status.ms_until_next_allowed, 1800);
+        assert_eq!(status.last_request_ago_ms, Some(1200));
+        assert_eq!(status.requests_in_last_minute, 1);
 
-        let papers = result.unwrap();
-        assert_eq!(papers.len(), 2, "Should parse 2 papers");
+        history.record(start + Duration::from_secs(30), interval);
+        let status = history.status(start + Duration::from_secs(45));
+        assert_eq!(status.ms_until_next_allowed, 0);
+        assert_eq!(status.last_request_ago_ms, Some(15_000));
+        assert_eq!(status.requests_in_last_minute, 2);
 
-        // Test first paper
-        let first_paper = &papers[0];
-        assert_eq!(first_paper.id, "2311.18775");
-        assert_eq!(first_paper.title, "GPT-4 Vision Technical Report");
-        assert_eq!(first_paper.authors, "OpenAI Team, Research Scientist");
-        assert_eq!(first_paper.category, "Computation and Language");
-        assert_eq!(first_paper.published_date, "2023-11-30");
-        assert!(first_paper.abstract_text.contains("GPT-4V(ision)"));
-        assert_eq!(first_paper.pdf_url, "http://arxiv.org/pdf/2311.18775v2.pdf");
-        assert_eq!(first_paper.categories.len(), 3);
-        assert!(first_paper.categories.contains(&"cs.CL".to_string()));
-        assert!(first_paper.categories.contains(&"cs.AI".to_string()));
-        assert!(first_paper.categories.contains(&"cs.LG".to_string()));
+        // The first request falls out of the window
+        let status = history.status(start + Duration::from_secs(61));
+        assert_eq!(status.requests_in_last_minute, 1);
+        history.record(start + Duration::from_secs(62), interval);
+        assert_eq!(history.recent.len(), 2);
+        history.record(start + Duration::from_secs(95), interval);
+        assert_eq!(history.recent.len(), 2);
+    }
 
-        // Test second paper
-        let second_paper = &papers[1];
-        assert_eq!(second_paper.id, "2401.12345");
+    #[test]
+    fn test_paper_page_url() {
         assert_eq!(
-            second_paper.title,
-            "Deep Learning for Computer Vision: A Comprehensive Survey"
+            paper_page_url("2311.18775v2", "abstract").unwrap(),
+            "https://arxiv.org/abs/2311.18775v2"
         );
-        assert_eq!(second_paper.authors, "John Doe, Jane Smith");
         assert_eq!(
-            second_paper.category,
-            "Computer Vision and Pattern Recognition"
+            paper_page_url(" arXiv:2311.18775", "pdf").unwrap(),
+            "https://arxiv.org/pdf/2311.18775"
         );
-        assert_eq!(second_paper.published_date, "2024-01-15");
-        assert!(second_paper.abstract_text.contains("comprehensive survey"));
         assert_eq!(
-            second_paper.pdf_url,
-            "http://arxiv.org/pdf/2401.12345v1.pdf"
+            paper_page_url("hep-th/9901001", "html").unwrap(),
+            "https://arxiv.org/html/hep-th/9901001"
+        );
+        assert!(matches!(
+            paper_page_url("2311.18775", "latex"),
+            Err(ArxivError::InvalidQuery(_))
+        ));
+        assert!(matches!(
+            paper_page_url("../../etc", "abstract"),
+            Err(ArxivError::InvalidQuery(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_ranks_across_pages() {
+        let entry = |id: &str| {
+            format!(
+                "<entry><id>http://arxiv.org/abs/{}v1</id><title>Paper {}</title></entry>",
+                id, id
+            )
+        };
+        let page = |ids: &[&str]| {
+            let entries: String = ids.iter().map(|id| entry(id)).collect();
+            http_response(
+                "200 OK",
+                "",
+                &format!(
+                    r#"<feed xmlns="http://www.w3.org/2005/Atom">{}</feed>"#,
+                    entries
+                ),
+            )
+        };
+        let (url, requests) = spawn_mock_server(vec![
+            page(&["2401.00001", "2401.00002"]),
+            page(&["2401.00003", "2401.00004"]),
+        ])
+        .await;
+        let config = ArxivConfig {
+            base_url: url,
+            ..Default::default()
+        };
+
+        let mut ranks = Vec::new();
+        for start in [0, 2] {
+            let options = ArxivSearchOptions {
+                start: Some(start),
+                max_results: Some(2),
+                cache_ttl_secs: Some(0),
+                ..Default::default()
+            };
+            let response = fetch_search_page(&config, "all:electron", &options)
+                .await
+                .unwrap();
+            ranks.extend(
+                response
+                    .papers
+                    .iter()
+                    .map(|paper| (paper.id.clone(), paper.rank)),
+            );
+        }
+
+        assert_eq!(requests.lock().unwrap().len(), 2);
+        assert_eq!(
+            ranks,
+            vec![
+                ("2401.00001".to_string(), 0),
+                ("2401.00002".to_string(), 1),
+                ("2401.00003".to_string(), 2),
+                ("2401.00004".to_string(), 3),
+            ]
         );
-        assert_eq!(second_paper.categories.len(), 2);
-        assert!(second_paper.categories.contains(&"cs.CV".to_string()));
-        assert!(second_paper.categories.contains(&"cs.LG".to_string()));
     }
 
     #[test]
-    fn test_category_formatting() {
-        assert_eq!(format_category("cs.AI"), "Artificial Intelligence");
-        assert_eq!(format_category("cs.CL"), "Computation and Language");
+    fn test_parse_author_affiliations() {
+        let xml = r#"<feed xmlns="http://www.w3.org/2005/Atom" xmlns:arxiv="http://arxiv.org/schemas/atom">
+  <entry>
+    <id>http://arxiv.org/abs/2401.12345v1</id>
+    <title>Affiliated</title>
+    <author>
+      <name>Ada Lovelace</name>
+      <arxiv:affiliation>University of London</arxiv:affiliation>
+    </author>
+    <author><name>Charles Babbage</name></author>
+    <author>
+      <name>Grace Hopper</name>
+      <arxiv:affiliation>Yale University</arxiv:affiliation>
+      <arxiv:affiliation>US Navy</arxiv:affiliation>
+    </author>
+  </entry>
+</feed>"#;
+
+        let paper = &parse_arxiv_xml(xml).unwrap()[0];
+        assert_eq!(paper.authors, "Ada Lovelace, Charles Babbage, Grace Hopper");
         assert_eq!(
-            format_category("cs.CV"),
-            "Computer Vision and Pattern Recognition"
+            paper.authors_detailed,
+            vec![
+                Author {
+                    name: "Ada Lovelace".to_string(),
+                    affiliation: Some("University of London".to_string()),
+                },
+                Author {
+                    name: "Charles Babbage".to_string(),
+                    affiliation: None,
+                },
+                Author {
+                    name: "Grace Hopper".to_string(),
+                    affiliation: Some("Yale University; US Navy".to_string()),
+                },
+            ]
         );
-        assert_eq!(format_category("cs.LG"), "Machine Learning");
-        assert_eq!(format_category("math.CO"), "Combinatorics");
+    }
+
+    #[test]
+    fn test_empty_query_uses_featured_categories() {
+        let default_url = build_search_url(
+            &ArxivConfig::default(),
+            "  ",
+            &ArxivSearchOptions::default(),
+        )
+        .unwrap();
+        assert!(
+            default_url.contains("search_query=cat:cs.AI+OR+cat:cs.LG+OR+cat:cs.CL+OR+cat:cs.CV&")
+        );
+
+        let config = ArxivConfig {
+            featured_categories: vec!["math.AG".to_string(), "hep-th".to_string()],
+            ..Default::default()
+        };
+        let url = build_search_url(&config, "", &ArxivSearchOptions::default()).unwrap();
+        assert!(url.contains("search_query=cat:math.AG+OR+cat:hep-th&"));
+
+        // A config saved before featured categories existed gets the defaults
+        let config: ArxivConfig = serde_json::from_str(
+            r#"{"base_url": "https://example.org/api/query", "timeout_secs": 10, "max_results_cap": 50}"#,
+        )
+        .unwrap();
+        assert_eq!(config.featured_categories, default_featured_categories());
+    }
+
+    #[test]
+    fn test_month_bounds() {
+        let bounds = |year_month: &str| {
+            month_bounds(year_month).map(|(first, last)| {
+                (
+                    first.format("%Y-%m-%d").to_string(),
+                    last.format("%Y-%m-%d").to_string(),
+                )
+            })
+        };
+        let expected = |first: &str, last: &str| Ok((first.to_string(), last.to_string()));
+
+        assert_eq!(bounds("2024-01"), expected("2024-01-01", "2024-01-31"));
+        assert_eq!(bounds("2024-04"), expected("2024-04-01", "2024-04-30"));
+        assert_eq!(bounds("2024-12"), expected("2024-12-01", "2024-12-31"));
+        // Leap years, including the century rules
+        assert_eq!(bounds("2024-02"), expected("2024-02-01", "2024-02-29"));
+        assert_eq!(bounds("2023-02"), expected("2023-02-01", "2023-02-28"));
+        assert_eq!(bounds("2000-02"), expected("2000-02-01", "2000-02-29"));
+        assert_eq!(bounds("1900-02"), expected("1900-02-01", "1900-02-28"));
+        assert_eq!(bounds(" 2024-02 "), expected("2024-02-01", "2024-02-29"));
+
+        for invalid in [
+            "2024-13", "2024-00", "2024-1", "24-01", "2024/01", "+202-01", "",
+        ] {
+            assert!(
+                matches!(month_bounds(invalid), Err(ArxivError::InvalidQuery(_))),
+                "{}",
+                invalid
+            );
+        }
+
+        let options = ArxivSearchOptions {
+            from_date: Some("2024-02-01".to_string()),
+            to_date: Some("2024-02-29".to_string()),
+            ..browse_options(None, Some(40), Some("submittedDate"), Some("ascending")).unwrap()
+        };
+        let url = build_search_url(&ArxivConfig::default(), "cat:cs.AI", &options).unwrap();
+        assert!(url.contains(
+            "search_query=(cat:cs.AI)+AND+submittedDate:[202402010000+TO+202402292359]&start=40&"
+        ));
+        assert!(url.ends_with("sortBy=submittedDate&sortOrder=ascending"));
+    }
+
+    #[test]
+    fn test_categories_query_match_all() {
+        let categories = vec!["cs.LG".to_string(), "stat.ML".to_string()];
         assert_eq!(
-            format_category("physics.data-an"),
-            "Data Analysis, Statistics and Probability"
+            categories_query(&categories, false),
+            "cat:cs.LG+OR+cat:stat.ML"
         );
-        assert_eq!(format_category("quant-ph"), "Quantum Physics");
-        assert_eq!(format_category("stat.ML"), "Machine Learning");
-        assert_eq!(format_category("q-bio.NC"), "Neurons and Cognition");
         assert_eq!(
-            format_category("astro-ph.CO"),
-            "Cosmology and Nongalactic Astrophysics"
+            categories_query(&categories, true),
+            "cat:cs.LG+AND+cat:stat.ML"
         );
-        assert_eq!(format_category("cond-mat.supr-con"), "Superconductivity");
-        assert_eq!(format_category("hep-th"), "High Energy Physics - Theory");
-        assert_eq!(format_category("unknown.category"), "UNKNOWN");
-        assert_eq!(format_category(""), "");
+        assert_eq!(categories_query(&categories[..1], true), "cat:cs.LG");
+
+        // The operator survives encoding and date filters wrap the whole expression
+        let options = ArxivSearchOptions {
+            from_date: Some("2024-01-01".to_string()),
+            ..Default::default()
+        };
+        let url = build_search_url(
+            &ArxivConfig::default(),
+            &categories_query(&categories, true),
+            &options,
+        )
+        .unwrap();
+        assert!(url.contains("search_query=(cat:cs.LG+AND+cat:stat.ML)+AND+submittedDate:"));
     }
 
     #[test]
-    fn test_parse_empty_xml() {
-        let empty_xml = r#"<?xml version="1.0" encoding="UTF-8"?>
-<feed xmlns="http://www.w3.org/2005/Atom">
-  <title>ArXiv Query: no results</title>
-</feed>"#;
+    fn test_project_paper() {
+        let paper = ArxivPaper {
+            id: "2401.12345v2".to_string(),
+            title: "Attention".to_string(),
+            authors: "A. Author".to_string(),
+            abstract_text: "A long abstract".to_string(),
+            categories: vec!["cs.LG".to_string()],
+            version: Some(2),
+            doi: Some("10.1000/example".to_string()),
+            rank: 7,
+            ..Default::default()
+        };
 
-        let result = parse_arxiv_xml(empty_xml);
-        assert!(result.is_ok());
+        let fields = validate_fields(&["Title".to_string(), "authors".to_string()]).unwrap();
+        let projected = project_paper(paper.clone(), &fields);
+        assert_eq!(projected.id, "2401.12345v2");
+        assert_eq!(projected.rank, 7);
+        assert_eq!(projected.title, "Attention");
+        assert_eq!(projected.authors, "A. Author");
+        assert!(projected.abstract_text.is_empty());
+        assert!(projected.categories.is_empty());
+        assert_eq!(projected.version, None);
+        assert_eq!(projected.doi, None);
 
-        let papers = result.unwrap();
-        assert_eq!(papers.len(), 0);
+        // Every field requested gives the paper back unchanged
+        let all = validate_fields(&PAPER_FIELDS.map(String::from)).unwrap();
+        let projected = project_paper(paper.clone(), &all);
+        assert_eq!(
+            serde_json::to_value(&projected).unwrap(),
+            serde_json::to_value(&paper).unwrap()
+        );
+
+        assert!(matches!(
+            validate_fields(&["abstract".to_string()]),
+            Err(ArxivError::InvalidQuery(_))
+        ));
     }
 }
@@ -1,8 +1,14 @@
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
+mod app_info;
 mod arxiv;
+mod logging;
+mod tray;
 mod vector_store;
+mod window_state;
 
+use arxiv::ArxivConfig;
 use std::sync::Arc;
+use tauri::{Manager, WindowEvent};
 use tokio::sync::Mutex;
 use vector_store::VectorStoreState;
 
@@ -14,38 +20,31 @@ fn greet(name: &str) -> String {
 // Only include devtools functionality in debug builds
 #[cfg(debug_assertions)]
 #[tauri::command]
-async fn toggle_devtools(_window: tauri::Window) -> Result<(), String> {
-    // In debug builds, try to open devtools
-    // Note: devtools API may not be available in all Tauri versions
-    // For now, we'll use a workaround approach
-    println!("[Debug] DevTools toggle requested in debug build");
-
-    // Try to use the window's native devtools functionality
-    // This is a placeholder implementation - the exact API may vary
-    match std::env::var("TAURI_ENV") {
-        Ok(env) if env == "dev" => {
-            println!("[Debug] Development environment detected, DevTools access allowed");
-            // TODO: Implement actual devtools toggle when API is stable
-            Ok(())
-        }
-        _ => {
-            println!("[Debug] DevTools toggle not yet fully implemented");
-            Err("DevTools functionality is not yet fully implemented".to_string())
-        }
+async fn toggle_devtools(window: tauri::WebviewWindow) -> Result<bool, String> {
+    // Returns whether devtools are open after the toggle so the UI can update
+    if window.is_devtools_open() {
+        window.close_devtools();
+        tracing::info!("DevTools closed");
+        Ok(false)
+    } else {
+        window.open_devtools();
+        tracing::info!("DevTools opened");
+        Ok(true)
     }
 }
 
 // Stub implementation for release builds
 #[cfg(not(debug_assertions))]
 #[tauri::command]
-async fn toggle_devtools(_window: tauri::Window) -> Result<(), String> {
+async fn toggle_devtools(_window: tauri::WebviewWindow) -> Result<bool, String> {
     // In production builds, deny access to devtools for security
-    println!("[Security] DevTools access denied in production build");
+    tracing::warn!("DevTools access denied in production build");
     Err("DevTools access is disabled in production builds for security reasons".to_string())
 }
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     let vector_store_state = Arc::new(Mutex::new(VectorStoreState::new()));
+    let arxiv_config = Arc::new(Mutex::new(ArxivConfig::default()));
 
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
@@ -56,19 +55,88 @@ pub fn run() {
         .plugin(tauri_plugin_process::init())
         .plugin(tauri_plugin_shell::init())
         .manage(vector_store_state)
+        .manage(arxiv_config)
+        .manage(tray::TrayState::default())
+        .setup(|app| {
+            logging::init(app);
+            if let Some(window) = app.get_webview_window("main") {
+                window_state::restore(&window);
+            }
+            tray::init(app)?;
+            Ok(())
+        })
+        .on_window_event(|window, event| {
+            if let WindowEvent::CloseRequested { api, .. } = event {
+                window_state::save(window);
+                tray::hide_on_close(window, api);
+            }
+        })
         .invoke_handler(tauri::generate_handler![
             greet,
             toggle_devtools,
+            app_info::get_app_info,
+            logging::set_log_level,
+            window_state::reset_window_state,
+            tray::set_close_to_tray,
             vector_store::vector_store_initialize,
+            vector_store::vector_store_warmup,
+            vector_store::vector_store_cancel_warmup,
+            vector_store::vector_store_set_concurrency_limit,
             vector_store::vector_store_add_chunks,
+            vector_store::vector_store_upsert_chunks,
+            vector_store::vector_store_reindex,
+            vector_store::vector_store_get_expected_dimension,
             vector_store::vector_store_search,
+            vector_store::vector_store_search_arrow,
+            vector_store::vector_store_hybrid_search,
+            vector_store::vector_store_create_index,
+            vector_store::vector_store_get_chunks,
+            vector_store::vector_store_preview,
+            vector_store::vector_store_similar_documents,
             vector_store::vector_store_has_document,
             vector_store::vector_store_delete_document,
+            vector_store::vector_store_delete_documents,
+            vector_store::vector_store_rename_document,
             vector_store::vector_store_clear_all,
             vector_store::vector_store_get_count,
+            vector_store::vector_store_set_metadata,
+            vector_store::vector_store_get_metadata,
+            vector_store::vector_store_list_documents,
+            vector_store::vector_store_stats,
+            vector_store::vector_store_total_stats,
+            vector_store::vector_store_check_compatibility,
+            vector_store::vector_store_migrate_table_names,
+            vector_store::vector_store_optimize,
+            vector_store::vector_store_optimize_all,
+            vector_store::vector_store_export_document,
+            vector_store::vector_store_import_document,
+            vector_store::vector_store_export_all,
+            vector_store::vector_store_import_all,
             arxiv::search_arxiv_papers,
+            arxiv::search_arxiv_papers_paged,
+            arxiv::stream_arxiv_papers,
+            arxiv::search_arxiv_advanced,
+            arxiv::search_by_author,
             arxiv::get_papers_by_categories,
+            arxiv::get_papers_grouped_by_category,
+            arxiv::get_new_submissions,
+            arxiv::get_monthly_listing,
+            arxiv::normalize_arxiv_id,
+            arxiv::is_valid_arxiv_id,
+            arxiv::open_arxiv_paper,
             arxiv::get_paper_by_id,
+            arxiv::get_related_papers,
+            arxiv::get_papers_by_ids,
+            arxiv::get_arxiv_categories,
+            arxiv::find_categories,
+            arxiv::arxiv_to_ris,
+            arxiv::clear_arxiv_cache,
+            arxiv::cancel_arxiv_search,
+            arxiv::check_arxiv_status,
+            arxiv::set_arxiv_min_request_interval,
+            arxiv::arxiv_rate_status,
+            arxiv::set_arxiv_config,
+            arxiv::set_featured_categories,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,212 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{App, Manager, State};
+use tracing::{info, warn};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::reload::{self, Handle};
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, EnvFilter, Registry};
+
+/// Filter used when `RUST_LOG` is not set
+const DEFAULT_LOG_FILTER: &str = "info";
+/// Directory under the app data dir holding the log files of release builds
+const LOG_DIR: &str = "logs";
+const LOG_FILE: &str = "redink.log";
+/// Size at which the log file is rotated
+const MAX_LOG_FILE_BYTES: u64 = 5 * 1024 * 1024;
+/// Rotated files kept next to the current one, `redink.log.1` being the newest
+const LOG_FILE_BACKUPS: usize = 3;
+
+/// Handle for changing the log filter while the app is running
+pub struct LogState {
+    filter: Handle<EnvFilter, Registry>,
+}
+
+/// Log file that starts over once it would grow past `max_bytes`, shifting
+/// its previous contents to numbered backups
+struct RotatingFile {
+    path: PathBuf,
+    max_bytes: u64,
+    backups: usize,
+    file: File,
+    written: u64,
+}
+
+impl RotatingFile {
+    fn open(path: PathBuf, max_bytes: u64, backups: usize) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let written = file.metadata()?.len();
+        Ok(Self {
+            path,
+            max_bytes,
+            backups,
+            file,
+            written,
+        })
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        if self.backups > 0 {
+            for index in (1..self.backups).rev() {
+                let from = backup_path(&self.path, index);
+                if from.exists() {
+                    fs::rename(&from, backup_path(&self.path, index + 1))?;
+                }
+            }
+            fs::rename(&self.path, backup_path(&self.path, 1))?;
+        }
+        self.file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&self.path)?;
+        self.written = 0;
+        Ok(())
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.written > 0 && self.written + buf.len() as u64 > self.max_bytes {
+            self.rotate()?;
+        }
+        let written = self.file.write(buf)?;
+        self.written += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+/// `redink.log` -> `redink.log.<index>`
+fn backup_path(path: &Path, index: usize) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".{}", index));
+    PathBuf::from(name)
+}
+
+fn open_log_file(app: &App) -> Result<RotatingFile, String> {
+    let log_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join(LOG_DIR);
+    fs::create_dir_all(&log_dir).map_err(|e| e.to_string())?;
+    RotatingFile::open(log_dir.join(LOG_FILE), MAX_LOG_FILE_BYTES, LOG_FILE_BACKUPS)
+        .map_err(|e| e.to_string())
+}
+
+/// Filter from a `RUST_LOG`-style directive such as `debug` or
+/// `info,redink_lib::arxiv=debug`
+fn parse_filter(directives: &str) -> Result<EnvFilter, String> {
+    EnvFilter::try_new(directives.trim())
+        .map_err(|e| format!("Invalid log level '{}': {}", directives, e))
+}
+
+/// Install the global subscriber: stdout in debug builds, a rotating file in
+/// the app data dir in release builds. The level comes from `RUST_LOG` and can
+/// be changed later with `set_log_level`.
+pub fn init(app: &App) {
+    let filter =
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(DEFAULT_LOG_FILTER));
+    let (filter, handle) = reload::Layer::new(filter);
+
+    let log_file = if cfg!(debug_assertions) {
+        None
+    } else {
+        match open_log_file(app) {
+            Ok(file) => Some(file),
+            Err(e) => {
+                eprintln!("Failed to open log file, logging to stdout: {}", e);
+                None
+            }
+        }
+    };
+    let stdout_layer = if log_file.is_none() {
+        Some(fmt::layer())
+    } else {
+        None
+    };
+    let file_layer =
+        log_file.map(|file| fmt::layer().with_ansi(false).with_writer(Mutex::new(file)));
+
+    let result = tracing_subscriber::registry()
+        .with(filter)
+        .with(stdout_layer)
+        .with(file_layer)
+        .try_init();
+    if let Err(e) = result {
+        warn!("Logging was already initialized: {}", e);
+    }
+
+    app.manage(LogState { filter: handle });
+}
+
+/// Change which log messages are recorded, e.g. `debug` for everything or
+/// `warn,redink_lib::vector_store=debug` for one subsystem
+#[tauri::command]
+pub fn set_log_level(state: State<'_, LogState>, level: String) -> Result<(), String> {
+    let filter = parse_filter(&level)?;
+    state
+        .filter
+        .reload(filter)
+        .map_err(|e| format!("Failed to change log level: {}", e))?;
+    info!("Log level set to {}", level.trim());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotating_file() {
+        let dir = std::env::temp_dir().join(format!("redink_logging_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(LOG_FILE);
+
+        let mut file = RotatingFile::open(path.clone(), 10, 2).unwrap();
+        for line in ["first\n", "second\n", "third\n", "fourth\n"] {
+            file.write_all(line.as_bytes()).unwrap();
+        }
+        file.flush().unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "fourth\n");
+        assert_eq!(
+            fs::read_to_string(backup_path(&path, 1)).unwrap(),
+            "third\n"
+        );
+        assert_eq!(
+            fs::read_to_string(backup_path(&path, 2)).unwrap(),
+            "second\n"
+        );
+        assert!(!backup_path(&path, 3).exists());
+
+        // Reopening counts the existing contents towards the limit
+        let mut file = RotatingFile::open(path.clone(), 10, 2).unwrap();
+        file.write_all(b"fifth\n").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "fifth\n");
+        assert_eq!(
+            fs::read_to_string(backup_path(&path, 1)).unwrap(),
+            "fourth\n"
+        );
+        assert_eq!(
+            fs::read_to_string(backup_path(&path, 2)).unwrap(),
+            "third\n"
+        );
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_parse_filter() {
+        assert!(parse_filter("debug").is_ok());
+        assert!(parse_filter(" warn,redink_lib::arxiv=debug ").is_ok());
+        assert!(parse_filter("redink_lib::arxiv=loud").is_err());
+    }
+}
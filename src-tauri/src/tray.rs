@@ -0,0 +1,97 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem};
+use tauri::tray::TrayIconBuilder;
+use tauri::{App, AppHandle, CloseRequestApi, Emitter, Manager, State, Window};
+use tracing::warn;
+
+/// Emitted to the main window when "New search" is picked from the tray menu
+const TRAY_NEW_SEARCH_EVENT: &str = "tray://new-search";
+
+const MENU_TOGGLE: &str = "toggle";
+const MENU_NEW_SEARCH: &str = "new-search";
+const MENU_QUIT: &str = "quit";
+
+/// Whether closing the main window hides it to the tray instead of quitting
+pub struct TrayState {
+    close_to_tray: AtomicBool,
+}
+
+impl Default for TrayState {
+    fn default() -> Self {
+        Self {
+            close_to_tray: AtomicBool::new(true),
+        }
+    }
+}
+
+/// Register the tray icon and its menu
+pub fn init(app: &App) -> tauri::Result<()> {
+    let menu = Menu::with_items(
+        app,
+        &[
+            &MenuItem::with_id(app, MENU_TOGGLE, "Show/Hide window", true, None::<&str>)?,
+            &MenuItem::with_id(app, MENU_NEW_SEARCH, "New search", true, None::<&str>)?,
+            &PredefinedMenuItem::separator(app)?,
+            &MenuItem::with_id(app, MENU_QUIT, "Quit", true, None::<&str>)?,
+        ],
+    )?;
+
+    let mut builder = TrayIconBuilder::with_id("main")
+        .tooltip(app.package_info().name.clone())
+        .menu(&menu)
+        .on_menu_event(handle_menu_event);
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    builder.build(app)?;
+
+    Ok(())
+}
+
+fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+fn handle_menu_event(app: &AppHandle, event: MenuEvent) {
+    match event.id().as_ref() {
+        MENU_TOGGLE => match app.get_webview_window("main") {
+            Some(window) if window.is_visible().unwrap_or(false) => {
+                let _ = window.hide();
+            }
+            _ => show_main_window(app),
+        },
+        MENU_NEW_SEARCH => {
+            show_main_window(app);
+            if let Err(e) = app.emit_to("main", TRAY_NEW_SEARCH_EVENT, ()) {
+                warn!("Failed to emit new search event: {}", e);
+            }
+        }
+        MENU_QUIT => {
+            // Exiting skips CloseRequested, so remember the geometry here
+            if let Some(window) = app.get_webview_window("main") {
+                crate::window_state::save(&window.as_ref().window());
+            }
+            app.exit(0);
+        }
+        _ => {}
+    }
+}
+
+/// Keep the app running in the tray when the main window is closed
+pub fn hide_on_close(window: &Window, api: &CloseRequestApi) {
+    let state = window.state::<TrayState>();
+    if window.label() == "main" && state.close_to_tray.load(Ordering::Relaxed) {
+        api.prevent_close();
+        let _ = window.hide();
+    }
+}
+
+/// Choose whether closing the main window hides it to the tray (the default) or quits
+#[tauri::command]
+pub fn set_close_to_tray(state: State<'_, TrayState>, enabled: bool) {
+    state.close_to_tray.store(enabled, Ordering::Relaxed);
+}
@@ -1,12 +1,105 @@
-use lancedb::connect;
-use lancedb::query::{ExecutableQuery, QueryBase};
-use serde::{Deserialize, Serialize};
+use lancedb::database::CreateTableMode;
+use lancedb::index::vector::IvfPqIndexBuilder;
+use lancedb::index::Index;
+use lancedb::{connect, Connection, DistanceType, Table};
+use lancedb::query::{ExecutableQuery, QueryBase, Select};
+use lancedb::arrow::SendableRecordBatchStream;
+use lancedb::table::OptimizeAction;
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Serialize, Serializer};
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use std::path::{Path, PathBuf};
 use std::sync::Arc as StdArc;
-use tauri::State;
-use tokio::sync::Mutex;
+use tauri::ipc::Response;
+use tauri::{AppHandle, Emitter, State};
+use tokio::sync::{Mutex, OwnedMutexGuard, OwnedSemaphorePermit, Semaphore};
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
 use futures::stream::StreamExt;
-use arrow_array::{ArrayRef, Float32Array, Int32Array, StringArray, FixedSizeListArray, RecordBatch, RecordBatchIterator};
-use arrow_schema::{DataType, Field, Schema};
+use arrow_ipc::reader::FileReader;
+use arrow_ipc::writer::{FileWriter, StreamWriter};
+use arrow_ord::sort::{lexsort_to_indices, SortColumn};
+use arrow_select::concat::concat_batches;
+use arrow_select::take::take_record_batch;
+use arrow_array::{Array, ArrayRef, Float32Array, Int32Array, ListArray, StringArray, FixedSizeListArray, RecordBatch, RecordBatchIterator, UInt64Array};
+use arrow_array::types::Float32Type;
+use arrow_schema::{ArrowError, DataType, Field, Schema, SchemaRef};
+
+/// Error returned by every vector store command. Serialized as
+/// `{ "kind": "table_not_found", "message": "..." }` so the frontend can tell
+/// e.g. "document not indexed yet" apart from a connection failure.
+#[derive(Debug, Clone, PartialEq)]
+pub enum VectorStoreError {
+    NotInitialized,
+    Connect(String),
+    TableNotFound(String),
+    SchemaMismatch(String),
+    InvalidArgument(String),
+    Query(String),
+    Io(String),
+}
+
+impl VectorStoreError {
+    /// Same error with `context` appended to its message
+    fn with_context(self, context: &str) -> Self {
+        let extend = |message: String| format!("{} ({})", message, context);
+        match self {
+            VectorStoreError::NotInitialized => VectorStoreError::NotInitialized,
+            VectorStoreError::Connect(message) => VectorStoreError::Connect(extend(message)),
+            VectorStoreError::TableNotFound(message) => VectorStoreError::TableNotFound(extend(message)),
+            VectorStoreError::SchemaMismatch(message) => VectorStoreError::SchemaMismatch(extend(message)),
+            VectorStoreError::InvalidArgument(message) => VectorStoreError::InvalidArgument(extend(message)),
+            VectorStoreError::Query(message) => VectorStoreError::Query(extend(message)),
+            VectorStoreError::Io(message) => VectorStoreError::Io(extend(message)),
+        }
+    }
+
+    fn kind(&self) -> &'static str {
+        match self {
+            VectorStoreError::NotInitialized => "not_initialized",
+            VectorStoreError::Connect(_) => "connect",
+            VectorStoreError::TableNotFound(_) => "table_not_found",
+            VectorStoreError::SchemaMismatch(_) => "schema_mismatch",
+            VectorStoreError::InvalidArgument(_) => "invalid_argument",
+            VectorStoreError::Query(_) => "query",
+            VectorStoreError::Io(_) => "io",
+        }
+    }
+}
+
+impl fmt::Display for VectorStoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VectorStoreError::NotInitialized => {
+                write!(f, "Vector store not initialized: call vector_store_initialize first")
+            }
+            VectorStoreError::Connect(message)
+            | VectorStoreError::TableNotFound(message)
+            | VectorStoreError::SchemaMismatch(message)
+            | VectorStoreError::InvalidArgument(message)
+            | VectorStoreError::Query(message)
+            | VectorStoreError::Io(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for VectorStoreError {}
+
+impl Serialize for VectorStoreError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut error = serializer.serialize_struct("VectorStoreError", 2)?;
+        error.serialize_field("kind", self.kind())?;
+        error.serialize_field("message", &self.to_string())?;
+        error.end()
+    }
+}
+
+impl From<std::io::Error> for VectorStoreError {
+    fn from(e: std::io::Error) -> Self {
+        VectorStoreError::Io(e.to_string())
+    }
+}
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct VectorSearchResult {
@@ -14,6 +107,230 @@ pub struct VectorSearchResult {
     pub text: String,
     pub score: f32,
     pub distance: f32,
+    pub chunk_index: i32,
+    pub text_length: i32,
+    /// Stored embedding, only filled in when the search asks for it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vector: Option<Vec<f32>>,
+}
+
+/// Result of `vector_store_add_chunks`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AddChunksReport {
+    pub table_name: String,
+    /// Chunks written to the table
+    pub inserted: usize,
+    /// Chunks dropped for being shorter than `min_text_length`
+    pub skipped: usize,
+}
+
+/// Payload of the `vector_store://progress` event emitted while chunks are ingested
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IngestProgress {
+    pub document_id: String,
+    pub inserted: usize,
+    pub total: usize,
+}
+
+/// Payload of the `vector_store://warmup-done` event emitted when `vector_store_warmup` finishes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WarmupSummary {
+    pub storage_path: String,
+    /// Document tables found in the store
+    pub tables: usize,
+    /// Document tables opened and queried once
+    pub warmed: usize,
+    /// Documents whose tables could not be warmed; searching them will report why
+    pub failed: Vec<String>,
+    /// Whether the warm-up stopped early, via `vector_store_cancel_warmup` or a newer warm-up
+    pub cancelled: bool,
+}
+
+/// Which half of a hybrid search found a chunk
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchSignal {
+    Vector,
+    Keyword,
+    Both,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HybridSearchResult {
+    #[serde(flatten)]
+    pub result: VectorSearchResult,
+    pub matched: MatchSignal,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct IndexOptions {
+    pub num_partitions: Option<u32>,
+    pub num_sub_vectors: Option<u32>,
+    /// Distance metric the index is trained for; must match the one used to search
+    pub metric: Option<String>,
+}
+
+/// Size of one document's table, for storage management
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DocumentStats {
+    /// Original document id, decoded from the table name
+    pub document_id: String,
+    pub row_count: u64,
+    pub size_bytes: u64,
+    /// Set when the table could not be opened or counted
+    pub error: Option<String>,
+}
+
+/// Size of the whole store, for a "library contains N papers / M chunks" status
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TotalStats {
+    pub document_count: usize,
+    pub chunk_count: u64,
+    /// One message per document table that could not be counted (counted as zero)
+    pub errors: Vec<String>,
+}
+
+/// What `vector_store_optimize` did to one document table
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OptimizeReport {
+    pub document_id: String,
+    pub fragments_removed: usize,
+    pub fragments_added: usize,
+    pub old_versions_removed: u64,
+    pub bytes_freed: u64,
+}
+
+/// Chunk counts before and after `vector_store_reindex` swapped a document's table
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct ReindexReport {
+    pub document_id: String,
+    pub old_count: usize,
+    pub new_count: usize,
+}
+
+/// Whether `vector_store_upsert_chunks` created a document or replaced one
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UpsertAction {
+    Created,
+    Replaced,
+}
+
+/// What `vector_store_upsert_chunks` did and how many chunks the document now has
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct UpsertReport {
+    pub action: UpsertAction,
+    pub row_count: usize,
+}
+
+/// Start of a document's text, returned by `vector_store_preview`
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct DocumentPreview {
+    pub chunk_count: usize,
+    /// Chunk texts in `chunk_index` order joined by newlines, at most `char_limit` chars
+    pub preview_text: String,
+    /// Whether the document has more text than `preview_text` shows
+    pub truncated: bool,
+}
+
+/// Document returned by `vector_store_similar_documents`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimilarDocument {
+    pub document_id: String,
+    /// Cosine similarity of the two documents' mean chunk vectors
+    pub score: f32,
+}
+
+/// Contents of the `_version` marker written when a store is first initialized
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StoreVersion {
+    pub format_version: String,
+    pub lancedb_version: String,
+    /// RFC 3339 timestamp of the initialization that wrote the marker
+    pub created_at: String,
+}
+
+/// Document table that `vector_store_check_compatibility` could not read
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UnreadableTable {
+    pub document_id: String,
+    pub error: String,
+}
+
+/// Outcome of `vector_store_migrate_table_names`
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct MigrationReport {
+    /// Number of tables moved to their `table_name_for` name
+    pub migrated: usize,
+    pub migrated_documents: Vec<String>,
+    /// Tables that may still carry a legacy name but have no `_metadata`
+    /// entry to recover their document id from. They are migrated the first
+    /// time their document is accessed by id.
+    pub unresolved: Vec<String>,
+}
+
+/// Whether this build can read an existing store, from `vector_store_check_compatibility`
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct CompatibilityReport {
+    /// LanceDB version this build links against
+    pub lancedb_version: String,
+    /// Store format this build writes
+    pub format_version: String,
+    /// Marker of the build that created the store; `None` for stores created
+    /// before the marker was introduced
+    pub store_version: Option<StoreVersion>,
+    /// Documents whose tables opened and could be counted
+    pub readable: Vec<String>,
+    pub unreadable: Vec<UnreadableTable>,
+}
+
+/// Outcome of deleting one document in `vector_store_delete_documents`
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", content = "message", rename_all = "snake_case")]
+pub enum DeletionResult {
+    Deleted,
+    NotFound,
+    Failed(String),
+}
+
+/// One document in an `ExportManifest`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportedDocument {
+    pub document_id: String,
+    /// Export file holding the document's chunks, relative to the manifest
+    pub file: String,
+    pub row_count: usize,
+    pub vector_dim: i32,
+    pub metadata: Option<DocumentMetadata>,
+}
+
+/// Index of a whole-store backup written by `vector_store_export_all`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportManifest {
+    pub format_version: String,
+    /// Store-wide embedding dimension, if one had been recorded
+    pub vector_dim: Option<i32>,
+    pub documents: Vec<ExportedDocument>,
+}
+
+/// Outcome of restoring one document in `vector_store_import_all`
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", content = "message", rename_all = "snake_case")]
+pub enum RestoreResult {
+    /// Number of chunks restored
+    Restored(usize),
+    Failed(String),
+}
+
+/// Descriptive information recorded alongside a document's chunks
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DocumentMetadata {
+    pub title: Option<String>,
+    pub source: Option<String>,
+    pub embedding_model: Option<String>,
+    pub vector_dim: Option<i32>,
+    /// RFC 3339 timestamp, filled in with the current time when not given
+    pub created_at: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -25,270 +342,4919 @@ pub struct ChunkData {
     pub text_length: i32,
 }
 
+/// Store-level key/value settings shared by every document
+const STORE_METADATA_TABLE: &str = "_store_metadata";
+/// Per-document metadata, one row per document id
+const DOCUMENT_METADATA_TABLE: &str = "_metadata";
+/// Single-row marker recording which build created the store
+const VERSION_TABLE: &str = "_version";
+/// Bump whenever the layout of the store's tables changes incompatibly
+const STORE_FORMAT_VERSION: &str = "1";
+/// Version of the `lancedb` dependency in Cargo.toml; keep the two in sync
+const LANCEDB_VERSION: &str = "0.22.2";
+/// Mean chunk vector per document, tagged with the chunk table version it was computed from
+const CENTROID_TABLE: &str = "_centroids";
+/// Embedding dimension recorded by the first ingestion and enforced store-wide
+const VECTOR_DIM_KEY: &str = "vector_dim";
+
+/// Prefix of every per-document chunk table
+const DOCUMENT_TABLE_PREFIX: &str = "doc_";
+/// Prefix for staging tables used while a document's table is being replaced
+const TEMP_TABLE_PREFIX: &str = "temp_";
+/// Created and removed again to check the storage directory is writable
+const WRITE_PROBE_FILE: &str = ".redink_write_probe";
+
+/// Custom metadata key stamped on files written by `vector_store_export_document`
+const EXPORT_FORMAT_KEY: &str = "redink.vector_store.format";
+/// Bump whenever the exported columns change incompatibly
+const EXPORT_FORMAT_VERSION: &str = "1";
+/// Manifest of a whole-store export, next to one export file per document
+const EXPORT_MANIFEST_FILE: &str = "manifest.json";
+
+/// PQ training needs at least 256 rows to fit its codebooks; smaller tables
+/// are fast enough to search by brute force anyway
+const MIN_ROWS_FOR_INDEX: usize = 256;
+/// `vector_store_add_chunks` builds an index automatically past this size
+const AUTO_INDEX_ROW_THRESHOLD: usize = 5000;
+
+/// Extra neighbours `search_table` fetches past `top_k`, so chunks tied with
+/// the last result are picked by `chunk_index` rather than LanceDB's arbitrary order
+const TIE_BREAK_OVERFETCH: usize = 8;
+
+/// Weight of the vector score in hybrid search; the keyword score gets the rest
+const DEFAULT_HYBRID_ALPHA: f32 = 0.7;
+
+/// Event emitted after every batch written by `vector_store_add_chunks`
+const PROGRESS_EVENT: &str = "vector_store://progress";
+/// Event emitted when the background part of `vector_store_warmup` finishes
+const WARMUP_DONE_EVENT: &str = "vector_store://warmup-done";
+/// Chunks written per batch by `vector_store_add_chunks`
+const DEFAULT_INGEST_BATCH_SIZE: usize = 256;
+
+/// Chunks whose text `preview_document` fetches per query
+const PREVIEW_WINDOW: usize = 16;
+
+/// Default number of LanceDB operations allowed to run at the same time
+const DEFAULT_MAX_CONCURRENT_OPERATIONS: usize = 4;
+
+/// Connection reused across commands, tagged with the path it was opened for
+type CachedConnection = Option<(String, Connection)>;
+
 pub struct VectorStoreState {
     db_path: StdArc<Mutex<Option<String>>>,
+    connection: StdArc<Mutex<CachedConnection>>,
+    operation_limit: StdArc<Semaphore>,
+    /// One lock per document table. Writes and maintenance take it so they
+    /// serialize per document; reads never do, since LanceDB serves them from
+    /// a consistent table version.
+    table_locks: StdArc<Mutex<HashMap<String, StdArc<Mutex<()>>>>>,
+    /// Generation and cancel token of the warm-up running in the background, if any
+    warmup: Option<(u64, CancellationToken)>,
+    next_warmup_generation: u64,
 }
 
 impl VectorStoreState {
     pub fn new() -> Self {
         Self {
             db_path: StdArc::new(Mutex::new(None)),
+            connection: StdArc::new(Mutex::new(None)),
+            operation_limit: StdArc::new(Semaphore::new(DEFAULT_MAX_CONCURRENT_OPERATIONS)),
+            table_locks: StdArc::new(Mutex::new(HashMap::new())),
+            warmup: None,
+            next_warmup_generation: 0,
         }
     }
 }
 
-#[tauri::command]
-pub async fn vector_store_initialize(
-    storage_path: String,
-    state: State<'_, StdArc<Mutex<VectorStoreState>>>,
-) -> Result<String, String> {
-    let mut store_state = state.lock().await;
-    store_state.db_path = StdArc::new(Mutex::new(Some(storage_path.clone())));
-    
-    // Test connection
-    let _db = connect(&storage_path)
-        .execute()
+/// Take the write lock for `table_name`, creating it on first use
+async fn lock_table(state: &StdArc<Mutex<VectorStoreState>>, table_name: &str) -> OwnedMutexGuard<()> {
+    let table_locks = state.lock().await.table_locks.clone();
+    let table_lock = table_locks
+        .lock()
         .await
-        .map_err(|e| format!("Failed to connect to LanceDB: {}", e))?;
-    
-    Ok(format!("LanceDB initialized at: {}", storage_path))
+        .entry(table_name.to_string())
+        .or_default()
+        .clone();
+    table_lock.lock_owned().await
 }
 
-#[tauri::command]
-pub async fn vector_store_add_chunks(
-    document_id: String,
-    chunks: Vec<ChunkData>,
-    storage_path: String,
-) -> Result<String, String> {
-    let db = connect(&storage_path)
-        .execute()
-        .await
-        .map_err(|e| format!("Failed to connect: {}", e))?;
-
-    let table_name = format!("doc_{}", document_id.replace(|c: char| !c.is_alphanumeric(), "_"));
-
-    // Create table (overwrite if exists) - try drop first
-    // LanceDB 0.22.2 API: drop_table(name, namespace)
-    let _ = db.drop_table(&table_name, &[]).await; // Ignore error if table doesn't exist
-    
-    // Build Arrow arrays following LanceDB 0.22.2 API guide
-    // Reference: https://docs.rs/lancedb/latest/lancedb/index.html
-    let ids: StringArray = chunks.iter().map(|c| Some(c.id.as_str())).collect();
-    let texts: StringArray = chunks.iter().map(|c| Some(c.text.as_str())).collect();
-    let chunk_indices: Int32Array = chunks.iter().map(|c| Some(c.chunk_index)).collect();
-    let text_lengths: Int32Array = chunks.iter().map(|c| Some(c.text_length)).collect();
-    
-    // Create fixed-size list for vectors (384 dimensions)
-    let vector_dim = if chunks.is_empty() { 384 } else { chunks[0].vector.len() as i32 };
-    let mut vector_values = Vec::new();
-    for chunk in &chunks {
-        vector_values.extend_from_slice(&chunk.vector);
+/// Table holding a document's chunks. ASCII letters and digits are kept as-is
+/// and every other byte becomes `_` plus two hex digits, so the mapping is
+/// reversible and distinct ids (`paper 1`, `paper-1`) never share a table.
+fn table_name_for(document_id: &str) -> String {
+    let mut table_name = String::from(DOCUMENT_TABLE_PREFIX);
+    for byte in document_id.bytes() {
+        if byte.is_ascii_alphanumeric() {
+            table_name.push(byte as char);
+        } else {
+            table_name.push_str(&format!("_{:02x}", byte));
+        }
     }
-    let vector_data = Float32Array::from(vector_values);
-    
-    // Use from_iter_primitive as shown in the official docs
-    let vectors = FixedSizeListArray::try_new(
-        StdArc::new(Field::new("item", DataType::Float32, true)),
-        vector_dim,
-        StdArc::new(vector_data) as ArrayRef,
-        None,
-    )
-    .map_err(|e| format!("Failed to create vector array: {}", e))?;
-    
-    // Define schema
-    let schema = StdArc::new(Schema::new(vec![
-        Field::new("id", DataType::Utf8, false),
-        Field::new("text", DataType::Utf8, false),
-        Field::new("vector", DataType::FixedSizeList(
-            StdArc::new(Field::new("item", DataType::Float32, true)),
-            vector_dim,
-        ), false),
-        Field::new("chunk_index", DataType::Int32, false),
-        Field::new("text_length", DataType::Int32, false),
-    ]));
-    
-    // Create RecordBatch
-    let batch = RecordBatch::try_new(
-        schema.clone(),
-        vec![
-            StdArc::new(ids) as ArrayRef,
-            StdArc::new(texts) as ArrayRef,
-            StdArc::new(vectors) as ArrayRef,
-            StdArc::new(chunk_indices) as ArrayRef,
-            StdArc::new(text_lengths) as ArrayRef,
-        ],
-    )
-    .map_err(|e| format!("Failed to create record batch: {}", e))?;
-    
-    // Create RecordBatchIterator as per LanceDB 0.22.2 API
-    // Reference: https://docs.rs/lancedb/latest/lancedb/index.html
-    let batches = RecordBatchIterator::new(
-        vec![Ok(batch)].into_iter(),
-        schema.clone(),
-    );
-    
-    // Create table with RecordBatchIterator - this works with Arrow 56.2!
-    db.create_table(&table_name, Box::new(batches))
-        .execute()
-        .await
-        .map_err(|e| format!("Failed to create table: {}", e))?;
+    table_name
+}
 
-    Ok(format!("Added {} chunks to table {}", chunks.len(), table_name))
+/// Table name given to `document_id` before `table_name_for` made the mapping
+/// reversible: every non-alphanumeric character became `_`
+fn legacy_table_name_for(document_id: &str) -> String {
+    format!("{}{}", DOCUMENT_TABLE_PREFIX, document_id.replace(|c: char| !c.is_alphanumeric(), "_"))
 }
 
-#[tauri::command]
-pub async fn vector_store_search(
-    document_id: String,
-    query_embedding: Vec<f32>,
-    top_k: usize,
-    storage_path: String,
-) -> Result<Vec<VectorSearchResult>, String> {
-    let db = connect(&storage_path)
-        .execute()
-        .await
-        .map_err(|e| format!("Failed to connect: {}", e))?;
+/// Inverse of `table_name_for`. Tables named by the old lossy scheme don't
+/// decode, so their raw suffix is returned instead.
+fn document_id_for(table_name: &str) -> Option<String> {
+    let encoded = table_name.strip_prefix(DOCUMENT_TABLE_PREFIX)?;
+
+    let mut bytes = Vec::with_capacity(encoded.len());
+    let mut rest = encoded.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        if byte != b'_' {
+            bytes.push(byte);
+            rest = tail;
+            continue;
+        }
+        let decoded = tail
+            .get(..2)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match decoded {
+            Some(decoded) => {
+                bytes.push(decoded);
+                rest = &tail[2..];
+            }
+            None => return Some(encoded.to_string()),
+        }
+    }
 
-    let table_name = format!("doc_{}", document_id.replace(|c: char| !c.is_alphanumeric(), "_"));
+    Some(String::from_utf8(bytes).unwrap_or_else(|_| encoded.to_string()))
+}
 
-    let table = db
-        .open_table(&table_name)
-        .execute()
+/// Wait for a free slot before touching LanceDB, so bursts of UI activity
+/// don't open an unbounded number of connections at once.
+async fn acquire_operation_permit(
+    state: &StdArc<Mutex<VectorStoreState>>,
+) -> Result<OwnedSemaphorePermit, VectorStoreError> {
+    // Clone the semaphore out so the state lock isn't held while waiting
+    let semaphore = state.lock().await.operation_limit.clone();
+    semaphore
+        .acquire_owned()
         .await
-        .map_err(|e| format!("Table not found: {}", e))?;
+        .map_err(|e| VectorStoreError::Query(format!("Failed to acquire operation permit: {}", e)))
+}
 
-    // Perform vector search
-    let mut result_stream = table
-        .query()
-        .nearest_to(query_embedding)
-        .map_err(|e| format!("Query failed: {}", e))?
-        .limit(top_k)
+async fn open_connection(storage_path: &str) -> Result<Connection, VectorStoreError> {
+    connect(storage_path)
         .execute()
         .await
-        .map_err(|e| format!("Search failed: {}", e))?;
-
-    let mut search_results = Vec::new();
-    
-    // Convert results to our format
-    while let Some(batch_result) = result_stream.next().await {
-        let batch = batch_result.map_err(|e| format!("Batch error: {}", e))?;
-        
-        let ids = batch.column(0)
-            .as_any()
-            .downcast_ref::<StringArray>()
-            .ok_or("Failed to downcast id column")?;
-            
-        let texts = batch.column(1)
-            .as_any()
-            .downcast_ref::<StringArray>()
-            .ok_or("Failed to downcast text column")?;
-            
-        let distances = batch.column_by_name("_distance")
-            .ok_or("No distance column")?
-            .as_any()
-            .downcast_ref::<Float32Array>()
-            .ok_or("Failed to downcast distance column")?;
-
-        for i in 0..batch.num_rows() {
-            let distance = distances.value(i);
-            let score = 1.0 / (1.0 + distance); // Convert L2 distance to score
+        .map_err(|e| VectorStoreError::Connect(format!("Failed to connect: {}", e)))
+}
 
-            search_results.push(VectorSearchResult {
-                id: ids.value(i).to_string(),
-                text: texts.value(i).to_string(),
-                score,
-                distance,
-            });
+/// Return the cached connection for `storage_path`, opening (and caching) a
+/// new one the first time or when a different path is requested
+async fn cached_connection(
+    cache: &Mutex<CachedConnection>,
+    storage_path: &str,
+) -> Result<Connection, VectorStoreError> {
+    let mut cached = cache.lock().await;
+    if let Some((path, db)) = cached.as_ref() {
+        if path == storage_path {
+            return Ok(db.clone());
         }
     }
 
-    Ok(search_results)
+    let db = open_connection(storage_path).await?;
+    *cached = Some((storage_path.to_string(), db.clone()));
+    Ok(db)
 }
 
-#[tauri::command]
-pub async fn vector_store_has_document(
-    document_id: String,
-    storage_path: String,
-) -> Result<bool, String> {
-    let db = connect(&storage_path)
+async fn state_connection(
+    state: &StdArc<Mutex<VectorStoreState>>,
+    storage_path: &str,
+) -> Result<Connection, VectorStoreError> {
+    // Clone the cache handle out so the state lock isn't held while connecting
+    let cache = state.lock().await.connection.clone();
+    cached_connection(&cache, storage_path).await
+}
+
+async fn open_table(db: &Connection, table_name: &str) -> Result<Table, VectorStoreError> {
+    db.open_table(table_name)
         .execute()
         .await
-        .map_err(|e| format!("Failed to connect: {}", e))?;
+        .map_err(|e| match e {
+            lancedb::Error::TableNotFound { .. } => {
+                VectorStoreError::TableNotFound(format!("Table not found: {}", e))
+            }
+            _ => VectorStoreError::Query(format!("Failed to open table: {}", e)),
+        })
+}
 
-    let table_name = format!("doc_{}", document_id.replace(|c: char| !c.is_alphanumeric(), "_"));
+/// Look a column up by name and downcast it to its concrete array type
+fn column<'a, T: 'static>(batch: &'a RecordBatch, name: &str) -> Result<&'a T, VectorStoreError> {
+    batch
+        .column_by_name(name)
+        .ok_or_else(|| VectorStoreError::SchemaMismatch(format!("No {} column", name)))?
+        .as_any()
+        .downcast_ref::<T>()
+        .ok_or_else(|| VectorStoreError::SchemaMismatch(format!("Failed to downcast {} column", name)))
+}
 
+async fn read_store_setting(db: &Connection, key: &str) -> Result<Option<String>, VectorStoreError> {
     let table_names = db
         .table_names()
         .execute()
         .await
-        .map_err(|e| format!("Failed to list tables: {}", e))?;
+        .map_err(|e| VectorStoreError::Query(format!("Failed to list tables: {}", e)))?;
+    if !table_names.iter().any(|name| name == STORE_METADATA_TABLE) {
+        return Ok(None);
+    }
 
-    Ok(table_names.contains(&table_name))
-}
+    let table = db
+        .open_table(STORE_METADATA_TABLE)
+        .execute()
+        .await
+        .map_err(|e| VectorStoreError::Query(format!("Failed to open store metadata: {}", e)))?;
 
-#[tauri::command]
-pub async fn vector_store_delete_document(
-    document_id: String,
-    storage_path: String,
-) -> Result<String, String> {
-    let db = connect(&storage_path)
+    let mut result_stream = table
+        .query()
+        .only_if(format!("key = '{}'", key.replace('\'', "''")))
         .execute()
         .await
-        .map_err(|e| format!("Failed to connect: {}", e))?;
+        .map_err(|e| VectorStoreError::Query(format!("Failed to read store metadata: {}", e)))?;
 
-    let table_name = format!("doc_{}", document_id.replace(|c: char| !c.is_alphanumeric(), "_"));
+    while let Some(batch_result) = result_stream.next().await {
+        let batch = batch_result.map_err(|e| VectorStoreError::Query(format!("Batch error: {}", e)))?;
+        let values = column::<StringArray>(&batch, "value")?;
 
-    db.drop_table(&table_name, &[])
-        .await
-        .map_err(|e| format!("Failed to delete table: {}", e))?;
+        if batch.num_rows() > 0 {
+            return Ok(Some(values.value(0).to_string()));
+        }
+    }
 
-    Ok(format!("Deleted table: {}", table_name))
+    Ok(None)
 }
 
-#[tauri::command]
-pub async fn vector_store_clear_all(storage_path: String) -> Result<String, String> {
-    let db = connect(&storage_path)
-        .execute()
-        .await
-        .map_err(|e| format!("Failed to connect: {}", e))?;
+async fn write_store_setting(db: &Connection, key: &str, value: &str) -> Result<(), VectorStoreError> {
+    let schema = StdArc::new(Schema::new(vec![
+        Field::new("key", DataType::Utf8, false),
+        Field::new("value", DataType::Utf8, false),
+    ]));
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            StdArc::new(StringArray::from(vec![key])) as ArrayRef,
+            StdArc::new(StringArray::from(vec![value])) as ArrayRef,
+        ],
+    )
+    .map_err(|e| VectorStoreError::Query(format!("Failed to create record batch: {}", e)))?;
+    let batches = RecordBatchIterator::new(vec![Ok(batch)].into_iter(), schema.clone());
 
     let table_names = db
         .table_names()
         .execute()
         .await
-        .map_err(|e| format!("Failed to list tables: {}", e))?;
+        .map_err(|e| VectorStoreError::Query(format!("Failed to list tables: {}", e)))?;
 
-    for table_name in table_names {
-        db.drop_table(&table_name, &[])
+    if table_names.iter().any(|name| name == STORE_METADATA_TABLE) {
+        let table = db
+            .open_table(STORE_METADATA_TABLE)
+            .execute()
             .await
-            .map_err(|e| format!("Failed to delete table {}: {}", table_name, e))?;
+            .map_err(|e| VectorStoreError::Query(format!("Failed to open store metadata: {}", e)))?;
+        table
+            .delete(&format!("key = '{}'", key.replace('\'', "''")))
+            .await
+            .map_err(|e| VectorStoreError::Query(format!("Failed to update store metadata: {}", e)))?;
+        table
+            .add(Box::new(batches))
+            .execute()
+            .await
+            .map_err(|e| VectorStoreError::Query(format!("Failed to update store metadata: {}", e)))?;
+    } else {
+        db.create_table(STORE_METADATA_TABLE, Box::new(batches))
+            .execute()
+            .await
+            .map_err(|e| VectorStoreError::Query(format!("Failed to create store metadata: {}", e)))?;
     }
 
-    Ok("Cleared all tables".to_string())
+    Ok(())
 }
 
-#[tauri::command]
-pub async fn vector_store_get_count(
-    document_id: String,
-    storage_path: String,
-) -> Result<i64, String> {
-    let db = connect(&storage_path)
+async fn table_exists(db: &Connection, table_name: &str) -> Result<bool, VectorStoreError> {
+    let table_names = db
+        .table_names()
         .execute()
         .await
-        .map_err(|e| format!("Failed to connect: {}", e))?;
+        .map_err(|e| VectorStoreError::Query(format!("Failed to list tables: {}", e)))?;
+    Ok(table_names.iter().any(|name| name == table_name))
+}
 
-    let table_name = format!("doc_{}", document_id.replace(|c: char| !c.is_alphanumeric(), "_"));
+async fn read_version_marker(db: &Connection) -> Result<Option<StoreVersion>, VectorStoreError> {
+    if !table_exists(db, VERSION_TABLE).await? {
+        return Ok(None);
+    }
 
     let table = db
-        .open_table(&table_name)
+        .open_table(VERSION_TABLE)
         .execute()
         .await
-        .map_err(|e| format!("Table not found: {}", e))?;
+        .map_err(|e| VectorStoreError::Query(format!("Failed to open version marker: {}", e)))?;
 
-    let count = table
-        .count_rows(None)
+    let mut result_stream = table
+        .query()
+        .execute()
         .await
-        .map_err(|e| format!("Failed to count rows: {}", e))?;
+        .map_err(|e| VectorStoreError::Query(format!("Failed to read version marker: {}", e)))?;
 
-    Ok(count as i64)
+    while let Some(batch_result) = result_stream.next().await {
+        let batch = batch_result.map_err(|e| VectorStoreError::Query(format!("Batch error: {}", e)))?;
+        if batch.num_rows() == 0 {
+            continue;
+        }
+
+        return Ok(Some(StoreVersion {
+            format_version: column::<StringArray>(&batch, "format_version")?.value(0).to_string(),
+            lancedb_version: column::<StringArray>(&batch, "lancedb_version")?.value(0).to_string(),
+            created_at: column::<StringArray>(&batch, "created_at")?.value(0).to_string(),
+        }));
+    }
+
+    Ok(None)
 }
 
+/// Record this build's format and LanceDB version, unless the store already
+/// has a marker (which then keeps describing the build that created it)
+async fn write_version_marker(db: &Connection) -> Result<(), VectorStoreError> {
+    if table_exists(db, VERSION_TABLE).await? {
+        return Ok(());
+    }
+
+    let schema = StdArc::new(Schema::new(vec![
+        Field::new("format_version", DataType::Utf8, false),
+        Field::new("lancedb_version", DataType::Utf8, false),
+        Field::new("created_at", DataType::Utf8, false),
+    ]));
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            StdArc::new(StringArray::from(vec![STORE_FORMAT_VERSION])) as ArrayRef,
+            StdArc::new(StringArray::from(vec![LANCEDB_VERSION])) as ArrayRef,
+            StdArc::new(StringArray::from(vec![chrono::Utc::now().to_rfc3339()])) as ArrayRef,
+        ],
+    )
+    .map_err(|e| VectorStoreError::Query(format!("Failed to create record batch: {}", e)))?;
+    let batches = RecordBatchIterator::new(vec![Ok(batch)].into_iter(), schema);
+
+    db.create_table(VERSION_TABLE, Box::new(batches))
+        .execute()
+        .await
+        .map_err(|e| VectorStoreError::Query(format!("Failed to create version marker: {}", e)))?;
+    Ok(())
+}
+
+/// Drop `table_name` if it is listed. A table that vanishes between the
+/// listing and the drop (another writer dropped it first) is not an error.
+async fn drop_table_if_exists(db: &Connection, table_name: &str) -> Result<(), VectorStoreError> {
+    if !table_exists(db, table_name).await? {
+        return Ok(());
+    }
+    match db.drop_table(table_name, &[]).await {
+        Ok(()) | Err(lancedb::Error::TableNotFound { .. }) => Ok(()),
+        Err(e) => Err(VectorStoreError::Query(format!("Failed to drop table '{}': {}", table_name, e))),
+    }
+}
+
+/// Create errors caused by a concurrent create/drop of the same table, which
+/// are worth one retry
+fn is_transient_create_error(error: &lancedb::Error) -> bool {
+    if matches!(error, lancedb::Error::TableAlreadyExists { .. }) {
+        return true;
+    }
+    let message = error.to_string().to_lowercase();
+    message.contains("already exists") || message.contains("lock") || message.contains("conflict")
+}
+
+/// Run `create`, running it once more if the first attempt hit a transient error
+async fn create_with_retry<T, F, Fut>(table_name: &str, mut create: F) -> Result<T, lancedb::Error>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<T, lancedb::Error>>,
+{
+    match create().await {
+        Err(e) if is_transient_create_error(&e) => {
+            warn!("Retrying creation of table '{}' after: {}", table_name, e);
+            create().await
+        }
+        result => result,
+    }
+}
+
+fn document_id_filter(document_id: &str) -> String {
+    format!("document_id = '{}'", document_id.replace('\'', "''"))
+}
+
+async fn read_document_metadata(
+    db: &Connection,
+    document_id: &str,
+) -> Result<Option<DocumentMetadata>, VectorStoreError> {
+    if !table_exists(db, DOCUMENT_METADATA_TABLE).await? {
+        return Ok(None);
+    }
+
+    let table = db
+        .open_table(DOCUMENT_METADATA_TABLE)
+        .execute()
+        .await
+        .map_err(|e| VectorStoreError::Query(format!("Failed to open document metadata: {}", e)))?;
+
+    let mut result_stream = table
+        .query()
+        .only_if(document_id_filter(document_id))
+        .execute()
+        .await
+        .map_err(|e| VectorStoreError::Query(format!("Failed to read document metadata: {}", e)))?;
+
+    while let Some(batch_result) = result_stream.next().await {
+        let batch = batch_result.map_err(|e| VectorStoreError::Query(format!("Batch error: {}", e)))?;
+        if batch.num_rows() == 0 {
+            continue;
+        }
+
+        let string_value = |name: &str| -> Result<Option<String>, VectorStoreError> {
+            let values = column::<StringArray>(&batch, name)?;
+            Ok((!values.is_null(0)).then(|| values.value(0).to_string()))
+        };
+        let vector_dims = column::<Int32Array>(&batch, "vector_dim")?;
+
+        return Ok(Some(DocumentMetadata {
+            title: string_value("title")?,
+            source: string_value("source")?,
+            embedding_model: string_value("embedding_model")?,
+            vector_dim: (!vector_dims.is_null(0)).then(|| vector_dims.value(0)),
+            created_at: string_value("created_at")?,
+        }));
+    }
+
+    Ok(None)
+}
+
+async fn write_document_metadata(
+    db: &Connection,
+    document_id: &str,
+    metadata: &DocumentMetadata,
+) -> Result<(), VectorStoreError> {
+    let schema = StdArc::new(Schema::new(vec![
+        Field::new("document_id", DataType::Utf8, false),
+        Field::new("title", DataType::Utf8, true),
+        Field::new("source", DataType::Utf8, true),
+        Field::new("embedding_model", DataType::Utf8, true),
+        Field::new("vector_dim", DataType::Int32, true),
+        Field::new("created_at", DataType::Utf8, true),
+    ]));
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            StdArc::new(StringArray::from(vec![document_id])) as ArrayRef,
+            StdArc::new(StringArray::from(vec![metadata.title.clone()])) as ArrayRef,
+            StdArc::new(StringArray::from(vec![metadata.source.clone()])) as ArrayRef,
+            StdArc::new(StringArray::from(vec![metadata.embedding_model.clone()])) as ArrayRef,
+            StdArc::new(Int32Array::from(vec![metadata.vector_dim])) as ArrayRef,
+            StdArc::new(StringArray::from(vec![metadata.created_at.clone()])) as ArrayRef,
+        ],
+    )
+    .map_err(|e| VectorStoreError::Query(format!("Failed to create record batch: {}", e)))?;
+    let batches = RecordBatchIterator::new(vec![Ok(batch)].into_iter(), schema.clone());
+
+    if table_exists(db, DOCUMENT_METADATA_TABLE).await? {
+        let table = db
+            .open_table(DOCUMENT_METADATA_TABLE)
+            .execute()
+            .await
+            .map_err(|e| VectorStoreError::Query(format!("Failed to open document metadata: {}", e)))?;
+        table
+            .delete(&document_id_filter(document_id))
+            .await
+            .map_err(|e| VectorStoreError::Query(format!("Failed to update document metadata: {}", e)))?;
+        table
+            .add(Box::new(batches))
+            .execute()
+            .await
+            .map_err(|e| VectorStoreError::Query(format!("Failed to update document metadata: {}", e)))?;
+    } else {
+        db.create_table(DOCUMENT_METADATA_TABLE, Box::new(batches))
+            .execute()
+            .await
+            .map_err(|e| VectorStoreError::Query(format!("Failed to create document metadata: {}", e)))?;
+    }
+
+    Ok(())
+}
+
+async fn delete_document_metadata(db: &Connection, document_id: &str) -> Result<(), VectorStoreError> {
+    if !table_exists(db, DOCUMENT_METADATA_TABLE).await? {
+        return Ok(());
+    }
+
+    let table = db
+        .open_table(DOCUMENT_METADATA_TABLE)
+        .execute()
+        .await
+        .map_err(|e| VectorStoreError::Query(format!("Failed to open document metadata: {}", e)))?;
+    table
+        .delete(&document_id_filter(document_id))
+        .await
+        .map_err(|e| VectorStoreError::Query(format!("Failed to delete document metadata: {}", e)))?;
+    Ok(())
+}
+
+/// Cached centroid of `document_id`, if one was computed from `table_version`
+async fn read_cached_centroid(
+    db: &Connection,
+    document_id: &str,
+    table_version: u64,
+) -> Result<Option<Vec<f32>>, VectorStoreError> {
+    if !table_exists(db, CENTROID_TABLE).await? {
+        return Ok(None);
+    }
+
+    let table = db
+        .open_table(CENTROID_TABLE)
+        .execute()
+        .await
+        .map_err(|e| VectorStoreError::Query(format!("Failed to open centroid cache: {}", e)))?;
+
+    let mut result_stream = table
+        .query()
+        .only_if(format!("{} AND table_version = {}", document_id_filter(document_id), table_version))
+        .execute()
+        .await
+        .map_err(|e| VectorStoreError::Query(format!("Failed to read centroid cache: {}", e)))?;
+
+    while let Some(batch_result) = result_stream.next().await {
+        let batch = batch_result.map_err(|e| VectorStoreError::Query(format!("Batch error: {}", e)))?;
+        if batch.num_rows() == 0 {
+            continue;
+        }
+
+        let centroids = column::<ListArray>(&batch, "centroid")?;
+        let centroid = centroids
+            .value(0)
+            .as_any()
+            .downcast_ref::<Float32Array>()
+            .ok_or_else(|| VectorStoreError::SchemaMismatch("Failed to downcast centroid values".to_string()))?
+            .values()
+            .to_vec();
+        return Ok(Some(centroid));
+    }
+
+    Ok(None)
+}
+
+async fn write_cached_centroid(
+    db: &Connection,
+    document_id: &str,
+    table_version: u64,
+    centroid: &[f32],
+) -> Result<(), VectorStoreError> {
+    let schema = StdArc::new(Schema::new(vec![
+        Field::new("document_id", DataType::Utf8, false),
+        Field::new("table_version", DataType::UInt64, false),
+        Field::new("centroid", DataType::List(StdArc::new(Field::new("item", DataType::Float32, true))), false),
+    ]));
+    let centroids = ListArray::from_iter_primitive::<Float32Type, _, _>(vec![Some(centroid.iter().map(|&value| Some(value)))]);
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            StdArc::new(StringArray::from(vec![document_id])) as ArrayRef,
+            StdArc::new(UInt64Array::from(vec![table_version])) as ArrayRef,
+            StdArc::new(centroids) as ArrayRef,
+        ],
+    )
+    .map_err(|e| VectorStoreError::Query(format!("Failed to create record batch: {}", e)))?;
+    let batches = RecordBatchIterator::new(vec![Ok(batch)].into_iter(), schema.clone());
+
+    if table_exists(db, CENTROID_TABLE).await? {
+        let table = db
+            .open_table(CENTROID_TABLE)
+            .execute()
+            .await
+            .map_err(|e| VectorStoreError::Query(format!("Failed to open centroid cache: {}", e)))?;
+        table
+            .delete(&document_id_filter(document_id))
+            .await
+            .map_err(|e| VectorStoreError::Query(format!("Failed to update centroid cache: {}", e)))?;
+        table
+            .add(Box::new(batches))
+            .execute()
+            .await
+            .map_err(|e| VectorStoreError::Query(format!("Failed to update centroid cache: {}", e)))?;
+    } else {
+        db.create_table(CENTROID_TABLE, Box::new(batches))
+            .execute()
+            .await
+            .map_err(|e| VectorStoreError::Query(format!("Failed to create centroid cache: {}", e)))?;
+    }
+
+    Ok(())
+}
+
+/// Forget a deleted document's centroid, so a new document with the same id
+/// (whose table versions start over) can't pick it up
+async fn delete_cached_centroid(db: &Connection, document_id: &str) -> Result<(), VectorStoreError> {
+    if !table_exists(db, CENTROID_TABLE).await? {
+        return Ok(());
+    }
+
+    let table = db
+        .open_table(CENTROID_TABLE)
+        .execute()
+        .await
+        .map_err(|e| VectorStoreError::Query(format!("Failed to open centroid cache: {}", e)))?;
+    table
+        .delete(&document_id_filter(document_id))
+        .await
+        .map_err(|e| VectorStoreError::Query(format!("Failed to delete cached centroid: {}", e)))?;
+    Ok(())
+}
+
+/// Vector dimension shared by every chunk in the batch. Rejects empty or
+/// ragged vectors instead of letting the first chunk silently decide the
+/// table schema, and cross-checks `expected_dim` when the caller passes one.
+/// NaN or infinite components are always rejected, since they silently break
+/// every distance computed against the vector.
+fn batch_vector_dim(chunks: &[ChunkData], expected_dim: Option<i32>) -> Result<i32, VectorStoreError> {
+    let Some(first) = chunks.first() else {
+        return Ok(expected_dim.unwrap_or(384));
+    };
+    if first.vector.is_empty() {
+        return Err(VectorStoreError::InvalidArgument(format!(
+            "Chunk '{}' has an empty vector",
+            first.id
+        )));
+    }
+
+    let vector_dim = first.vector.len();
+    if let Some(chunk) = chunks.iter().find(|chunk| chunk.vector.len() != vector_dim) {
+        return Err(VectorStoreError::InvalidArgument(format!(
+            "Ragged vectors: chunk '{}' has {} dimensions but chunk '{}' has {}",
+            chunk.id,
+            chunk.vector.len(),
+            first.id,
+            vector_dim
+        )));
+    }
+
+    if let Some(chunk) = chunks.iter().find(|chunk| chunk.vector.iter().any(|value| !value.is_finite())) {
+        return Err(VectorStoreError::InvalidArgument(format!(
+            "Chunk '{}' has a NaN or infinite vector component",
+            chunk.id
+        )));
+    }
+
+    let vector_dim = vector_dim as i32;
+    if let Some(expected) = expected_dim {
+        if expected != vector_dim {
+            return Err(VectorStoreError::SchemaMismatch(format!(
+                "Embedding dimension mismatch: expected {}-dim vectors, got {}",
+                expected, vector_dim
+            )));
+        }
+    }
+
+    Ok(vector_dim)
+}
+
+/// Scale every vector to unit length, so L2 and dot-product search rank like
+/// cosine. All-zero vectors have no direction and are left as they are.
+fn normalize_vectors(chunks: &mut [ChunkData]) {
+    for chunk in chunks {
+        let norm = chunk.vector.iter().map(|value| value * value).sum::<f32>().sqrt();
+        if norm > 0.0 {
+            chunk.vector.iter_mut().for_each(|value| *value /= norm);
+        }
+    }
+}
+
+/// Copy row `i` of a `FixedSizeList<Float32>` vector column out as a `Vec`
+fn vector_at(vectors: &FixedSizeListArray, i: usize) -> Result<Vec<f32>, VectorStoreError> {
+    Ok(vectors
+        .value(i)
+        .as_any()
+        .downcast_ref::<Float32Array>()
+        .ok_or_else(|| VectorStoreError::SchemaMismatch("Failed to downcast vector values".to_string()))?
+        .values()
+        .to_vec())
+}
+
+/// Columns of a document's chunk table
+fn chunk_schema(vector_dim: i32) -> SchemaRef {
+    StdArc::new(Schema::new(vec![
+        Field::new("id", DataType::Utf8, false),
+        Field::new("text", DataType::Utf8, false),
+        Field::new("vector", DataType::FixedSizeList(
+            StdArc::new(Field::new("item", DataType::Float32, true)),
+            vector_dim,
+        ), false),
+        Field::new("chunk_index", DataType::Int32, false),
+        Field::new("text_length", DataType::Int32, false),
+    ]))
+}
+
+/// Build the Arrow batch stored for a document's chunks
+fn build_chunk_batch(chunks: &[ChunkData], vector_dim: i32) -> Result<(SchemaRef, RecordBatch), VectorStoreError> {
+    // Build Arrow arrays following LanceDB 0.22.2 API guide
+    // Reference: https://docs.rs/lancedb/latest/lancedb/index.html
+    let ids: StringArray = chunks.iter().map(|c| Some(c.id.as_str())).collect();
+    let texts: StringArray = chunks.iter().map(|c| Some(c.text.as_str())).collect();
+    let chunk_indices: Int32Array = chunks.iter().map(|c| Some(c.chunk_index)).collect();
+    let text_lengths: Int32Array = chunks.iter().map(|c| Some(c.text_length)).collect();
+    
+    let mut vector_values = Vec::new();
+    for chunk in chunks {
+        vector_values.extend_from_slice(&chunk.vector);
+    }
+    let vector_data = Float32Array::from(vector_values);
+    
+    // Use from_iter_primitive as shown in the official docs
+    let vectors = FixedSizeListArray::try_new(
+        StdArc::new(Field::new("item", DataType::Float32, true)),
+        vector_dim,
+        StdArc::new(vector_data) as ArrayRef,
+        None,
+    )
+    .map_err(|e| VectorStoreError::Query(format!("Failed to create vector array: {}", e)))?;
+    
+    let schema = chunk_schema(vector_dim);
+    
+    // Create RecordBatch
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            StdArc::new(ids) as ArrayRef,
+            StdArc::new(texts) as ArrayRef,
+            StdArc::new(vectors) as ArrayRef,
+            StdArc::new(chunk_indices) as ArrayRef,
+            StdArc::new(text_lengths) as ArrayRef,
+        ],
+    )
+    .map_err(|e| VectorStoreError::Query(format!("Failed to create record batch: {}", e)))?;
+
+    Ok((schema, batch))
+}
+
+/// Replace the contents of `table_name` without a window where it doesn't exist.
+///
+/// The new rows are first written to a `temp_` staging table. Only once that
+/// succeeds is the original overwritten from it; LanceDB OSS has no
+/// `rename_table`, but an overwrite commits as a single new table version.
+/// On any failure the original table is left intact and the staging table
+/// is cleaned up.
+async fn replace_table(
+    db: &Connection,
+    table_name: &str,
+    schema: SchemaRef,
+    batches: Vec<Result<RecordBatch, ArrowError>>,
+) -> Result<(), VectorStoreError> {
+    replace_table_with_progress(db, table_name, schema, batches, &mut |_| {}).await
+}
+
+/// `replace_table`, staging one batch at a time and calling `on_batch` with
+/// the number of rows staged so far after each
+async fn replace_table_with_progress(
+    db: &Connection,
+    table_name: &str,
+    schema: SchemaRef,
+    batches: Vec<Result<RecordBatch, ArrowError>>,
+    on_batch: &mut impl FnMut(usize),
+) -> Result<(), VectorStoreError> {
+    let temp_name = format!("{}{}", TEMP_TABLE_PREFIX, table_name);
+    let batch_rows = |batch: &Result<RecordBatch, ArrowError>| batch.as_ref().map_or(0, RecordBatch::num_rows);
+
+    // A crashed earlier write may have left its staging table behind
+    drop_table_if_exists(db, &temp_name).await?;
+
+    let staged = async {
+        let mut batches = batches.into_iter();
+        let first = batches
+            .next()
+            .unwrap_or_else(|| Ok(RecordBatch::new_empty(schema.clone())))
+            .map_err(|e| VectorStoreError::Query(format!("Failed to create table: {}", e)))?;
+        let mut staged_rows = first.num_rows();
+        let temp_table = create_with_retry(&temp_name, || {
+            let reader = RecordBatchIterator::new(vec![Ok(first.clone())].into_iter(), schema.clone());
+            db.create_table(&temp_name, Box::new(reader)).mode(CreateTableMode::Overwrite).execute()
+        })
+        .await
+        .map_err(|e| VectorStoreError::Query(format!("Failed to create table: {}", e)))?;
+        on_batch(staged_rows);
+
+        for batch in batches {
+            let rows = batch_rows(&batch);
+            let reader = RecordBatchIterator::new(vec![batch].into_iter(), schema.clone());
+            temp_table
+                .add(Box::new(reader))
+                .execute()
+                .await
+                .map_err(|e| VectorStoreError::Query(format!("Failed to stage chunks: {}", e)))?;
+            staged_rows += rows;
+            on_batch(staged_rows);
+        }
+        Ok(temp_table)
+    }
+    .await;
+    let temp_table = match staged {
+        Ok(table) => table,
+        Err(e) => {
+            drop_staging_table(db, &temp_name).await;
+            return Err(e);
+        }
+    };
+
+    let swap_result = create_with_retry(table_name, || async {
+        let staged_rows = temp_table.query().execute().await?;
+        db.create_table_streaming(table_name, staged_rows)
+            .mode(CreateTableMode::Overwrite)
+            .execute()
+            .await
+    })
+    .await
+    .map(|_| ())
+    .map_err(|e| VectorStoreError::Query(format!("Failed to replace table: {}", e)));
+
+    drop_staging_table(db, &temp_name).await;
+    swap_result
+}
+
+/// Clean up after `replace_table_with_progress`. A failure here only leaves a
+/// stale staging table behind, so it is logged rather than returned in place
+/// of the write's own result.
+async fn drop_staging_table(db: &Connection, temp_name: &str) {
+    if let Err(e) = drop_table_if_exists(db, temp_name).await {
+        warn!("{}", e);
+    }
+}
+
+/// Compare column types, ignoring nullability/naming differences of the
+/// vector list's inner field that LanceDB may normalize on write
+fn column_types_match(existing: &DataType, incoming: &DataType) -> bool {
+    match (existing, incoming) {
+        (DataType::FixedSizeList(existing_item, existing_len), DataType::FixedSizeList(incoming_item, incoming_len)) => {
+            existing_len == incoming_len && existing_item.data_type() == incoming_item.data_type()
+        }
+        _ => existing == incoming,
+    }
+}
+
+fn validate_schema(existing: &Schema, incoming: &Schema) -> Result<(), VectorStoreError> {
+    for field in incoming.fields() {
+        let existing_field = existing
+            .field_with_name(field.name())
+            .map_err(|_| VectorStoreError::SchemaMismatch(format!("Schema mismatch: column '{}' does not exist in the table", field.name())))?;
+
+        if !column_types_match(existing_field.data_type(), field.data_type()) {
+            return Err(VectorStoreError::SchemaMismatch(format!(
+                "Schema mismatch: column '{}' is {} in the table but {} in the new chunks",
+                field.name(),
+                existing_field.data_type(),
+                field.data_type()
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Add rows to an existing table, creating it when it doesn't exist yet
+async fn append_to_table(
+    db: &Connection,
+    table_name: &str,
+    schema: SchemaRef,
+    batch: RecordBatch,
+) -> Result<(), VectorStoreError> {
+    let table_names = db
+        .table_names()
+        .execute()
+        .await
+        .map_err(|e| VectorStoreError::Query(format!("Failed to list tables: {}", e)))?;
+
+    let batches = RecordBatchIterator::new(vec![Ok(batch)].into_iter(), schema.clone());
+
+    if !table_names.iter().any(|name| name == table_name) {
+        db.create_table(table_name, Box::new(batches))
+            .execute()
+            .await
+            .map_err(|e| VectorStoreError::Query(format!("Failed to create table: {}", e)))?;
+        return Ok(());
+    }
+
+    let table = open_table(db, table_name).await?;
+
+    let existing_schema = table
+        .schema()
+        .await
+        .map_err(|e| VectorStoreError::Query(format!("Failed to read table schema: {}", e)))?;
+    validate_schema(&existing_schema, &schema)?;
+
+    table
+        .add(Box::new(batches))
+        .execute()
+        .await
+        .map_err(|e| VectorStoreError::Query(format!("Failed to append chunks: {}", e)))?;
+
+    Ok(())
+}
+
+/// Build an IVF_PQ index on the `vector` column.
+/// Returns `false` without touching the table when it has too few rows to index.
+async fn create_vector_index(table: &Table, options: &IndexOptions) -> Result<bool, VectorStoreError> {
+    let row_count = table
+        .count_rows(None)
+        .await
+        .map_err(|e| VectorStoreError::Query(format!("Failed to count rows: {}", e)))?;
+    if row_count < MIN_ROWS_FOR_INDEX {
+        return Ok(false);
+    }
+
+    let mut builder = IvfPqIndexBuilder::default().distance_type(parse_metric(options.metric.as_deref())?);
+    if let Some(num_partitions) = options.num_partitions {
+        builder = builder.num_partitions(num_partitions);
+    }
+    if let Some(num_sub_vectors) = options.num_sub_vectors {
+        builder = builder.num_sub_vectors(num_sub_vectors);
+    }
+
+    table
+        .create_index(&["vector"], Index::IvfPq(builder))
+        .replace(true)
+        .execute()
+        .await
+        .map_err(|e| VectorStoreError::Query(format!("Failed to create index: {}", e)))?;
+
+    Ok(true)
+}
+
+/// Read every chunk of a table, sorted by `chunk_index`.
+/// Vectors are only fetched when requested, otherwise they are left empty.
+async fn read_chunks(table: &Table, include_vectors: bool) -> Result<Vec<ChunkData>, VectorStoreError> {
+    let mut columns = vec!["id", "text", "chunk_index", "text_length"];
+    if include_vectors {
+        columns.push("vector");
+    }
+
+    let mut result_stream = table
+        .query()
+        .select(Select::columns(&columns))
+        .execute()
+        .await
+        .map_err(|e| VectorStoreError::Query(format!("Failed to scan table: {}", e)))?;
+
+    let mut chunks = Vec::new();
+    while let Some(batch_result) = result_stream.next().await {
+        let batch = batch_result.map_err(|e| VectorStoreError::Query(format!("Batch error: {}", e)))?;
+
+        let ids = column::<StringArray>(&batch, "id")?;
+        let texts = column::<StringArray>(&batch, "text")?;
+        let chunk_indices = column::<Int32Array>(&batch, "chunk_index")?;
+        let text_lengths = column::<Int32Array>(&batch, "text_length")?;
+        let vectors = match batch.column_by_name("vector") {
+            Some(column) => Some(
+                column
+                    .as_any()
+                    .downcast_ref::<FixedSizeListArray>()
+                    .ok_or_else(|| VectorStoreError::SchemaMismatch("Failed to downcast vector column".to_string()))?,
+            ),
+            None => None,
+        };
+
+        for i in 0..batch.num_rows() {
+            let vector = match vectors {
+                Some(vectors) => vector_at(vectors, i)?,
+                None => Vec::new(),
+            };
+
+            chunks.push(ChunkData {
+                id: ids.value(i).to_string(),
+                text: texts.value(i).to_string(),
+                vector,
+                chunk_index: chunk_indices.value(i),
+                text_length: text_lengths.value(i),
+            });
+        }
+    }
+
+    // LanceDB scans have no ORDER BY, so sort here
+    chunks.sort_by_key(|chunk| chunk.chunk_index);
+    Ok(chunks)
+}
+
+/// First `char_limit` chars of a document's text. Only the `chunk_index`
+/// column is scanned in full; texts are then fetched a window of chunks at a
+/// time, stopping once the limit is reached.
+async fn preview_document(table: &Table, char_limit: usize) -> Result<DocumentPreview, VectorStoreError> {
+    let mut result_stream = table
+        .query()
+        .select(Select::columns(&["chunk_index"]))
+        .execute()
+        .await
+        .map_err(|e| VectorStoreError::Query(format!("Failed to scan table: {}", e)))?;
+    let mut chunk_indices = Vec::new();
+    while let Some(batch_result) = result_stream.next().await {
+        let batch = batch_result.map_err(|e| VectorStoreError::Query(format!("Batch error: {}", e)))?;
+        chunk_indices.extend(column::<Int32Array>(&batch, "chunk_index")?.values().iter().copied());
+    }
+    let chunk_count = chunk_indices.len();
+    // LanceDB scans have no ORDER BY, so sort here
+    chunk_indices.sort_unstable();
+    chunk_indices.dedup();
+
+    let mut preview_text = String::new();
+    let mut preview_chars = 0;
+    for window in chunk_indices.chunks(PREVIEW_WINDOW) {
+        let (first, last) = (window[0], window[window.len() - 1]);
+        let mut result_stream = table
+            .query()
+            .select(Select::columns(&["text", "chunk_index"]))
+            .only_if(format!("chunk_index >= {} AND chunk_index <= {}", first, last))
+            .execute()
+            .await
+            .map_err(|e| VectorStoreError::Query(format!("Failed to scan table: {}", e)))?;
+        let mut texts = Vec::new();
+        while let Some(batch_result) = result_stream.next().await {
+            let batch = batch_result.map_err(|e| VectorStoreError::Query(format!("Batch error: {}", e)))?;
+            let chunk_indices = column::<Int32Array>(&batch, "chunk_index")?;
+            let batch_texts = column::<StringArray>(&batch, "text")?;
+            for i in 0..batch.num_rows() {
+                texts.push((chunk_indices.value(i), batch_texts.value(i).to_string()));
+            }
+        }
+        texts.sort_by_key(|(chunk_index, _)| *chunk_index);
+
+        for (_, text) in texts {
+            let text = if preview_text.is_empty() { text } else { format!("\n{}", text) };
+            let remaining = char_limit - preview_chars;
+            let text_chars = text.chars().count();
+            if text_chars > remaining {
+                preview_text.extend(text.chars().take(remaining));
+                return Ok(DocumentPreview { chunk_count, preview_text, truncated: true });
+            }
+            preview_text.push_str(&text);
+            preview_chars += text_chars;
+        }
+    }
+
+    Ok(DocumentPreview { chunk_count, preview_text, truncated: false })
+}
+
+/// Total size of all files below `path`; unreadable entries count as zero
+fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return 0;
+    };
+
+    entries
+        .flatten()
+        .map(|entry| match entry.metadata() {
+            Ok(metadata) if metadata.is_dir() => dir_size(&entry.path()),
+            Ok(metadata) => metadata.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+/// Ids of every indexed document, sorted. Staging and metadata tables are not
+/// documents and are left out.
+async fn list_document_ids(db: &Connection) -> Result<Vec<String>, VectorStoreError> {
+    let table_names = db
+        .table_names()
+        .execute()
+        .await
+        .map_err(|e| VectorStoreError::Query(format!("Failed to list tables: {}", e)))?;
+
+    let mut document_ids: Vec<String> = table_names.iter().filter_map(|name| document_id_for(name)).collect();
+    document_ids.sort();
+    Ok(document_ids)
+}
+
+/// Mean of every chunk vector in `table`, or `None` for an empty table
+async fn mean_vector(table: &Table) -> Result<Option<Vec<f32>>, VectorStoreError> {
+    let mut result_stream = table
+        .query()
+        .select(Select::columns(&["vector"]))
+        .execute()
+        .await
+        .map_err(|e| VectorStoreError::Query(format!("Failed to scan table: {}", e)))?;
+
+    let mut sum: Vec<f64> = Vec::new();
+    let mut rows = 0usize;
+    while let Some(batch_result) = result_stream.next().await {
+        let batch = batch_result.map_err(|e| VectorStoreError::Query(format!("Batch error: {}", e)))?;
+        let vectors = column::<FixedSizeListArray>(&batch, "vector")?;
+        for i in 0..batch.num_rows() {
+            let vector = vector_at(vectors, i)?;
+            if sum.is_empty() {
+                sum = vec![0.0; vector.len()];
+            }
+            for (total, value) in sum.iter_mut().zip(&vector) {
+                *total += f64::from(*value);
+            }
+            rows += 1;
+        }
+    }
+
+    Ok((rows > 0).then(|| sum.iter().map(|total| (total / rows as f64) as f32).collect()))
+}
+
+/// Centroid of a document's chunk vectors, computed once per table version
+/// and cached in `CENTROID_TABLE`
+async fn document_centroid(db: &Connection, document_id: &str) -> Result<Option<Vec<f32>>, VectorStoreError> {
+    let table = open_table(db, &table_name_for(document_id)).await?;
+    let table_version = table
+        .version()
+        .await
+        .map_err(|e| VectorStoreError::Query(format!("Failed to read table version: {}", e)))?;
+
+    if let Some(centroid) = read_cached_centroid(db, document_id, table_version).await? {
+        return Ok(Some(centroid));
+    }
+
+    let centroid = mean_vector(&table).await?;
+    if let Some(centroid) = &centroid {
+        // Only a cache; a failed write just means computing it again next time
+        if let Err(e) = write_cached_centroid(db, document_id, table_version, centroid).await {
+            warn!("Failed to cache centroid of '{}': {}", document_id, e);
+        }
+    }
+    Ok(centroid)
+}
+
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm = |v: &[f32]| v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norms = norm(a) * norm(b);
+    if norms == 0.0 {
+        0.0
+    } else {
+        dot / norms
+    }
+}
+
+/// Other documents ranked by how close their centroid is to `document_id`'s.
+/// Empty documents and documents embedded with a different dimension are
+/// skipped, as are ones that fail to read (logged).
+async fn similar_documents(db: &Connection, document_id: &str, top_k: usize) -> Result<Vec<SimilarDocument>, VectorStoreError> {
+    let Some(source) = document_centroid(db, document_id).await? else {
+        return Ok(Vec::new());
+    };
+
+    let mut similar = Vec::new();
+    for other_id in list_document_ids(db).await? {
+        if other_id == document_id {
+            continue;
+        }
+        match document_centroid(db, &other_id).await {
+            Ok(Some(centroid)) if centroid.len() == source.len() => similar.push(SimilarDocument {
+                score: cosine_similarity(&source, &centroid),
+                document_id: other_id,
+            }),
+            Ok(_) => {}
+            Err(e) => warn!("Skipping '{}' in similarity search: {}", other_id, e),
+        }
+    }
+
+    similar.sort_by(|a, b| b.score.total_cmp(&a.score));
+    similar.truncate(top_k);
+    Ok(similar)
+}
+
+/// Row count and on-disk size of every document table. Tables that fail to
+/// open are reported with `error` set instead of failing the whole listing.
+async fn collect_document_stats(db: &Connection) -> Result<Vec<DocumentStats>, VectorStoreError> {
+    let table_names = db
+        .table_names()
+        .execute()
+        .await
+        .map_err(|e| VectorStoreError::Query(format!("Failed to list tables: {}", e)))?;
+
+    let mut stats = Vec::new();
+    for table_name in table_names {
+        let Some(document_id) = document_id_for(&table_name) else {
+            continue;
+        };
+
+        // Local LanceDB stores each table in a `<name>.lance` directory
+        let size_bytes = dir_size(&Path::new(db.uri()).join(format!("{}.lance", table_name)));
+        let row_count = match db.open_table(&table_name).execute().await {
+            Ok(table) => table
+                .count_rows(None)
+                .await
+                .map_err(|e| VectorStoreError::Query(format!("Failed to count rows: {}", e))),
+            Err(e) => Err(VectorStoreError::Query(format!("Failed to open table: {}", e))),
+        };
+
+        stats.push(match row_count {
+            Ok(row_count) => DocumentStats {
+                document_id: document_id.clone(),
+                row_count: row_count as u64,
+                size_bytes,
+                error: None,
+            },
+            Err(error) => DocumentStats {
+                document_id,
+                row_count: 0,
+                size_bytes,
+                error: Some(error.to_string()),
+            },
+        });
+    }
+
+    Ok(stats)
+}
+
+fn total_stats(documents: &[DocumentStats]) -> TotalStats {
+    TotalStats {
+        document_count: documents.len(),
+        chunk_count: documents.iter().map(|document| document.row_count).sum(),
+        errors: documents
+            .iter()
+            .filter_map(|document| {
+                let error = document.error.as_ref()?;
+                Some(format!("{}: {}", document.document_id, error))
+            })
+            .collect(),
+    }
+}
+
+/// Path recorded by `vector_store_initialize`, `None` before it has succeeded
+pub async fn initialized_storage_path(state: &StdArc<Mutex<VectorStoreState>>) -> Option<String> {
+    let db_path = state.lock().await.db_path.clone();
+    let stored_path = db_path.lock().await.clone();
+    stored_path
+}
+
+/// Use the explicitly passed path, falling back to the one recorded by
+/// `vector_store_initialize` so the state is the single source of truth.
+/// `path` resolved the way `vector_store_initialize` stores it, so an explicit
+/// path shares the cached connection. A path that can't be resolved (e.g. one
+/// that doesn't exist yet) is used as given.
+fn canonical_storage_path(path: String) -> String {
+    match std::fs::canonicalize(&path) {
+        Ok(resolved) => resolved.to_string_lossy().into_owned(),
+        Err(_) => path,
+    }
+}
+
+async fn resolve_storage_path(
+    state: &StdArc<Mutex<VectorStoreState>>,
+    storage_path: Option<String>,
+) -> Result<String, VectorStoreError> {
+    if let Some(path) = storage_path {
+        return Ok(canonical_storage_path(path));
+    }
+
+    initialized_storage_path(state).await.ok_or(VectorStoreError::NotInitialized)
+}
+
+#[tauri::command]
+pub async fn vector_store_set_concurrency_limit(
+    limit: usize,
+    state: State<'_, StdArc<Mutex<VectorStoreState>>>,
+) -> Result<String, VectorStoreError> {
+    if limit == 0 {
+        return Err(VectorStoreError::InvalidArgument("Concurrency limit must be at least 1".to_string()));
+    }
+
+    // Operations already holding a permit finish against the old semaphore
+    let mut store_state = state.lock().await;
+    store_state.operation_limit = StdArc::new(Semaphore::new(limit));
+
+    Ok(format!("Concurrency limit set to {}", limit))
+}
+
+/// Create the storage directory if needed and make sure it accepts writes,
+/// returning its canonical absolute path. Failures here are `Io` errors so
+/// they can be told apart from LanceDB failing to connect.
+fn prepare_storage_dir(storage_path: &str) -> Result<PathBuf, VectorStoreError> {
+    std::fs::create_dir_all(storage_path)
+        .map_err(|e| VectorStoreError::Io(format!("Failed to create storage directory {}: {}", storage_path, e)))?;
+    let storage_dir = std::fs::canonicalize(storage_path)
+        .map_err(|e| VectorStoreError::Io(format!("Failed to resolve storage directory {}: {}", storage_path, e)))?;
+
+    let sentinel = storage_dir.join(WRITE_PROBE_FILE);
+    std::fs::write(&sentinel, b"")
+        .map_err(|e| VectorStoreError::Io(format!("Storage directory {} is not writable: {}", storage_dir.display(), e)))?;
+    let _ = std::fs::remove_file(&sentinel);
+
+    Ok(storage_dir)
+}
+
+/// Delete `temp_*.arrow` files an interrupted ingest left in the storage
+/// directory, returning how many were removed
+fn remove_stale_temp_files(storage_dir: &Path) -> usize {
+    let Ok(entries) = std::fs::read_dir(storage_dir) else {
+        return 0;
+    };
+
+    entries
+        .flatten()
+        .filter(|entry| {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            name.starts_with(TEMP_TABLE_PREFIX) && name.ends_with(".arrow")
+        })
+        .filter(|entry| entry.file_type().is_ok_and(|file_type| file_type.is_file()))
+        .filter(|entry| std::fs::remove_file(entry.path()).is_ok())
+        .count()
+}
+
+#[tauri::command]
+pub async fn vector_store_initialize(
+    storage_path: String,
+    state: State<'_, StdArc<Mutex<VectorStoreState>>>,
+) -> Result<String, VectorStoreError> {
+    let _permit = acquire_operation_permit(&state).await?;
+
+    let storage_path = prepare_storage_dir(&storage_path)?.to_string_lossy().into_owned();
+
+    // Test connection
+    let db = state_connection(&state, &storage_path).await?;
+
+    let removed = remove_stale_temp_files(Path::new(&storage_path));
+    if removed > 0 {
+        info!("Removed {} stale temp file(s) from {}", removed, storage_path);
+    }
+
+    write_version_marker(&db).await?;
+
+    // Only remember the path once it is known to work, replacing any
+    // connection cached for a previous path
+    let store_state = state.lock().await;
+    *store_state.db_path.lock().await = Some(storage_path.clone());
+    *store_state.connection.lock().await = Some((storage_path.clone(), db));
+
+    Ok(format!("LanceDB initialized at: {}", storage_path))
+}
+
+/// Open a document table and run one nearest-neighbour query against it, so
+/// its manifest and any vector index are loaded before the first real search
+async fn warm_table(db: &Connection, table_name: &str) -> Result<(), VectorStoreError> {
+    let table = open_table(db, table_name).await?;
+    table
+        .list_indices()
+        .await
+        .map_err(|e| VectorStoreError::Query(format!("Failed to list indices: {}", e)))?;
+
+    let vector_dim = table_vector_dim(&table).await?;
+    let mut result_stream = search_batches(&table, vec![0.0; vector_dim as usize], 1, DistanceType::L2, None).await?;
+    while let Some(batch_result) = result_stream.next().await {
+        batch_result.map_err(|e| VectorStoreError::Query(format!("Batch error: {}", e)))?;
+    }
+    Ok(())
+}
+
+/// Warm each of `table_names` in turn, each under its own operation permit so
+/// searches issued meanwhile are not held up behind the whole warm-up
+async fn warm_tables(
+    state: &StdArc<Mutex<VectorStoreState>>,
+    db: &Connection,
+    storage_path: String,
+    table_names: Vec<String>,
+    token: &CancellationToken,
+) -> WarmupSummary {
+    let mut summary = WarmupSummary {
+        storage_path,
+        tables: table_names.len(),
+        warmed: 0,
+        failed: Vec::new(),
+        cancelled: false,
+    };
+
+    for table_name in table_names {
+        let warmed = tokio::select! {
+            _ = token.cancelled() => None,
+            warmed = async {
+                let _permit = acquire_operation_permit(state).await?;
+                warm_table(db, &table_name).await
+            } => Some(warmed),
+        };
+        match warmed {
+            Some(Ok(())) => summary.warmed += 1,
+            Some(Err(e)) => {
+                warn!("Failed to warm up {}: {}", table_name, e);
+                summary.failed.extend(document_id_for(&table_name));
+            }
+            None => {
+                summary.cancelled = true;
+                break;
+            }
+        }
+    }
+
+    summary
+}
+
+/// Open the connection and list the store's tables now, then (unless
+/// `touch_tables` is `false`) warm every document table in the background and
+/// emit `vector_store://warmup-done`. Meant to be called right after
+/// `vector_store_initialize` so the first search doesn't pay for lazy loading.
+/// Starting a new warm-up cancels the previous one.
+#[tauri::command]
+pub async fn vector_store_warmup(
+    touch_tables: Option<bool>,
+    storage_path: Option<String>,
+    app: AppHandle,
+    state: State<'_, StdArc<Mutex<VectorStoreState>>>,
+) -> Result<String, VectorStoreError> {
+    let storage_path = resolve_storage_path(&state, storage_path).await?;
+    let table_names = {
+        let _permit = acquire_operation_permit(&state).await?;
+        let db = state_connection(&state, &storage_path).await?;
+        db.table_names()
+            .execute()
+            .await
+            .map_err(|e| VectorStoreError::Query(format!("Failed to list tables: {}", e)))?
+    };
+    let table_names: Vec<String> = table_names
+        .into_iter()
+        .filter(|name| document_id_for(name).is_some())
+        .collect();
+
+    let token = CancellationToken::new();
+    let generation = {
+        let mut store_state = state.lock().await;
+        let generation = store_state.next_warmup_generation;
+        store_state.next_warmup_generation += 1;
+        if let Some((_, previous)) = store_state.warmup.replace((generation, token.clone())) {
+            previous.cancel();
+        }
+        generation
+    };
+
+    let touch_tables = touch_tables.unwrap_or(true);
+    let message = if touch_tables {
+        format!("Warming up {} document table(s)", table_names.len())
+    } else {
+        "Connection ready".to_string()
+    };
+    let table_names = if touch_tables { table_names } else { Vec::new() };
+
+    let state = state.inner().clone();
+    tauri::async_runtime::spawn(async move {
+        let summary = match state_connection(&state, &storage_path).await {
+            Ok(db) => warm_tables(&state, &db, storage_path, table_names, &token).await,
+            Err(e) => {
+                warn!("Warm-up lost its connection: {}", e);
+                let document_ids = table_names.iter().filter_map(|name| document_id_for(name)).collect();
+                WarmupSummary { storage_path, tables: table_names.len(), warmed: 0, failed: document_ids, cancelled: false }
+            }
+        };
+        info!(
+            "Warm-up finished: {}/{} table(s) warmed{}",
+            summary.warmed,
+            summary.tables,
+            if summary.cancelled { " (cancelled)" } else { "" }
+        );
+
+        {
+            // Leave the token of a newer warm-up in place
+            let mut store_state = state.lock().await;
+            if store_state.warmup.as_ref().is_some_and(|(current, _)| *current == generation) {
+                store_state.warmup = None;
+            }
+        }
+
+        if let Err(e) = app.emit(WARMUP_DONE_EVENT, &summary) {
+            warn!("Failed to emit warm-up summary: {}", e);
+        }
+    });
+
+    Ok(message)
+}
+
+/// Stop the running warm-up, if any. Returns whether there was one to stop.
+#[tauri::command]
+pub async fn vector_store_cancel_warmup(
+    state: State<'_, StdArc<Mutex<VectorStoreState>>>,
+) -> Result<bool, VectorStoreError> {
+    match state.lock().await.warmup.take() {
+        Some((_, token)) => {
+            token.cancel();
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+/// Dimension new chunks of `document_id` are checked against: the one in its
+/// metadata, else `store_dim`. Comes with whose dimension it is, for errors.
+async fn expected_ingest_dim(
+    db: &Connection,
+    document_id: &str,
+    store_dim: Option<i32>,
+) -> Result<Option<(&'static str, i32)>, VectorStoreError> {
+    let document_dim = read_document_metadata(db, document_id).await?.and_then(|metadata| metadata.vector_dim);
+    Ok(match (document_dim, store_dim) {
+        (Some(dim), _) => Some(("document", dim)),
+        (None, Some(dim)) => Some(("store", dim)),
+        (None, None) => None,
+    })
+}
+
+/// Whether every non-empty document table except `document_id`'s holds
+/// `vector_dim`-dim vectors
+async fn other_documents_have_dim(db: &Connection, document_id: &str, vector_dim: i32) -> Result<bool, VectorStoreError> {
+    let own_table_name = table_name_for(document_id);
+    let table_names = db
+        .table_names()
+        .execute()
+        .await
+        .map_err(|e| VectorStoreError::Query(format!("Failed to list tables: {}", e)))?;
+
+    for table_name in table_names {
+        if table_name == own_table_name || document_id_for(&table_name).is_none() {
+            continue;
+        }
+        let table = open_table(db, &table_name).await?;
+        let row_count = table
+            .count_rows(None)
+            .await
+            .map_err(|e| VectorStoreError::Query(format!("Failed to count rows: {}", e)))?;
+        if row_count > 0 && table_vector_dim(&table).await? != vector_dim {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Replace every chunk of a document with freshly embedded ones. Unlike
+/// `vector_store_add_chunks` the dimension may change: re-embedding with a
+/// new model is the point, so the new dimension becomes the store-wide one.
+async fn reindex_document(
+    db: &Connection,
+    document_id: &str,
+    chunks: &[ChunkData],
+    embedding_model: Option<String>,
+) -> Result<ReindexReport, VectorStoreError> {
+    let table_name = table_name_for(document_id);
+    let vector_dim = batch_vector_dim(chunks, None)?;
+
+    let old_count = if table_exists(db, &table_name).await? {
+        open_table(db, &table_name)
+            .await?
+            .count_rows(None)
+            .await
+            .map_err(|e| VectorStoreError::Query(format!("Failed to count rows: {}", e)))?
+    } else {
+        0
+    };
+
+    // The staged swap in `replace_table` never leaves the document empty
+    let (schema, batch) = build_chunk_batch(chunks, vector_dim)?;
+    replace_table(db, &table_name, schema, vec![Ok(batch)]).await?;
+
+    // The replaced table starts without an index
+    if chunks.len() >= AUTO_INDEX_ROW_THRESHOLD {
+        let table = open_table(db, &table_name).await?;
+        create_vector_index(&table, &IndexOptions::default()).await?;
+    }
+
+    // Other documents keep their old embeddings, so the store-wide dimension
+    // only follows when no document is left at another one; otherwise the
+    // document's own metadata records its dimension
+    if !chunks.is_empty() && other_documents_have_dim(db, document_id, vector_dim).await? {
+        write_store_setting(db, VECTOR_DIM_KEY, &vector_dim.to_string()).await?;
+    }
+    let metadata = read_document_metadata(db, document_id).await?.unwrap_or_default();
+    let metadata = DocumentMetadata {
+        vector_dim: (!chunks.is_empty()).then_some(vector_dim),
+        embedding_model: embedding_model.or(metadata.embedding_model),
+        ..metadata
+    };
+    write_document_metadata(db, document_id, &metadata).await?;
+
+    Ok(ReindexReport {
+        document_id: document_id.to_string(),
+        old_count,
+        new_count: chunks.len(),
+    })
+}
+
+/// Ids of chunks whose caller-supplied `text_length` matches none of the
+/// usual lengths of their text: UTF-8 bytes, chars, or UTF-16 code units (a
+/// JavaScript string's `length`)
+fn mismatched_text_lengths(chunks: &[ChunkData]) -> Vec<&str> {
+    chunks
+        .iter()
+        .filter(|chunk| {
+            let text_length = chunk.text_length as usize;
+            text_length != chunk.text.len()
+                && text_length != chunk.text.chars().count()
+                && text_length != chunk.text.encode_utf16().count()
+        })
+        .map(|chunk| chunk.id.as_str())
+        .collect()
+}
+
+/// Drop chunks with a `text_length` below `min_text_length`, such as stray
+/// headings, returning the kept chunks and how many were dropped
+fn filter_short_chunks(chunks: Vec<ChunkData>, min_text_length: Option<i32>) -> (Vec<ChunkData>, usize) {
+    let Some(min_text_length) = min_text_length else {
+        return (chunks, 0);
+    };
+    let total = chunks.len();
+    let kept: Vec<ChunkData> = chunks.into_iter().filter(|chunk| chunk.text_length >= min_text_length).collect();
+    let skipped = total - kept.len();
+    (kept, skipped)
+}
+
+/// Write `chunks` to `table_name` in batches of `batch_size`, calling
+/// `on_batch` with the number of chunks written so far after each. Appended
+/// batches are committed one by one; a replacement only becomes visible once
+/// every batch is staged. Errors say how many chunks were committed.
+async fn ingest_chunks(
+    db: &Connection,
+    table_name: &str,
+    chunks: &[ChunkData],
+    vector_dim: i32,
+    append: bool,
+    batch_size: usize,
+    on_batch: &mut impl FnMut(usize),
+) -> Result<(), VectorStoreError> {
+    let total = chunks.len();
+    // An empty ingestion still writes one (empty) batch so the table exists
+    let slices: Vec<&[ChunkData]> = if chunks.is_empty() {
+        vec![chunks]
+    } else {
+        chunks.chunks(batch_size).collect()
+    };
+
+    if append {
+        let mut committed = 0;
+        for batch_chunks in slices {
+            let appended = async {
+                let (schema, batch) = build_chunk_batch(batch_chunks, vector_dim)?;
+                append_to_table(db, table_name, schema, batch).await
+            }
+            .await;
+            appended.map_err(|e| e.with_context(&format!("{} of {} chunks committed", committed, total)))?;
+            committed += batch_chunks.len();
+            on_batch(committed);
+        }
+        return Ok(());
+    }
+
+    // Replace atomically so a failed write never leaves the document without a table
+    let replaced = async {
+        let batches = slices
+            .into_iter()
+            .map(|batch_chunks| build_chunk_batch(batch_chunks, vector_dim).map(|(_, batch)| batch))
+            .collect::<Result<Vec<_>, _>>()?;
+        replace_table_with_progress(
+            db,
+            table_name,
+            chunk_schema(vector_dim),
+            batches.into_iter().map(Ok).collect(),
+            on_batch,
+        )
+        .await
+    }
+    .await;
+    replaced.map_err(|e| e.with_context(&format!("0 of {} chunks committed, existing chunks kept", total)))
+}
+
+/// Body of `vector_store_add_chunks`, reporting progress through `on_progress`
+/// instead of events
+#[allow(clippy::too_many_arguments)]
+async fn add_chunks(
+    state: &StdArc<Mutex<VectorStoreState>>,
+    document_id: String,
+    chunks: Vec<ChunkData>,
+    storage_path: Option<String>,
+    allow_dimension_mismatch: Option<bool>,
+    append: Option<bool>,
+    expected_dim: Option<i32>,
+    batch_size: Option<usize>,
+    min_text_length: Option<i32>,
+    normalize: Option<bool>,
+    on_progress: &mut impl FnMut(&IngestProgress),
+) -> Result<AddChunksReport, VectorStoreError> {
+    let storage_path = resolve_storage_path(state, storage_path).await?;
+    let _permit = acquire_operation_permit(state).await?;
+
+    let db = state_connection(state, &storage_path).await?;
+
+    let table_name = table_name_for(&document_id);
+    let _table_lock = lock_table(state, &table_name).await;
+    migrate_legacy_table(&db, &document_id).await?;
+
+    // text_length is caller-supplied, so a wrong value is worth flagging
+    let mismatched = mismatched_text_lengths(&chunks);
+    if !mismatched.is_empty() {
+        warn!(
+            "{} chunk(s) of {} have a text_length that doesn't match their text: {:?}",
+            mismatched.len(),
+            document_id,
+            mismatched
+        );
+    }
+    let (mut chunks, skipped) = filter_short_chunks(chunks, min_text_length);
+
+    // Every chunk must agree on the dimension (384 for an empty batch)
+    let vector_dim = batch_vector_dim(&chunks, expected_dim)?;
+    if normalize.unwrap_or(false) {
+        normalize_vectors(&mut chunks);
+    }
+
+    // Mixing embedding dimensions across documents is almost always a model
+    // misconfiguration, so check against the dimension recorded store-wide.
+    // A document reindexed with another model keeps its own dimension.
+    let store_dim = read_store_setting(&db, VECTOR_DIM_KEY)
+        .await?
+        .and_then(|value| value.parse::<i32>().ok());
+    if let Some((scope, expected)) = expected_ingest_dim(&db, &document_id, store_dim).await? {
+        if !chunks.is_empty() && expected != vector_dim && !allow_dimension_mismatch.unwrap_or(false) {
+            return Err(VectorStoreError::SchemaMismatch(format!(
+                "Embedding dimension mismatch: {} expects {}-dim vectors, got {}",
+                scope, expected, vector_dim
+            )));
+        }
+    }
+
+    let batch_size = batch_size.unwrap_or(DEFAULT_INGEST_BATCH_SIZE);
+    if batch_size == 0 {
+        return Err(VectorStoreError::InvalidArgument("batch_size must be greater than zero".to_string()));
+    }
+
+    // Streamed ingestion (append) keeps earlier batches for this document
+    let total = chunks.len();
+    let mut emit_progress = |inserted: usize| {
+        on_progress(&IngestProgress { document_id: document_id.clone(), inserted, total });
+    };
+    ingest_chunks(
+        &db,
+        &table_name,
+        &chunks,
+        vector_dim,
+        append.unwrap_or(false),
+        batch_size,
+        &mut emit_progress,
+    )
+    .await?;
+
+    // Large documents get an ANN index once, the first time they cross the threshold
+    let table = open_table(&db, &table_name).await?;
+    let row_count = table
+        .count_rows(None)
+        .await
+        .map_err(|e| VectorStoreError::Query(format!("Failed to count rows: {}", e)))?;
+    if row_count >= AUTO_INDEX_ROW_THRESHOLD {
+        let indices = table
+            .list_indices()
+            .await
+            .map_err(|e| VectorStoreError::Query(format!("Failed to list indices: {}", e)))?;
+        if indices.is_empty() {
+            create_vector_index(&table, &IndexOptions::default()).await?;
+        }
+    }
+
+    // The first non-empty ingestion decides the store-wide dimension
+    if store_dim.is_none() && !chunks.is_empty() {
+        write_store_setting(&db, VECTOR_DIM_KEY, &vector_dim.to_string()).await?;
+    }
+
+    // Record the dimension alongside the document's other metadata
+    if !chunks.is_empty() {
+        let metadata = read_document_metadata(&db, &document_id).await?.unwrap_or_default();
+        if metadata.vector_dim != Some(vector_dim) {
+            let metadata = DocumentMetadata { vector_dim: Some(vector_dim), ..metadata };
+            write_document_metadata(&db, &document_id, &metadata).await?;
+        }
+    }
+
+    Ok(AddChunksReport { table_name, inserted: chunks.len(), skipped })
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn vector_store_add_chunks(
+    document_id: String,
+    chunks: Vec<ChunkData>,
+    storage_path: Option<String>,
+    allow_dimension_mismatch: Option<bool>,
+    append: Option<bool>,
+    expected_dim: Option<i32>,
+    batch_size: Option<usize>,
+    min_text_length: Option<i32>,
+    normalize: Option<bool>,
+    app: AppHandle,
+    state: State<'_, StdArc<Mutex<VectorStoreState>>>,
+) -> Result<AddChunksReport, VectorStoreError> {
+    let mut emit_progress = |progress: &IngestProgress| {
+        if let Err(e) = app.emit(PROGRESS_EVENT, progress) {
+            warn!("Failed to emit progress: {}", e);
+        }
+    };
+    add_chunks(
+        &state,
+        document_id,
+        chunks,
+        storage_path,
+        allow_dimension_mismatch,
+        append,
+        expected_dim,
+        batch_size,
+        min_text_length,
+        normalize,
+        &mut emit_progress,
+    )
+    .await
+}
+
+/// Create a document from `chunks`, or replace all of an existing document's
+/// chunks through the staged swap of `replace_table`. Metadata recorded for
+/// the document is kept.
+async fn upsert_document(
+    db: &Connection,
+    document_id: &str,
+    chunks: &[ChunkData],
+) -> Result<UpsertReport, VectorStoreError> {
+    let table_name = table_name_for(document_id);
+
+    // Checked against the document's own dimension when it was reindexed with
+    // another model, and also used for an empty upsert's table
+    let store_dim = read_store_setting(db, VECTOR_DIM_KEY)
+        .await?
+        .and_then(|value| value.parse::<i32>().ok());
+    let expected_dim = expected_ingest_dim(db, document_id, store_dim).await?.map(|(_, dim)| dim);
+    let vector_dim = batch_vector_dim(chunks, expected_dim)?;
+
+    let action = if table_exists(db, &table_name).await? { UpsertAction::Replaced } else { UpsertAction::Created };
+    let (schema, batch) = build_chunk_batch(chunks, vector_dim)?;
+    replace_table(db, &table_name, schema, vec![Ok(batch)]).await?;
+
+    // The replaced table starts without an index
+    if chunks.len() >= AUTO_INDEX_ROW_THRESHOLD {
+        let table = open_table(db, &table_name).await?;
+        create_vector_index(&table, &IndexOptions::default()).await?;
+    }
+
+    if !chunks.is_empty() {
+        if store_dim.is_none() {
+            write_store_setting(db, VECTOR_DIM_KEY, &vector_dim.to_string()).await?;
+        }
+        let metadata = read_document_metadata(db, document_id).await?.unwrap_or_default();
+        if metadata.vector_dim != Some(vector_dim) {
+            let metadata = DocumentMetadata { vector_dim: Some(vector_dim), ..metadata };
+            write_document_metadata(db, document_id, &metadata).await?;
+        }
+    }
+
+    Ok(UpsertReport { action, row_count: chunks.len() })
+}
+
+/// Index a document whether or not it exists yet: its chunks end up exactly
+/// `chunks`, so calling this again with the same chunks changes nothing
+#[tauri::command]
+pub async fn vector_store_upsert_chunks(
+    document_id: String,
+    chunks: Vec<ChunkData>,
+    storage_path: Option<String>,
+    state: State<'_, StdArc<Mutex<VectorStoreState>>>,
+) -> Result<UpsertReport, VectorStoreError> {
+    let storage_path = resolve_storage_path(&state, storage_path).await?;
+    let _permit = acquire_operation_permit(&state).await?;
+
+    let db = state_connection(&state, &storage_path).await?;
+
+    let _table_lock = lock_table(&state, &table_name_for(&document_id)).await;
+    migrate_legacy_table(&db, &document_id).await?;
+
+    upsert_document(&db, &document_id, &chunks).await
+}
+
+/// Swap a document's chunks for ones re-embedded with a new model
+#[tauri::command]
+pub async fn vector_store_reindex(
+    document_id: String,
+    new_chunks: Vec<ChunkData>,
+    embedding_model: Option<String>,
+    storage_path: Option<String>,
+    state: State<'_, StdArc<Mutex<VectorStoreState>>>,
+) -> Result<ReindexReport, VectorStoreError> {
+    let storage_path = resolve_storage_path(&state, storage_path).await?;
+    let _permit = acquire_operation_permit(&state).await?;
+
+    let db = state_connection(&state, &storage_path).await?;
+
+    let _table_lock = lock_table(&state, &table_name_for(&document_id)).await;
+    migrate_legacy_table(&db, &document_id).await?;
+
+    reindex_document(&db, &document_id, &new_chunks, embedding_model).await
+}
+
+/// Return the embedding dimension enforced across the store, if one has been recorded
+#[tauri::command]
+pub async fn vector_store_get_expected_dimension(
+    storage_path: Option<String>,
+    state: State<'_, StdArc<Mutex<VectorStoreState>>>,
+) -> Result<Option<i32>, VectorStoreError> {
+    let storage_path = resolve_storage_path(&state, storage_path).await?;
+    let _permit = acquire_operation_permit(&state).await?;
+
+    let db = state_connection(&state, &storage_path).await?;
+
+    Ok(read_store_setting(&db, VECTOR_DIM_KEY)
+        .await?
+        .and_then(|value| value.parse::<i32>().ok()))
+}
+
+/// Map the metric name accepted by the search command to LanceDB's distance type
+fn parse_metric(metric: Option<&str>) -> Result<DistanceType, VectorStoreError> {
+    match metric.map(|m| m.to_lowercase()).as_deref() {
+        None | Some("l2") => Ok(DistanceType::L2),
+        Some("cosine") => Ok(DistanceType::Cosine),
+        Some("dot") => Ok(DistanceType::Dot),
+        Some(other) => Err(VectorStoreError::InvalidArgument(format!(
+            "Unsupported distance metric '{}': expected l2, cosine, or dot",
+            other
+        ))),
+    }
+}
+
+/// Convert a LanceDB distance into a higher-is-better score
+fn distance_to_score(distance: f32, metric: DistanceType) -> f32 {
+    match metric {
+        // LanceDB reports cosine as 1 - cos(a, b) and dot as 1 - a·b
+        DistanceType::Cosine | DistanceType::Dot => 1.0 - distance,
+        _ => 1.0 / (1.0 + distance),
+    }
+}
+
+/// Alternative score functions a search can ask for in place of the per-metric
+/// default of `distance_to_score`. Each depends on the distance alone, so
+/// scores are comparable across metrics and reproducible from `distance`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ScoreMode {
+    /// `1 / (1 + distance)`, in (0, 1] for non-negative distances
+    Inverse,
+    /// `-distance`, preserving the spacing between distances
+    Negative,
+    /// `exp(-distance)`, in (0, 1] for non-negative distances and falling off
+    /// faster than `Inverse`
+    ExpDecay,
+}
+
+impl ScoreMode {
+    fn score(self, distance: f32) -> f32 {
+        match self {
+            ScoreMode::Inverse => 1.0 / (1.0 + distance),
+            ScoreMode::Negative => -distance,
+            ScoreMode::ExpDecay => (-distance).exp(),
+        }
+    }
+}
+
+fn parse_score_mode(score_mode: Option<&str>) -> Result<Option<ScoreMode>, VectorStoreError> {
+    match score_mode.map(|m| m.to_lowercase()).as_deref() {
+        None => Ok(None),
+        Some("inverse") => Ok(Some(ScoreMode::Inverse)),
+        Some("negative") => Ok(Some(ScoreMode::Negative)),
+        Some("exp_decay") => Ok(Some(ScoreMode::ExpDecay)),
+        Some(other) => Err(VectorStoreError::InvalidArgument(format!(
+            "Unsupported score mode '{}': expected inverse, negative, or exp_decay",
+            other
+        ))),
+    }
+}
+
+/// Recompute every score from its raw distance with `score_mode`
+fn apply_score_mode(results: &mut [VectorSearchResult], score_mode: Option<ScoreMode>) {
+    if let Some(score_mode) = score_mode {
+        for result in results {
+            result.score = score_mode.score(result.distance);
+        }
+    }
+}
+
+/// Split a SQL filter into its top-level AND/OR clauses
+fn split_filter_clauses(filter: &str) -> Vec<String> {
+    let mut clauses = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+    for token in filter.split_whitespace() {
+        if token.eq_ignore_ascii_case("and") || token.eq_ignore_ascii_case("or") {
+            if !current.is_empty() {
+                clauses.push(current.join(" "));
+                current.clear();
+            }
+        } else {
+            current.push(token);
+        }
+    }
+    if !current.is_empty() {
+        clauses.push(current.join(" "));
+    }
+    clauses
+}
+
+/// Turn a failed filtered query into an error naming the clause LanceDB rejected
+async fn describe_filter_error(table: &Table, filter: &str, error: &str) -> String {
+    for clause in split_filter_clauses(filter) {
+        if table.count_rows(Some(clause.clone())).await.is_err() {
+            return format!("Invalid filter clause '{}' in '{}': {}", clause, filter, error);
+        }
+    }
+    format!("Invalid filter '{}': {}", filter, error)
+}
+
+/// Length of the table's `vector` column
+async fn table_vector_dim(table: &Table) -> Result<i32, VectorStoreError> {
+    let schema = table
+        .schema()
+        .await
+        .map_err(|e| VectorStoreError::Query(format!("Failed to read table schema: {}", e)))?;
+    let field = schema
+        .field_with_name("vector")
+        .map_err(|_| VectorStoreError::SchemaMismatch("No vector column in table".to_string()))?;
+
+    match field.data_type() {
+        DataType::FixedSizeList(_, dim) => Ok(*dim),
+        other => Err(VectorStoreError::SchemaMismatch(format!("Unexpected vector column type: {}", other))),
+    }
+}
+
+/// Run a nearest-neighbour query, returning LanceDB's batches of matching
+/// rows with their `_distance`
+async fn search_batches(
+    table: &Table,
+    query_embedding: Vec<f32>,
+    top_k: usize,
+    metric: DistanceType,
+    filter: Option<&str>,
+) -> Result<SendableRecordBatchStream, VectorStoreError> {
+    if query_embedding.is_empty() {
+        return Err(VectorStoreError::InvalidArgument("Query embedding is empty".to_string()));
+    }
+    let expected_dim = table_vector_dim(table).await?;
+    if query_embedding.len() != expected_dim as usize {
+        return Err(VectorStoreError::SchemaMismatch(format!(
+            "Query embedding dimension mismatch: expected {}-dim vector, got {}",
+            expected_dim,
+            query_embedding.len()
+        )));
+    }
+
+    // Perform vector search
+    let mut query = table
+        .query()
+        .nearest_to(query_embedding)
+        .map_err(|e| VectorStoreError::Query(format!("Query failed: {}", e)))?
+        .distance_type(metric)
+        .limit(top_k);
+    if let Some(filter) = filter {
+        query = query.only_if(filter);
+    }
+
+    match query.execute().await {
+        Ok(stream) => Ok(stream),
+        Err(e) => Err(match filter {
+            Some(filter) => VectorStoreError::InvalidArgument(
+                describe_filter_error(table, filter, &e.to_string()).await,
+            ),
+            None => VectorStoreError::Query(format!("Search failed: {}", e)),
+        }),
+    }
+}
+
+async fn search_table(
+    table: &Table,
+    query_embedding: Vec<f32>,
+    top_k: usize,
+    metric: DistanceType,
+    filter: Option<&str>,
+    include_vectors: bool,
+) -> Result<Vec<VectorSearchResult>, VectorStoreError> {
+    let mut result_stream =
+        search_batches(table, query_embedding, top_k.saturating_add(TIE_BREAK_OVERFETCH), metric, filter).await?;
+
+    let mut search_results = Vec::new();
+    
+    // Convert results to our format
+    while let Some(batch_result) = result_stream.next().await {
+        let batch = batch_result.map_err(|e| VectorStoreError::Query(format!("Batch error: {}", e)))?;
+        
+        // Look columns up by name so the code doesn't depend on column order
+        let ids = column::<StringArray>(&batch, "id")?;
+            
+        let texts = column::<StringArray>(&batch, "text")?;
+
+        let chunk_indices = column::<Int32Array>(&batch, "chunk_index")?;
+
+        let text_lengths = column::<Int32Array>(&batch, "text_length")?;
+            
+        let distances = column::<Float32Array>(&batch, "_distance")?;
+
+        let vectors = if include_vectors {
+            Some(column::<FixedSizeListArray>(&batch, "vector")?)
+        } else {
+            None
+        };
+
+        for i in 0..batch.num_rows() {
+            let distance = distances.value(i);
+            let score = distance_to_score(distance, metric);
+            let vector = match vectors {
+                Some(vectors) => Some(vector_at(vectors, i)?),
+                None => None,
+            };
+
+            search_results.push(VectorSearchResult {
+                id: ids.value(i).to_string(),
+                text: texts.value(i).to_string(),
+                score,
+                distance,
+                chunk_index: chunk_indices.value(i),
+                text_length: text_lengths.value(i),
+                vector,
+            });
+        }
+    }
+
+    sort_search_results(&mut search_results);
+    search_results.truncate(top_k);
+    Ok(search_results)
+}
+
+/// Nearest first, breaking distance ties by `chunk_index` and then `id` so
+/// the same query always returns the same order
+fn sort_search_results(results: &mut [VectorSearchResult]) {
+    results.sort_by(|a, b| {
+        a.distance
+            .total_cmp(&b.distance)
+            .then(a.chunk_index.cmp(&b.chunk_index))
+            .then_with(|| a.id.cmp(&b.id))
+    });
+}
+
+/// Lowercased, de-duplicated terms of a keyword query
+fn keyword_terms(query_text: &str) -> Vec<String> {
+    let mut terms: Vec<String> = Vec::new();
+    for term in query_text.split_whitespace().map(str::to_lowercase) {
+        if !terms.contains(&term) {
+            terms.push(term);
+        }
+    }
+    terms
+}
+
+/// Filter matching chunks whose text contains any of the terms
+fn keyword_filter(terms: &[String]) -> String {
+    terms
+        .iter()
+        .map(|term| format!("lower(text) LIKE '%{}%' ESCAPE '\\'", escape_like(term)))
+        .collect::<Vec<_>>()
+        .join(" OR ")
+}
+
+/// `term` as a literal inside a quoted LIKE pattern, with `\` as the escape
+/// character so `%` and `_` in the query match themselves
+fn escape_like(term: &str) -> String {
+    let mut escaped = String::with_capacity(term.len());
+    for c in term.chars() {
+        match c {
+            '\\' | '%' | '_' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\'' => escaped.push_str("''"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Fraction of the query terms that appear in `text`
+fn keyword_score(text: &str, terms: &[String]) -> f32 {
+    if terms.is_empty() {
+        return 0.0;
+    }
+    let text = text.to_lowercase();
+    let matched = terms.iter().filter(|term| text.contains(term.as_str())).count();
+    matched as f32 / terms.len() as f32
+}
+
+/// Merge vector and keyword hits into one ranking, weighting the vector
+/// score by `alpha` and the keyword score by `1 - alpha`
+fn merge_hybrid_results(
+    vector_results: Vec<VectorSearchResult>,
+    keyword_results: Vec<VectorSearchResult>,
+    terms: &[String],
+    alpha: f32,
+    top_k: usize,
+) -> Vec<HybridSearchResult> {
+    let mut merged: Vec<HybridSearchResult> = Vec::new();
+    for result in vector_results {
+        merged.push(HybridSearchResult { result, matched: MatchSignal::Vector });
+    }
+    for result in keyword_results {
+        match merged.iter_mut().find(|hit| hit.result.id == result.id) {
+            Some(hit) => hit.matched = MatchSignal::Both,
+            None => merged.push(HybridSearchResult { result, matched: MatchSignal::Keyword }),
+        }
+    }
+
+    for hit in &mut merged {
+        let keyword = keyword_score(&hit.result.text, terms);
+        hit.result.score = alpha * hit.result.score + (1.0 - alpha) * keyword;
+    }
+    // Ties broken like `sort_search_results` so the ranking is deterministic
+    merged.sort_by(|a, b| {
+        b.result
+            .score
+            .total_cmp(&a.result.score)
+            .then(a.result.chunk_index.cmp(&b.result.chunk_index))
+            .then_with(|| a.result.id.cmp(&b.result.id))
+    });
+    merged.truncate(top_k);
+    merged
+}
+
+/// Columns of each batch returned by `search_table_ipc`
+fn search_result_schema() -> SchemaRef {
+    StdArc::new(Schema::new(vec![
+        Field::new("id", DataType::Utf8, false),
+        Field::new("text", DataType::Utf8, false),
+        Field::new("chunk_index", DataType::Int32, false),
+        Field::new("text_length", DataType::Int32, false),
+        Field::new("distance", DataType::Float32, false),
+        Field::new("score", DataType::Float32, false),
+    ]))
+}
+
+/// `search_table` serialized as an Arrow IPC stream (see `search_result_schema`)
+/// instead of one struct per row, in the same order
+async fn search_table_ipc(
+    table: &Table,
+    query_embedding: Vec<f32>,
+    top_k: usize,
+    metric: DistanceType,
+    filter: Option<&str>,
+) -> Result<Vec<u8>, VectorStoreError> {
+    let mut result_stream =
+        search_batches(table, query_embedding, top_k.saturating_add(TIE_BREAK_OVERFETCH), metric, filter).await?;
+
+    let schema = search_result_schema();
+    let ipc_error = |e: ArrowError| VectorStoreError::Io(format!("Failed to encode search results: {}", e));
+    let mut batches = Vec::new();
+    while let Some(batch_result) = result_stream.next().await {
+        let batch = batch_result.map_err(|e| VectorStoreError::Query(format!("Batch error: {}", e)))?;
+        let distances = column::<Float32Array>(&batch, "_distance")?;
+        let scores: Float32Array = distances.iter().map(|distance| distance.map(|d| distance_to_score(d, metric))).collect();
+
+        let passthrough = |name: &str| {
+            batch
+                .column_by_name(name)
+                .cloned()
+                .ok_or_else(|| VectorStoreError::SchemaMismatch(format!("No {} column", name)))
+        };
+        let columns: Vec<ArrayRef> = vec![
+            passthrough("id")?,
+            passthrough("text")?,
+            passthrough("chunk_index")?,
+            passthrough("text_length")?,
+            StdArc::new(distances.clone()),
+            StdArc::new(scores),
+        ];
+        let batch = RecordBatch::try_new(schema.clone(), columns)
+            .map_err(|e| VectorStoreError::SchemaMismatch(format!("Unexpected search result columns: {}", e)))?;
+        batches.push(batch);
+    }
+
+    let mut writer = StreamWriter::try_new(Vec::new(), &schema).map_err(ipc_error)?;
+    let batch = concat_batches(&schema, &batches).map_err(ipc_error)?;
+    if batch.num_rows() > 0 {
+        writer.write(&sort_search_batch(&batch, top_k).map_err(ipc_error)?).map_err(ipc_error)?;
+    }
+    writer.into_inner().map_err(ipc_error)
+}
+
+/// `sort_search_results` for a batch in `search_result_schema`, keeping the first `top_k` rows
+fn sort_search_batch(batch: &RecordBatch, top_k: usize) -> Result<RecordBatch, ArrowError> {
+    let sort_columns = ["distance", "chunk_index", "id"]
+        .into_iter()
+        .map(|name| Ok(SortColumn { values: batch.column(batch.schema().index_of(name)?).clone(), options: None }))
+        .collect::<Result<Vec<_>, ArrowError>>()?;
+    let indices = lexsort_to_indices(&sort_columns, Some(top_k))?;
+    take_record_batch(batch, &indices)
+}
+
+async fn hybrid_search_table(
+    table: &Table,
+    query_embedding: Vec<f32>,
+    query_text: &str,
+    top_k: usize,
+    alpha: f32,
+) -> Result<Vec<HybridSearchResult>, VectorStoreError> {
+    let metric = parse_metric(None)?;
+    let terms = keyword_terms(query_text);
+
+    let vector_results = search_table(table, query_embedding.clone(), top_k, metric, None, false).await?;
+    // Keyword hits are ranked by vector distance too, so they carry a real distance
+    let keyword_results = if terms.is_empty() {
+        Vec::new()
+    } else {
+        search_table(table, query_embedding, top_k, metric, Some(&keyword_filter(&terms)), false).await?
+    };
+
+    Ok(merge_hybrid_results(vector_results, keyword_results, &terms, alpha, top_k))
+}
+
+/// Drop results scoring below `min_score`. Without a `ScoreMode` scores depend
+/// on the metric (see `distance_to_score`): l2 gives 1 / (1 + distance) in
+/// (0, 1], cosine gives the cosine similarity in [-1, 1], and dot gives the
+/// raw dot product, which is only comparable across queries for normalized
+/// embeddings.
+fn retain_min_score(results: &mut Vec<VectorSearchResult>, min_score: Option<f32>) {
+    if let Some(min_score) = min_score {
+        results.retain(|result| result.score >= min_score);
+    }
+}
+
+/// Refuse to search a document embedded by a different model than the query.
+/// Documents without a recorded model (older tables) only get the dimension
+/// check `search_table` does.
+async fn check_embedding_model(
+    db: &Connection,
+    document_id: &str,
+    embedding_model: Option<&str>,
+) -> Result<(), VectorStoreError> {
+    let Some(query_model) = embedding_model else {
+        return Ok(());
+    };
+    let stored_model = read_document_metadata(db, document_id).await?.and_then(|metadata| metadata.embedding_model);
+    match stored_model {
+        Some(stored_model) if stored_model != query_model => Err(VectorStoreError::SchemaMismatch(format!(
+            "Embedding model mismatch: document {} was embedded with {}, query uses {}",
+            document_id, stored_model, query_model
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Nearest chunks to `query_embedding`, at most `top_k` of them, and fewer
+/// when `min_score` filters out weak matches. `score_mode` (`inverse`,
+/// `negative` or `exp_decay`, see `ScoreMode`) replaces the metric's default
+/// score; `distance` is always the raw value LanceDB reported. When
+/// `embedding_model` is given it must match the model recorded for the document.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn vector_store_search(
+    document_id: String,
+    query_embedding: Vec<f32>,
+    top_k: usize,
+    metric: Option<String>,
+    filter: Option<String>,
+    min_score: Option<f32>,
+    include_vectors: Option<bool>,
+    score_mode: Option<String>,
+    embedding_model: Option<String>,
+    storage_path: Option<String>,
+    state: State<'_, StdArc<Mutex<VectorStoreState>>>,
+) -> Result<Vec<VectorSearchResult>, VectorStoreError> {
+    let metric = parse_metric(metric.as_deref())?;
+    let score_mode = parse_score_mode(score_mode.as_deref())?;
+    let storage_path = resolve_storage_path(&state, storage_path).await?;
+    let _permit = acquire_operation_permit(&state).await?;
+
+    let db = state_connection(&state, &storage_path).await?;
+
+    migrate_legacy_table_locking(&state, &db, &document_id).await?;
+    let table_name = table_name_for(&document_id);
+
+    let table = open_table(&db, &table_name).await?;
+    check_embedding_model(&db, &document_id, embedding_model.as_deref()).await?;
+
+    let mut results = search_table(
+        &table,
+        query_embedding,
+        top_k,
+        metric,
+        filter.as_deref(),
+        include_vectors.unwrap_or(false),
+    )
+    .await?;
+    apply_score_mode(&mut results, score_mode);
+    retain_min_score(&mut results, min_score);
+    Ok(results)
+}
+
+/// `vector_store_search` for Arrow-aware frontends: the results come back as
+/// raw Arrow IPC stream bytes rather than JSON, with columns `id` and `text`
+/// (utf8), `chunk_index` and `text_length` (int32), and `distance` and
+/// `score` (float32), in nearest-first order
+#[tauri::command]
+pub async fn vector_store_search_arrow(
+    document_id: String,
+    query_embedding: Vec<f32>,
+    top_k: usize,
+    metric: Option<String>,
+    filter: Option<String>,
+    storage_path: Option<String>,
+    state: State<'_, StdArc<Mutex<VectorStoreState>>>,
+) -> Result<Response, VectorStoreError> {
+    let metric = parse_metric(metric.as_deref())?;
+    let storage_path = resolve_storage_path(&state, storage_path).await?;
+    let _permit = acquire_operation_permit(&state).await?;
+
+    let db = state_connection(&state, &storage_path).await?;
+
+    migrate_legacy_table_locking(&state, &db, &document_id).await?;
+    let table = open_table(&db, &table_name_for(&document_id)).await?;
+
+    let bytes = search_table_ipc(&table, query_embedding, top_k, metric, filter.as_deref()).await?;
+    Ok(Response::new(bytes))
+}
+
+/// Combine nearest-neighbour search with a keyword match on the chunk text,
+/// so exact terms (an equation or method name) aren't lost to the embedding
+#[tauri::command]
+pub async fn vector_store_hybrid_search(
+    document_id: String,
+    query_embedding: Vec<f32>,
+    query_text: String,
+    top_k: usize,
+    alpha: Option<f32>,
+    storage_path: Option<String>,
+    state: State<'_, StdArc<Mutex<VectorStoreState>>>,
+) -> Result<Vec<HybridSearchResult>, VectorStoreError> {
+    let alpha = alpha.unwrap_or(DEFAULT_HYBRID_ALPHA);
+    if !(0.0..=1.0).contains(&alpha) {
+        return Err(VectorStoreError::InvalidArgument(format!(
+            "Hybrid alpha must be between 0 and 1, got {}",
+            alpha
+        )));
+    }
+    let storage_path = resolve_storage_path(&state, storage_path).await?;
+    let _permit = acquire_operation_permit(&state).await?;
+
+    let db = state_connection(&state, &storage_path).await?;
+
+    migrate_legacy_table_locking(&state, &db, &document_id).await?;
+    let table_name = table_name_for(&document_id);
+
+    let table = open_table(&db, &table_name).await?;
+
+    hybrid_search_table(&table, query_embedding, &query_text, top_k, alpha).await
+}
+
+/// Documents whose content is closest to `document_id`'s overall, by the
+/// cosine similarity of their mean chunk vectors
+#[tauri::command]
+pub async fn vector_store_similar_documents(
+    document_id: String,
+    top_k: usize,
+    storage_path: Option<String>,
+    state: State<'_, StdArc<Mutex<VectorStoreState>>>,
+) -> Result<Vec<SimilarDocument>, VectorStoreError> {
+    let storage_path = resolve_storage_path(&state, storage_path).await?;
+    let _permit = acquire_operation_permit(&state).await?;
+
+    let db = state_connection(&state, &storage_path).await?;
+
+    migrate_legacy_table_locking(&state, &db, &document_id).await?;
+    similar_documents(&db, &document_id, top_k).await
+}
+
+#[tauri::command]
+pub async fn vector_store_create_index(
+    document_id: String,
+    options: Option<IndexOptions>,
+    storage_path: Option<String>,
+    state: State<'_, StdArc<Mutex<VectorStoreState>>>,
+) -> Result<String, VectorStoreError> {
+    let storage_path = resolve_storage_path(&state, storage_path).await?;
+    let _permit = acquire_operation_permit(&state).await?;
+
+    let db = state_connection(&state, &storage_path).await?;
+
+    let table_name = table_name_for(&document_id);
+    let _table_lock = lock_table(&state, &table_name).await;
+    migrate_legacy_table(&db, &document_id).await?;
+
+    let table = open_table(&db, &table_name).await?;
+
+    if create_vector_index(&table, &options.unwrap_or_default()).await? {
+        Ok(format!("Created IVF_PQ index on table {}", table_name))
+    } else {
+        Ok(format!(
+            "Skipped indexing table {}: fewer than {} rows",
+            table_name, MIN_ROWS_FOR_INDEX
+        ))
+    }
+}
+
+/// Chunk count and the first `char_limit` characters of a document's text,
+/// without reading the whole document
+#[tauri::command]
+pub async fn vector_store_preview(
+    document_id: String,
+    char_limit: usize,
+    storage_path: Option<String>,
+    state: State<'_, StdArc<Mutex<VectorStoreState>>>,
+) -> Result<DocumentPreview, VectorStoreError> {
+    let storage_path = resolve_storage_path(&state, storage_path).await?;
+    let _permit = acquire_operation_permit(&state).await?;
+
+    let db = state_connection(&state, &storage_path).await?;
+
+    migrate_legacy_table_locking(&state, &db, &document_id).await?;
+    let table = open_table(&db, &table_name_for(&document_id)).await?;
+
+    preview_document(&table, char_limit).await
+}
+
+/// Read back a document's chunks in `chunk_index` order, one page at a time.
+/// Vectors are omitted (left empty) unless `include_vectors` is set.
+#[tauri::command]
+pub async fn vector_store_get_chunks(
+    document_id: String,
+    offset: Option<usize>,
+    limit: Option<usize>,
+    include_vectors: Option<bool>,
+    storage_path: Option<String>,
+    state: State<'_, StdArc<Mutex<VectorStoreState>>>,
+) -> Result<Vec<ChunkData>, VectorStoreError> {
+    let storage_path = resolve_storage_path(&state, storage_path).await?;
+    let _permit = acquire_operation_permit(&state).await?;
+
+    let db = state_connection(&state, &storage_path).await?;
+
+    migrate_legacy_table_locking(&state, &db, &document_id).await?;
+    let table_name = table_name_for(&document_id);
+
+    let table = open_table(&db, &table_name).await?;
+
+    let chunks = read_chunks(&table, include_vectors.unwrap_or(false)).await?;
+
+    Ok(chunks
+        .into_iter()
+        .skip(offset.unwrap_or(0))
+        .take(limit.unwrap_or(usize::MAX))
+        .collect())
+}
+
+#[tauri::command]
+pub async fn vector_store_has_document(
+    document_id: String,
+    storage_path: Option<String>,
+    state: State<'_, StdArc<Mutex<VectorStoreState>>>,
+) -> Result<bool, VectorStoreError> {
+    let storage_path = resolve_storage_path(&state, storage_path).await?;
+    let _permit = acquire_operation_permit(&state).await?;
+
+    let db = state_connection(&state, &storage_path).await?;
+
+    migrate_legacy_table_locking(&state, &db, &document_id).await?;
+    let table_name = table_name_for(&document_id);
+
+    let table_names = db
+        .table_names()
+        .execute()
+        .await
+        .map_err(|e| VectorStoreError::Query(format!("Failed to list tables: {}", e)))?;
+
+    Ok(table_names.contains(&table_name))
+}
+
+#[tauri::command]
+pub async fn vector_store_delete_document(
+    document_id: String,
+    storage_path: Option<String>,
+    state: State<'_, StdArc<Mutex<VectorStoreState>>>,
+) -> Result<String, VectorStoreError> {
+    let storage_path = resolve_storage_path(&state, storage_path).await?;
+    let _permit = acquire_operation_permit(&state).await?;
+
+    let db = state_connection(&state, &storage_path).await?;
+
+    let table_name = table_name_for(&document_id);
+    let _table_lock = lock_table(&state, &table_name).await;
+    migrate_legacy_table(&db, &document_id).await?;
+
+    db.drop_table(&table_name, &[])
+        .await
+        .map_err(|e| VectorStoreError::Query(format!("Failed to delete table: {}", e)))?;
+    delete_document_metadata(&db, &document_id).await?;
+    delete_cached_centroid(&db, &document_id).await?;
+
+    Ok(format!("Deleted table: {}", table_name))
+}
+
+#[tauri::command]
+pub async fn vector_store_clear_all(
+    storage_path: Option<String>,
+    state: State<'_, StdArc<Mutex<VectorStoreState>>>,
+) -> Result<String, VectorStoreError> {
+    let storage_path = resolve_storage_path(&state, storage_path).await?;
+    let _permit = acquire_operation_permit(&state).await?;
+
+    let db = state_connection(&state, &storage_path).await?;
+
+    let table_names = db
+        .table_names()
+        .execute()
+        .await
+        .map_err(|e| VectorStoreError::Query(format!("Failed to list tables: {}", e)))?;
+
+    // Held until the connection is reset, and taken in sorted order like the
+    // batch delete so no writer touches a table while it's being dropped
+    let table_names: BTreeSet<String> = table_names.into_iter().collect();
+    let mut held_locks = Vec::new();
+    for table_name in &table_names {
+        held_locks.push(lock_table(&state, table_name).await);
+    }
+
+    for table_name in &table_names {
+        db.drop_table(&table_name, &[])
+            .await
+            .map_err(|e| VectorStoreError::Query(format!("Failed to delete table {}: {}", table_name, e)))?;
+    }
+
+    // Start the next command from a fresh connection rather than one that
+    // still remembers the dropped tables
+    *state.lock().await.connection.lock().await = None;
+
+    Ok("Cleared all tables".to_string())
+}
+
+#[tauri::command]
+pub async fn vector_store_get_count(
+    document_id: String,
+    storage_path: Option<String>,
+    state: State<'_, StdArc<Mutex<VectorStoreState>>>,
+) -> Result<i64, VectorStoreError> {
+    let storage_path = resolve_storage_path(&state, storage_path).await?;
+    let _permit = acquire_operation_permit(&state).await?;
+
+    let db = state_connection(&state, &storage_path).await?;
+
+    migrate_legacy_table_locking(&state, &db, &document_id).await?;
+    let table_name = table_name_for(&document_id);
+
+    let table = open_table(&db, &table_name).await?;
+
+    let count = table
+        .count_rows(None)
+        .await
+        .map_err(|e| VectorStoreError::Query(format!("Failed to count rows: {}", e)))?;
+
+    Ok(count as i64)
+}
+
+/// Drop the tables of several documents, reporting each one separately so a
+/// missing or failing document doesn't abort the rest
+async fn delete_documents(
+    db: &Connection,
+    document_ids: &[String],
+) -> Result<HashMap<String, DeletionResult>, VectorStoreError> {
+    let table_names = db
+        .table_names()
+        .execute()
+        .await
+        .map_err(|e| VectorStoreError::Query(format!("Failed to list tables: {}", e)))?;
+
+    let mut results = HashMap::new();
+    for document_id in document_ids {
+        let table_name = table_name_for(document_id);
+
+        let result = if !table_names.contains(&table_name) {
+            DeletionResult::NotFound
+        } else if let Err(e) = db.drop_table(&table_name, &[]).await {
+            DeletionResult::Failed(format!("Failed to delete table: {}", e))
+        } else {
+            let cleanup = match delete_document_metadata(db, document_id).await {
+                Ok(()) => delete_cached_centroid(db, document_id).await,
+                Err(e) => Err(e),
+            };
+            match cleanup {
+                Ok(()) => DeletionResult::Deleted,
+                Err(e) => DeletionResult::Failed(e.to_string()),
+            }
+        };
+        results.insert(document_id.clone(), result);
+    }
+
+    Ok(results)
+}
+
+#[tauri::command]
+pub async fn vector_store_delete_documents(
+    document_ids: Vec<String>,
+    storage_path: Option<String>,
+    state: State<'_, StdArc<Mutex<VectorStoreState>>>,
+) -> Result<HashMap<String, DeletionResult>, VectorStoreError> {
+    let storage_path = resolve_storage_path(&state, storage_path).await?;
+    let _permit = acquire_operation_permit(&state).await?;
+
+    let db = state_connection(&state, &storage_path).await?;
+
+    // Sorted and deduplicated so two batch deletes can't deadlock each other
+    let table_names: BTreeSet<String> = document_ids.iter().map(|id| table_name_for(id)).collect();
+    let mut held_locks = Vec::new();
+    for table_name in &table_names {
+        held_locks.push(lock_table(&state, table_name).await);
+    }
+    // A document whose legacy table can't be migrated fails on its own
+    let mut failed = HashMap::new();
+    for document_id in &document_ids {
+        if let Err(e) = migrate_legacy_table(&db, document_id).await {
+            failed.insert(document_id.clone(), DeletionResult::Failed(e.to_string()));
+        }
+    }
+    let remaining: Vec<String> = document_ids.iter().filter(|id| !failed.contains_key(*id)).cloned().collect();
+
+    let mut results = delete_documents(&db, &remaining).await?;
+    results.extend(failed);
+    Ok(results)
+}
+
+/// Copy a document's chunks from `old_table` into the new table `new_table`,
+/// returning the number of chunks copied. A partial copy is dropped again.
+async fn copy_document_table(db: &Connection, old_table: &Table, new_table: &str) -> Result<usize, VectorStoreError> {
+    let expected_rows = old_table
+        .count_rows(None)
+        .await
+        .map_err(|e| VectorStoreError::Query(format!("Failed to count rows: {}", e)))?;
+
+    let copied = async {
+        let rows = old_table
+            .query()
+            .execute()
+            .await
+            .map_err(|e| VectorStoreError::Query(format!("Failed to scan table: {}", e)))?;
+        // Create mode, so an existing table is never overwritten
+        let table = db
+            .create_table_streaming(new_table, rows)
+            .execute()
+            .await
+            .map_err(|e| VectorStoreError::Query(format!("Failed to create table: {}", e)))?;
+        let copied_rows = table
+            .count_rows(None)
+            .await
+            .map_err(|e| VectorStoreError::Query(format!("Failed to count rows: {}", e)))?;
+        if copied_rows != expected_rows {
+            return Err(VectorStoreError::Query(format!("Copied {} of {} chunks", copied_rows, expected_rows)));
+        }
+        Ok(copied_rows)
+    }
+    .await;
+
+    if copied.is_err() {
+        if let Err(e) = drop_table_if_exists(db, new_table).await {
+            warn!("{}", e);
+        }
+    }
+    copied
+}
+
+/// Rename `old_table_name` to the unused `new_table_name`, returning its chunk
+/// count. LanceDB OSS can't rename tables, so the chunks are copied to the new
+/// table (re-indexed if the old one was indexed) before the old one is dropped.
+async fn move_table(db: &Connection, old_table_name: &str, new_table_name: &str) -> Result<usize, VectorStoreError> {
+    let old_table = open_table(db, old_table_name).await?;
+
+    match db.rename_table(old_table_name, new_table_name, &[], &[]).await {
+        Ok(()) => open_table(db, new_table_name)
+            .await?
+            .count_rows(None)
+            .await
+            .map_err(|e| VectorStoreError::Query(format!("Failed to count rows: {}", e))),
+        Err(lancedb::Error::NotSupported { .. }) => {
+            let row_count = copy_document_table(db, &old_table, new_table_name).await?;
+
+            let indices = old_table
+                .list_indices()
+                .await
+                .map_err(|e| VectorStoreError::Query(format!("Failed to list indices: {}", e)))?;
+            if !indices.is_empty() {
+                let new_table = open_table(db, new_table_name).await?;
+                create_vector_index(&new_table, &IndexOptions::default()).await?;
+            }
+
+            db.drop_table(old_table_name, &[])
+                .await
+                .map_err(|e| VectorStoreError::Query(format!("Failed to delete table: {}", e)))?;
+            Ok(row_count)
+        }
+        Err(e) => Err(VectorStoreError::Query(format!("Failed to rename table: {}", e))),
+    }
+}
+
+/// Whether `document_id` only has a table under its legacy name
+async fn needs_migration(db: &Connection, document_id: &str) -> Result<bool, VectorStoreError> {
+    let table_name = table_name_for(document_id);
+    let legacy_table_name = legacy_table_name_for(document_id);
+    if legacy_table_name == table_name {
+        return Ok(false);
+    }
+
+    let table_names = db
+        .table_names()
+        .execute()
+        .await
+        .map_err(|e| VectorStoreError::Query(format!("Failed to list tables: {}", e)))?;
+    Ok(!table_names.contains(&table_name) && table_names.contains(&legacy_table_name))
+}
+
+/// Move a document's table from its `legacy_table_name_for` name to its
+/// `table_name_for` one, recording `document_id` in its metadata since the
+/// lossy legacy name can't be decoded back to it. Returns whether a table was
+/// moved; documents already under their current name are left alone.
+async fn migrate_legacy_table(db: &Connection, document_id: &str) -> Result<bool, VectorStoreError> {
+    if !needs_migration(db, document_id).await? {
+        return Ok(false);
+    }
+
+    let table_name = table_name_for(document_id);
+    let legacy_table_name = legacy_table_name_for(document_id);
+    let row_count = move_table(db, &legacy_table_name, &table_name).await?;
+    if read_document_metadata(db, document_id).await?.is_none() {
+        write_document_metadata(db, document_id, &DocumentMetadata::default()).await?;
+    }
+    info!("Migrated {} to {} ({} chunks)", legacy_table_name, table_name, row_count);
+    Ok(true)
+}
+
+/// `migrate_legacy_table` for commands that don't otherwise take the
+/// document's table lock. The lock is only taken when there is something to
+/// migrate, so reads of current documents still never wait on writes.
+async fn migrate_legacy_table_locking(
+    state: &StdArc<Mutex<VectorStoreState>>,
+    db: &Connection,
+    document_id: &str,
+) -> Result<bool, VectorStoreError> {
+    if !needs_migration(db, document_id).await? {
+        return Ok(false);
+    }
+    let _table_lock = lock_table(state, &table_name_for(document_id)).await;
+    migrate_legacy_table(db, document_id).await
+}
+
+/// Ids of every document with an entry in `_metadata`
+async fn metadata_document_ids(db: &Connection) -> Result<Vec<String>, VectorStoreError> {
+    if !table_exists(db, DOCUMENT_METADATA_TABLE).await? {
+        return Ok(Vec::new());
+    }
+
+    let table = db
+        .open_table(DOCUMENT_METADATA_TABLE)
+        .execute()
+        .await
+        .map_err(|e| VectorStoreError::Query(format!("Failed to open document metadata: {}", e)))?;
+
+    let mut result_stream = table
+        .query()
+        .select(Select::columns(&["document_id"]))
+        .execute()
+        .await
+        .map_err(|e| VectorStoreError::Query(format!("Failed to read document metadata: {}", e)))?;
+
+    let mut document_ids = Vec::new();
+    while let Some(batch_result) = result_stream.next().await {
+        let batch = batch_result.map_err(|e| VectorStoreError::Query(format!("Batch error: {}", e)))?;
+        let ids = column::<StringArray>(&batch, "document_id")?;
+        document_ids.extend((0..ids.len()).map(|i| ids.value(i).to_string()));
+    }
+    Ok(document_ids)
+}
+
+/// Move every table whose document id is recorded in `_metadata` off its
+/// legacy name. Documents already migrated are skipped, so running it again is a no-op.
+async fn migrate_table_names(
+    state: &StdArc<Mutex<VectorStoreState>>,
+    db: &Connection,
+) -> Result<MigrationReport, VectorStoreError> {
+    let document_ids = metadata_document_ids(db).await?;
+
+    let mut migrated_documents = Vec::new();
+    for document_id in &document_ids {
+        if migrate_legacy_table_locking(state, db, document_id).await? {
+            migrated_documents.push(document_id.clone());
+        }
+    }
+
+    // Legacy names only differ from current ones where the id had characters
+    // other than ASCII letters and digits, which both schemes turn into `_...`
+    let known_tables: BTreeSet<String> = document_ids.iter().map(|id| table_name_for(id)).collect();
+    let table_names = db
+        .table_names()
+        .execute()
+        .await
+        .map_err(|e| VectorStoreError::Query(format!("Failed to list tables: {}", e)))?;
+    let unresolved = table_names
+        .into_iter()
+        .filter(|name| {
+            name.strip_prefix(DOCUMENT_TABLE_PREFIX).is_some_and(|suffix| suffix.contains('_'))
+                && !known_tables.contains(name)
+        })
+        .collect();
+
+    Ok(MigrationReport { migrated: migrated_documents.len(), migrated_documents, unresolved })
+}
+
+/// Move a document to `new_id` along with its metadata, returning its chunk
+/// count
+async fn rename_document(db: &Connection, old_id: &str, new_id: &str) -> Result<usize, VectorStoreError> {
+    if new_id.trim().is_empty() {
+        return Err(VectorStoreError::InvalidArgument("New document id is empty".to_string()));
+    }
+    let old_table_name = table_name_for(old_id);
+    let new_table_name = table_name_for(new_id);
+    if old_table_name == new_table_name {
+        return Err(VectorStoreError::InvalidArgument(format!("'{}' and '{}' are the same document", old_id, new_id)));
+    }
+
+    // Fails with TableNotFound for an unknown document
+    open_table(db, &old_table_name).await?;
+    if table_exists(db, &new_table_name).await? {
+        return Err(VectorStoreError::InvalidArgument(format!("Document '{}' already exists", new_id)));
+    }
+
+    let row_count = move_table(db, &old_table_name, &new_table_name).await?;
+
+    if let Some(metadata) = read_document_metadata(db, old_id).await? {
+        write_document_metadata(db, new_id, &metadata).await?;
+        delete_document_metadata(db, old_id).await?;
+    }
+    delete_cached_centroid(db, old_id).await?;
+
+    Ok(row_count)
+}
+
+/// Give an indexed document a new id without re-ingesting it. Fails rather
+/// than overwrite when `new_id` is already taken.
+#[tauri::command]
+pub async fn vector_store_rename_document(
+    old_id: String,
+    new_id: String,
+    storage_path: Option<String>,
+    state: State<'_, StdArc<Mutex<VectorStoreState>>>,
+) -> Result<String, VectorStoreError> {
+    let storage_path = resolve_storage_path(&state, storage_path).await?;
+    let _permit = acquire_operation_permit(&state).await?;
+
+    let db = state_connection(&state, &storage_path).await?;
+
+    // Sorted so a rename in the opposite direction can't deadlock with this one
+    let table_names: BTreeSet<String> = [table_name_for(&old_id), table_name_for(&new_id)].into_iter().collect();
+    let mut held_locks = Vec::new();
+    for table_name in &table_names {
+        held_locks.push(lock_table(&state, table_name).await);
+    }
+    migrate_legacy_table(&db, &old_id).await?;
+
+    let row_count = rename_document(&db, &old_id, &new_id).await?;
+
+    Ok(format!("Renamed document {} to {} ({} chunks)", old_id, new_id, row_count))
+}
+
+/// Record title, source, embedding model etc. for a document, replacing any previous entry
+#[tauri::command]
+pub async fn vector_store_set_metadata(
+    document_id: String,
+    metadata: DocumentMetadata,
+    storage_path: Option<String>,
+    state: State<'_, StdArc<Mutex<VectorStoreState>>>,
+) -> Result<String, VectorStoreError> {
+    let storage_path = resolve_storage_path(&state, storage_path).await?;
+    let _permit = acquire_operation_permit(&state).await?;
+
+    let db = state_connection(&state, &storage_path).await?;
+
+    // write_document_metadata deletes then re-adds the row, which must not
+    // interleave with the metadata writes of add_chunks or reindex
+    let _table_lock = lock_table(&state, &table_name_for(&document_id)).await;
+
+    let metadata = DocumentMetadata {
+        created_at: metadata.created_at.clone().or_else(|| Some(chrono::Utc::now().to_rfc3339())),
+        ..metadata
+    };
+    write_document_metadata(&db, &document_id, &metadata).await?;
+
+    Ok(format!("Saved metadata for document: {}", document_id))
+}
+
+#[tauri::command]
+pub async fn vector_store_get_metadata(
+    document_id: String,
+    storage_path: Option<String>,
+    state: State<'_, StdArc<Mutex<VectorStoreState>>>,
+) -> Result<Option<DocumentMetadata>, VectorStoreError> {
+    let storage_path = resolve_storage_path(&state, storage_path).await?;
+    let _permit = acquire_operation_permit(&state).await?;
+
+    let db = state_connection(&state, &storage_path).await?;
+
+    read_document_metadata(&db, &document_id).await
+}
+
+/// Ids of the documents in the store
+#[tauri::command]
+pub async fn vector_store_list_documents(
+    storage_path: Option<String>,
+    state: State<'_, StdArc<Mutex<VectorStoreState>>>,
+) -> Result<Vec<String>, VectorStoreError> {
+    let storage_path = resolve_storage_path(&state, storage_path).await?;
+    let _permit = acquire_operation_permit(&state).await?;
+
+    let db = state_connection(&state, &storage_path).await?;
+
+    list_document_ids(&db).await
+}
+
+/// Row count and on-disk size for each indexed document
+#[tauri::command]
+pub async fn vector_store_stats(
+    storage_path: Option<String>,
+    state: State<'_, StdArc<Mutex<VectorStoreState>>>,
+) -> Result<Vec<DocumentStats>, VectorStoreError> {
+    let storage_path = resolve_storage_path(&state, storage_path).await?;
+    let _permit = acquire_operation_permit(&state).await?;
+
+    let db = state_connection(&state, &storage_path).await?;
+
+    collect_document_stats(&db).await
+}
+
+/// Try to open and count every document table, collecting LanceDB's error
+/// for the ones that fail instead of stopping at the first
+async fn check_compatibility(db: &Connection) -> Result<CompatibilityReport, VectorStoreError> {
+    let table_names = db
+        .table_names()
+        .execute()
+        .await
+        .map_err(|e| VectorStoreError::Query(format!("Failed to list tables: {}", e)))?;
+
+    let mut readable = Vec::new();
+    let mut unreadable = Vec::new();
+    for table_name in table_names {
+        let Some(document_id) = document_id_for(&table_name) else {
+            continue;
+        };
+
+        let row_count = match db.open_table(&table_name).execute().await {
+            Ok(table) => table.count_rows(None).await.map_err(|e| e.to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+        match row_count {
+            Ok(_) => readable.push(document_id),
+            Err(error) => unreadable.push(UnreadableTable { document_id, error }),
+        }
+    }
+
+    Ok(CompatibilityReport {
+        lancedb_version: LANCEDB_VERSION.to_string(),
+        format_version: STORE_FORMAT_VERSION.to_string(),
+        store_version: read_version_marker(db).await?,
+        readable,
+        unreadable,
+    })
+}
+
+/// Diagnose a store written by another build: which document tables this
+/// build can read, which it can't (with the reason), and which build created it
+#[tauri::command]
+pub async fn vector_store_check_compatibility(
+    storage_path: Option<String>,
+    state: State<'_, StdArc<Mutex<VectorStoreState>>>,
+) -> Result<CompatibilityReport, VectorStoreError> {
+    let storage_path = resolve_storage_path(&state, storage_path).await?;
+    let _permit = acquire_operation_permit(&state).await?;
+
+    let db = state_connection(&state, &storage_path).await?;
+
+    check_compatibility(&db).await
+}
+
+/// Move tables left under the pre-`table_name_for` naming scheme to their
+/// current names, for every document whose id is recorded in `_metadata`
+#[tauri::command]
+pub async fn vector_store_migrate_table_names(
+    storage_path: Option<String>,
+    state: State<'_, StdArc<Mutex<VectorStoreState>>>,
+) -> Result<MigrationReport, VectorStoreError> {
+    let storage_path = resolve_storage_path(&state, storage_path).await?;
+    let _permit = acquire_operation_permit(&state).await?;
+
+    let db = state_connection(&state, &storage_path).await?;
+
+    let report = migrate_table_names(&state, &db).await?;
+    if report.migrated > 0 {
+        info!("Migrated {} table(s) to the current naming scheme", report.migrated);
+    }
+    Ok(report)
+}
+
+/// Number of documents and chunks across the whole store
+#[tauri::command]
+pub async fn vector_store_total_stats(
+    storage_path: Option<String>,
+    state: State<'_, StdArc<Mutex<VectorStoreState>>>,
+) -> Result<TotalStats, VectorStoreError> {
+    let storage_path = resolve_storage_path(&state, storage_path).await?;
+    let _permit = acquire_operation_permit(&state).await?;
+
+    let db = state_connection(&state, &storage_path).await?;
+
+    Ok(total_stats(&collect_document_stats(&db).await?))
+}
+
+/// Compact a document table's data files and prune old versions, reporting
+/// how much disk space that freed. On an already-compact table it is a no-op.
+async fn optimize_table(db: &Connection, table_name: &str) -> Result<OptimizeReport, VectorStoreError> {
+    let table = open_table(db, table_name).await?;
+    // Local LanceDB stores each table in a `<name>.lance` directory
+    let table_dir = Path::new(db.uri()).join(format!("{}.lance", table_name));
+    let size_before = dir_size(&table_dir);
+
+    let stats = table
+        .optimize(OptimizeAction::All)
+        .await
+        .map_err(|e| VectorStoreError::Query(format!("Failed to optimize table {}: {}", table_name, e)))?;
+
+    let compaction = stats.compaction.unwrap_or_default();
+    let prune = stats.prune.unwrap_or_default();
+    Ok(OptimizeReport {
+        document_id: document_id_for(table_name).unwrap_or_else(|| table_name.to_string()),
+        fragments_removed: compaction.fragments_removed,
+        fragments_added: compaction.fragments_added,
+        old_versions_removed: prune.old_versions,
+        bytes_freed: size_before.saturating_sub(dir_size(&table_dir)),
+    })
+}
+
+#[tauri::command]
+pub async fn vector_store_optimize(
+    document_id: String,
+    storage_path: Option<String>,
+    state: State<'_, StdArc<Mutex<VectorStoreState>>>,
+) -> Result<OptimizeReport, VectorStoreError> {
+    let storage_path = resolve_storage_path(&state, storage_path).await?;
+    let _permit = acquire_operation_permit(&state).await?;
+
+    let db = state_connection(&state, &storage_path).await?;
+
+    let table_name = table_name_for(&document_id);
+    let _table_lock = lock_table(&state, &table_name).await;
+    migrate_legacy_table(&db, &document_id).await?;
+
+    optimize_table(&db, &table_name).await
+}
+
+/// Run `vector_store_optimize` over every document table
+#[tauri::command]
+pub async fn vector_store_optimize_all(
+    storage_path: Option<String>,
+    state: State<'_, StdArc<Mutex<VectorStoreState>>>,
+) -> Result<Vec<OptimizeReport>, VectorStoreError> {
+    let storage_path = resolve_storage_path(&state, storage_path).await?;
+    let _permit = acquire_operation_permit(&state).await?;
+
+    let db = state_connection(&state, &storage_path).await?;
+
+    let table_names = db
+        .table_names()
+        .execute()
+        .await
+        .map_err(|e| VectorStoreError::Query(format!("Failed to list tables: {}", e)))?;
+
+    let mut reports = Vec::new();
+    for table_name in table_names.iter().filter(|name| name.starts_with(DOCUMENT_TABLE_PREFIX)) {
+        let _table_lock = lock_table(&state, table_name).await;
+        reports.push(optimize_table(&db, table_name).await?);
+    }
+
+    Ok(reports)
+}
+
+/// Write every row of a document table to an Arrow IPC file, returning the row count
+async fn export_table(table: &Table, dest_path: &Path) -> Result<usize, VectorStoreError> {
+    let schema = table
+        .schema()
+        .await
+        .map_err(|e| VectorStoreError::Query(format!("Failed to read table schema: {}", e)))?;
+    let mut stream = table
+        .query()
+        .execute()
+        .await
+        .map_err(|e| VectorStoreError::Query(format!("Failed to read table: {}", e)))?;
+
+    let file = std::fs::File::create(dest_path)?;
+    let mut writer = FileWriter::try_new(file, &schema)
+        .map_err(|e| VectorStoreError::Io(format!("Failed to write export file: {}", e)))?;
+    writer.write_metadata(EXPORT_FORMAT_KEY, EXPORT_FORMAT_VERSION);
+
+    let mut row_count = 0;
+    while let Some(batch) = stream.next().await {
+        let batch = batch.map_err(|e| VectorStoreError::Query(format!("Failed to read table: {}", e)))?;
+        row_count += batch.num_rows();
+        writer
+            .write(&batch)
+            .map_err(|e| VectorStoreError::Io(format!("Failed to write export file: {}", e)))?;
+    }
+    writer
+        .finish()
+        .map_err(|e| VectorStoreError::Io(format!("Failed to write export file: {}", e)))?;
+
+    Ok(row_count)
+}
+
+/// Read a file written by `export_table`, checking it holds chunk rows this
+/// version knows how to load. Returns the batches and their vector dimension.
+fn read_export_file(src_path: &Path) -> Result<(SchemaRef, Vec<RecordBatch>, i32), VectorStoreError> {
+    let file = std::fs::File::open(src_path)?;
+    let reader = FileReader::try_new(file, None)
+        .map_err(|e| VectorStoreError::InvalidArgument(format!("Not a valid vector store export: {}", e)))?;
+
+    match reader.custom_metadata().get(EXPORT_FORMAT_KEY) {
+        Some(version) if version == EXPORT_FORMAT_VERSION => {}
+        Some(version) => {
+            return Err(VectorStoreError::SchemaMismatch(format!(
+                "Unsupported export format version {} (expected {})",
+                version, EXPORT_FORMAT_VERSION
+            )))
+        }
+        None => {
+            return Err(VectorStoreError::InvalidArgument(
+                "Not a vector store export: missing format version".to_string(),
+            ))
+        }
+    }
+
+    let schema = reader.schema();
+    let vector_dim = match schema.field_with_name("vector").map(|field| field.data_type()) {
+        Ok(DataType::FixedSizeList(_, dim)) => *dim,
+        _ => {
+            return Err(VectorStoreError::SchemaMismatch(
+                "Export file has no fixed-size 'vector' column".to_string(),
+            ))
+        }
+    };
+
+    let expected = chunk_schema(vector_dim);
+    for field in expected.fields() {
+        let incoming = schema
+            .field_with_name(field.name())
+            .map_err(|_| VectorStoreError::SchemaMismatch(format!("Export file is missing column '{}'", field.name())))?;
+        if !column_types_match(field.data_type(), incoming.data_type()) {
+            return Err(VectorStoreError::SchemaMismatch(format!(
+                "Export file column '{}' is {}, expected {}",
+                field.name(),
+                incoming.data_type(),
+                field.data_type()
+            )));
+        }
+    }
+    if let Some(extra) = schema.fields().iter().find(|field| expected.field_with_name(field.name()).is_err()) {
+        return Err(VectorStoreError::SchemaMismatch(format!(
+            "Export file has unexpected column '{}'",
+            extra.name()
+        )));
+    }
+
+    let batches = reader
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| VectorStoreError::InvalidArgument(format!("Export file is corrupt: {}", e)))?;
+
+    Ok((schema, batches, vector_dim))
+}
+
+/// Load an exported file as `document_id`, replacing any existing table for it.
+/// Returns the number of chunks imported.
+async fn import_document(db: &Connection, document_id: &str, src_path: &Path) -> Result<usize, VectorStoreError> {
+    let (schema, batches, vector_dim) = read_export_file(src_path)?;
+    let row_count: usize = batches.iter().map(|batch| batch.num_rows()).sum();
+
+    // Same store-wide dimension rule as `vector_store_add_chunks`
+    let store_dim = read_store_setting(db, VECTOR_DIM_KEY)
+        .await?
+        .and_then(|value| value.parse::<i32>().ok());
+    if let Some(expected) = store_dim {
+        if row_count > 0 && expected != vector_dim {
+            return Err(VectorStoreError::SchemaMismatch(format!(
+                "Embedding dimension mismatch: store expects {}-dim vectors, got {}",
+                expected, vector_dim
+            )));
+        }
+    }
+
+    let table_name = table_name_for(document_id);
+    replace_table(db, &table_name, schema, batches.into_iter().map(Ok).collect()).await?;
+
+    if row_count > 0 {
+        if store_dim.is_none() {
+            write_store_setting(db, VECTOR_DIM_KEY, &vector_dim.to_string()).await?;
+        }
+        let metadata = read_document_metadata(db, document_id).await?.unwrap_or_default();
+        if metadata.vector_dim != Some(vector_dim) {
+            let metadata = DocumentMetadata { vector_dim: Some(vector_dim), ..metadata };
+            write_document_metadata(db, document_id, &metadata).await?;
+        }
+    }
+
+    Ok(row_count)
+}
+
+/// Export every document into `dest_dir`, one file per table streamed batch
+/// by batch, then write the manifest describing them. The manifest goes last,
+/// so a directory without one is an incomplete export.
+async fn export_all(db: &Connection, dest_dir: &Path) -> Result<ExportManifest, VectorStoreError> {
+    std::fs::create_dir_all(dest_dir)?;
+
+    let mut documents = Vec::new();
+    for document_id in list_document_ids(db).await? {
+        let table_name = table_name_for(&document_id);
+        let table = open_table(db, &table_name).await?;
+        // Table names only hold ASCII letters, digits and `_`, so they are safe file names
+        let file = format!("{}.arrow", table_name);
+        let row_count = export_table(&table, &dest_dir.join(&file))
+            .await
+            .map_err(|e| e.with_context(&format!("exporting {}", document_id)))?;
+        documents.push(ExportedDocument {
+            vector_dim: table_vector_dim(&table).await?,
+            metadata: read_document_metadata(db, &document_id).await?,
+            document_id,
+            file,
+            row_count,
+        });
+    }
+
+    let manifest = ExportManifest {
+        format_version: EXPORT_FORMAT_VERSION.to_string(),
+        vector_dim: read_store_setting(db, VECTOR_DIM_KEY).await?.and_then(|value| value.parse().ok()),
+        documents,
+    };
+    let contents = serde_json::to_string_pretty(&manifest)
+        .map_err(|e| VectorStoreError::Io(format!("Failed to serialize export manifest: {}", e)))?;
+    std::fs::write(dest_dir.join(EXPORT_MANIFEST_FILE), contents)?;
+
+    Ok(manifest)
+}
+
+fn read_manifest(src_dir: &Path) -> Result<ExportManifest, VectorStoreError> {
+    let contents = std::fs::read_to_string(src_dir.join(EXPORT_MANIFEST_FILE))
+        .map_err(|e| VectorStoreError::InvalidArgument(format!("No export manifest in {}: {}", src_dir.display(), e)))?;
+    let manifest: ExportManifest = serde_json::from_str(&contents)
+        .map_err(|e| VectorStoreError::InvalidArgument(format!("Export manifest is corrupt: {}", e)))?;
+    if manifest.format_version != EXPORT_FORMAT_VERSION {
+        return Err(VectorStoreError::SchemaMismatch(format!(
+            "Unsupported export format version {} (expected {})",
+            manifest.format_version, EXPORT_FORMAT_VERSION
+        )));
+    }
+    Ok(manifest)
+}
+
+/// Restore one manifest entry, checking every listed chunk came back
+async fn restore_document(db: &Connection, src_dir: &Path, entry: &ExportedDocument) -> Result<usize, VectorStoreError> {
+    // Only plain file names: a manifest must not reach outside its directory
+    if Path::new(&entry.file).file_name() != Some(std::ffi::OsStr::new(&entry.file)) {
+        return Err(VectorStoreError::InvalidArgument(format!("Invalid export file name '{}'", entry.file)));
+    }
+
+    let row_count = import_document(db, &entry.document_id, &src_dir.join(&entry.file)).await?;
+    if row_count != entry.row_count {
+        return Err(VectorStoreError::SchemaMismatch(format!(
+            "Export file holds {} chunks, manifest lists {}",
+            row_count, entry.row_count
+        )));
+    }
+    if let Some(metadata) = &entry.metadata {
+        let metadata = DocumentMetadata { vector_dim: Some(entry.vector_dim), ..metadata.clone() };
+        write_document_metadata(db, &entry.document_id, &metadata).await?;
+    }
+    Ok(row_count)
+}
+
+/// Restore every document listed in an export's manifest. One document
+/// failing doesn't stop the others; each gets its own result.
+async fn import_all(db: &Connection, manifest: &ExportManifest, src_dir: &Path) -> HashMap<String, RestoreResult> {
+    let mut results = HashMap::new();
+    for entry in &manifest.documents {
+        let result = match restore_document(db, src_dir, entry).await {
+            Ok(row_count) => RestoreResult::Restored(row_count),
+            Err(e) => RestoreResult::Failed(e.to_string()),
+        };
+        results.insert(entry.document_id.clone(), result);
+    }
+    results
+}
+
+/// Write a document's chunks to a single Arrow IPC file, for backup or moving between machines
+#[tauri::command]
+pub async fn vector_store_export_document(
+    document_id: String,
+    dest_path: String,
+    storage_path: Option<String>,
+    state: State<'_, StdArc<Mutex<VectorStoreState>>>,
+) -> Result<String, VectorStoreError> {
+    let storage_path = resolve_storage_path(&state, storage_path).await?;
+    let _permit = acquire_operation_permit(&state).await?;
+
+    let db = state_connection(&state, &storage_path).await?;
+
+    migrate_legacy_table_locking(&state, &db, &document_id).await?;
+    let table = open_table(&db, &table_name_for(&document_id)).await?;
+    let row_count = export_table(&table, Path::new(&dest_path)).await?;
+
+    Ok(format!("Exported {} chunks to {}", row_count, dest_path))
+}
+
+/// Create a document's table from a file written by `vector_store_export_document`
+#[tauri::command]
+pub async fn vector_store_import_document(
+    src_path: String,
+    document_id: String,
+    storage_path: Option<String>,
+    state: State<'_, StdArc<Mutex<VectorStoreState>>>,
+) -> Result<String, VectorStoreError> {
+    let storage_path = resolve_storage_path(&state, storage_path).await?;
+    let _permit = acquire_operation_permit(&state).await?;
+
+    let db = state_connection(&state, &storage_path).await?;
+
+    let table_name = table_name_for(&document_id);
+    let _table_lock = lock_table(&state, &table_name).await;
+    migrate_legacy_table(&db, &document_id).await?;
+
+    let row_count = import_document(&db, &document_id, Path::new(&src_path)).await?;
+
+    Ok(format!("Imported {} chunks into table {}", row_count, table_name))
+}
+
+/// Back the whole store up into `dest_path`: one export file per document
+/// plus a manifest with their ids, dimensions, row counts and metadata
+#[tauri::command]
+pub async fn vector_store_export_all(
+    dest_path: String,
+    storage_path: Option<String>,
+    state: State<'_, StdArc<Mutex<VectorStoreState>>>,
+) -> Result<ExportManifest, VectorStoreError> {
+    let storage_path = resolve_storage_path(&state, storage_path).await?;
+    let _permit = acquire_operation_permit(&state).await?;
+
+    let db = state_connection(&state, &storage_path).await?;
+
+    export_all(&db, Path::new(&dest_path)).await
+}
+
+/// Restore a backup written by `vector_store_export_all`, replacing documents
+/// that already exist. Reports the outcome for each document in the manifest.
+#[tauri::command]
+pub async fn vector_store_import_all(
+    src_path: String,
+    storage_path: Option<String>,
+    state: State<'_, StdArc<Mutex<VectorStoreState>>>,
+) -> Result<HashMap<String, RestoreResult>, VectorStoreError> {
+    let storage_path = resolve_storage_path(&state, storage_path).await?;
+    let _permit = acquire_operation_permit(&state).await?;
+
+    let db = state_connection(&state, &storage_path).await?;
+
+    let src_dir = Path::new(&src_path);
+    let manifest = read_manifest(src_dir)?;
+
+    // Sorted and deduplicated so this can't deadlock with a batch delete
+    let table_names: BTreeSet<String> = manifest.documents.iter().map(|entry| table_name_for(&entry.document_id)).collect();
+    let mut held_locks = Vec::new();
+    for table_name in &table_names {
+        held_locks.push(lock_table(&state, table_name).await);
+    }
+
+    Ok(import_all(&db, &manifest, src_dir).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn test_db(name: &str) -> Connection {
+        let path = std::env::temp_dir().join(format!(
+            "redink_vector_store_{}_{}",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&path);
+        connect(path.to_str().unwrap()).execute().await.unwrap()
+    }
+
+    fn sample_chunks(count: usize, dim: usize) -> Vec<ChunkData> {
+        (0..count)
+            .map(|i| ChunkData {
+                id: format!("chunk-{}", i),
+                text: format!("text {}", i),
+                vector: vec![i as f32; dim],
+                chunk_index: i as i32,
+                text_length: format!("text {}", i).len() as i32,
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_replace_table_keeps_original_on_failure() {
+        let db = test_db("replace_failure").await;
+
+        let (schema, batch) = build_chunk_batch(&sample_chunks(3, 4), 4).unwrap();
+        replace_table(&db, "doc_paper", schema.clone(), vec![Ok(batch)]).await.unwrap();
+
+        // Simulate the new data failing midway through the create
+        let result = replace_table(
+            &db,
+            "doc_paper",
+            schema,
+            vec![Err(ArrowError::ComputeError("simulated failure".to_string()))],
+        )
+        .await;
+        assert!(result.is_err());
+
+        let table = db.open_table("doc_paper").execute().await.unwrap();
+        assert_eq!(table.count_rows(None).await.unwrap(), 3);
+
+        let table_names = db.table_names().execute().await.unwrap();
+        assert_eq!(table_names, vec!["doc_paper".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_replace_table_over_leftover_tables() {
+        let db = test_db("replace_leftover").await;
+
+        // Staging table of an interrupted write, with an unrelated schema
+        let leftover_schema = StdArc::new(Schema::new(vec![Field::new("stale", DataType::Int32, false)]));
+        let leftover = RecordBatch::try_new(leftover_schema.clone(), vec![StdArc::new(Int32Array::from(vec![1, 2]))]).unwrap();
+        let temp_name = format!("{}doc_paper", TEMP_TABLE_PREFIX);
+        db.create_table(&temp_name, Box::new(RecordBatchIterator::new(vec![Ok(leftover)].into_iter(), leftover_schema)))
+            .execute()
+            .await
+            .unwrap();
+
+        let (schema, batch) = build_chunk_batch(&sample_chunks(3, 4), 4).unwrap();
+        replace_table(&db, "doc_paper", schema.clone(), vec![Ok(batch)]).await.unwrap();
+        let (_, batch) = build_chunk_batch(&sample_chunks(5, 4), 4).unwrap();
+        replace_table(&db, "doc_paper", schema, vec![Ok(batch)]).await.unwrap();
+
+        let table = db.open_table("doc_paper").execute().await.unwrap();
+        assert_eq!(table.count_rows(None).await.unwrap(), 5);
+        assert_eq!(db.table_names().execute().await.unwrap(), vec!["doc_paper".to_string()]);
+
+        drop_table_if_exists(&db, "doc_missing").await.unwrap();
+        assert!(is_transient_create_error(&lancedb::Error::TableAlreadyExists { name: "doc_paper".to_string() }));
+        assert!(!is_transient_create_error(&lancedb::Error::InvalidInput { message: "bad vector".to_string() }));
+    }
+
+    #[test]
+    fn test_distance_to_score_per_metric() {
+        assert_eq!(parse_metric(None).unwrap(), DistanceType::L2);
+        assert_eq!(parse_metric(Some("Cosine")).unwrap(), DistanceType::Cosine);
+        assert!(parse_metric(Some("manhattan")).is_err());
+
+        assert_eq!(distance_to_score(1.0, DistanceType::L2), 0.5);
+        assert_eq!(distance_to_score(0.25, DistanceType::Cosine), 0.75);
+        assert_eq!(distance_to_score(-1.0, DistanceType::Dot), 2.0);
+    }
+
+    #[tokio::test]
+    async fn test_create_vector_index_threshold() {
+        let db = test_db("create_index").await;
+
+        let (schema, batch) = build_chunk_batch(&sample_chunks(10, 16), 16).unwrap();
+        replace_table(&db, "doc_small", schema, vec![Ok(batch)]).await.unwrap();
+        let small = db.open_table("doc_small").execute().await.unwrap();
+        assert!(!create_vector_index(&small, &IndexOptions::default()).await.unwrap());
+        assert!(small.list_indices().await.unwrap().is_empty());
+
+        let (schema, batch) = build_chunk_batch(&sample_chunks(300, 16), 16).unwrap();
+        replace_table(&db, "doc_large", schema, vec![Ok(batch)]).await.unwrap();
+        let large = db.open_table("doc_large").execute().await.unwrap();
+        let options = IndexOptions {
+            num_partitions: Some(2),
+            num_sub_vectors: Some(4),
+            metric: None,
+        };
+        assert!(create_vector_index(&large, &options).await.unwrap());
+        assert_eq!(large.list_indices().await.unwrap().len(), 1);
+
+        // Search through the index still finds the exact match among the top-k
+        let results = search_table(&large, vec![150.0; 16], 5, DistanceType::L2, None, false).await.unwrap();
+        assert_eq!(results.len(), 5);
+        assert!(results.iter().any(|r| r.id == "chunk-150"));
+    }
+
+    #[tokio::test]
+    async fn test_search_returns_chunk_fields() {
+        let db = test_db("search_chunk_fields").await;
+
+        let chunks = sample_chunks(5, 4);
+        let (schema, batch) = build_chunk_batch(&chunks, 4).unwrap();
+        replace_table(&db, "doc_paper", schema, vec![Ok(batch)]).await.unwrap();
+        let table = db.open_table("doc_paper").execute().await.unwrap();
+
+        let results = search_table(&table, vec![3.0; 4], 1, DistanceType::L2, None, false).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, "chunk-3");
+        assert_eq!(results[0].chunk_index, chunks[3].chunk_index);
+        assert_eq!(results[0].text_length, chunks[3].text_length);
+    }
+
+    #[tokio::test]
+    async fn test_search_with_filter() {
+        let db = test_db("search_filter").await;
+
+        let (schema, batch) = build_chunk_batch(&sample_chunks(6, 4), 4).unwrap();
+        replace_table(&db, "doc_paper", schema, vec![Ok(batch)]).await.unwrap();
+        let table = db.open_table("doc_paper").execute().await.unwrap();
+
+        let results = search_table(
+            &table,
+            vec![0.0; 4],
+            10,
+            DistanceType::L2,
+            Some("chunk_index >= 2 AND chunk_index < 4"),
+            false,
+        )
+        .await
+        .unwrap();
+        let mut indices: Vec<i32> = results.iter().map(|r| r.chunk_index).collect();
+        indices.sort();
+        assert_eq!(indices, vec![2, 3]);
+
+        let error = search_table(
+            &table,
+            vec![0.0; 4],
+            10,
+            DistanceType::L2,
+            Some("chunk_index >= 2 AND missing_column = 1"),
+            false,
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(error, VectorStoreError::InvalidArgument(_)), "{:?}", error);
+        assert!(error.to_string().contains("'missing_column = 1'"), "{}", error);
+    }
+
+    #[test]
+    fn test_split_filter_clauses() {
+        assert_eq!(
+            split_filter_clauses("chunk_index >= 10 and chunk_index < 50 OR id = 'a'"),
+            vec!["chunk_index >= 10", "chunk_index < 50", "id = 'a'"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_read_chunks_in_order() {
+        let db = test_db("read_chunks").await;
+
+        // Insert out of order to make sure the scan is sorted
+        let mut chunks = sample_chunks(4, 4);
+        chunks.reverse();
+        let (schema, batch) = build_chunk_batch(&chunks, 4).unwrap();
+        replace_table(&db, "doc_paper", schema, vec![Ok(batch)]).await.unwrap();
+        let table = db.open_table("doc_paper").execute().await.unwrap();
+
+        let without_vectors = read_chunks(&table, false).await.unwrap();
+        let indices: Vec<i32> = without_vectors.iter().map(|c| c.chunk_index).collect();
+        assert_eq!(indices, vec![0, 1, 2, 3]);
+        assert!(without_vectors.iter().all(|c| c.vector.is_empty()));
+
+        let with_vectors = read_chunks(&table, true).await.unwrap();
+        assert_eq!(with_vectors[2].vector, vec![2.0; 4]);
+    }
+
+    #[tokio::test]
+    async fn test_append_creates_then_extends_table() {
+        let db = test_db("append").await;
+
+        let (schema, batch) = build_chunk_batch(&sample_chunks(2, 4), 4).unwrap();
+        append_to_table(&db, "doc_paper", schema.clone(), batch.clone()).await.unwrap();
+        append_to_table(&db, "doc_paper", schema, batch).await.unwrap();
+
+        let table = db.open_table("doc_paper").execute().await.unwrap();
+        assert_eq!(table.count_rows(None).await.unwrap(), 4);
+
+        // A batch with a different vector dimension must not be appended
+        let (schema, batch) = build_chunk_batch(&sample_chunks(1, 8), 8).unwrap();
+        let error = append_to_table(&db, "doc_paper", schema, batch).await.unwrap_err();
+        assert!(matches!(error, VectorStoreError::SchemaMismatch(_)), "{:?}", error);
+        assert!(error.to_string().contains("Schema mismatch: column 'vector'"), "{}", error);
+        assert_eq!(table.count_rows(None).await.unwrap(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_document_stats() {
+        let db = test_db("stats").await;
+
+        let (schema, batch) = build_chunk_batch(&sample_chunks(3, 4), 4).unwrap();
+        append_to_table(&db, "doc_paper", schema, batch).await.unwrap();
+        write_store_setting(&db, VECTOR_DIM_KEY, "4").await.unwrap();
+
+        // A directory that looks like a table but holds no valid dataset
+        let broken = Path::new(db.uri()).join("doc_broken.lance");
+        std::fs::create_dir_all(&broken).unwrap();
+        std::fs::write(broken.join("junk"), b"not a lance table").unwrap();
+
+        let mut stats = collect_document_stats(&db).await.unwrap();
+        stats.sort_by(|a, b| a.document_id.cmp(&b.document_id));
+
+        // Store metadata is not a document
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].document_id, "broken");
+        assert!(stats[0].error.is_some());
+        assert_eq!(stats[1].document_id, "paper");
+        assert_eq!(stats[1].row_count, 3);
+        assert!(stats[1].size_bytes > 0);
+        assert!(stats[1].error.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_document_metadata_round_trip() {
+        let db = test_db("metadata").await;
+        assert_eq!(read_document_metadata(&db, "paper").await.unwrap(), None);
+
+        let metadata = DocumentMetadata {
+            title: Some("Attention Is All You Need".to_string()),
+            source: Some("/papers/attention.pdf".to_string()),
+            embedding_model: Some("all-MiniLM-L6-v2".to_string()),
+            vector_dim: Some(384),
+            created_at: None,
+        };
+        write_document_metadata(&db, "paper", &metadata).await.unwrap();
+        write_document_metadata(&db, "other", &DocumentMetadata::default()).await.unwrap();
+        assert_eq!(read_document_metadata(&db, "paper").await.unwrap(), Some(metadata));
+
+        // Writing again replaces the previous entry rather than adding a second row
+        let updated = DocumentMetadata {
+            title: Some("Renamed".to_string()),
+            ..Default::default()
+        };
+        write_document_metadata(&db, "paper", &updated).await.unwrap();
+        assert_eq!(read_document_metadata(&db, "paper").await.unwrap(), Some(updated));
+        let table = db.open_table(DOCUMENT_METADATA_TABLE).execute().await.unwrap();
+        assert_eq!(table.count_rows(None).await.unwrap(), 2);
+
+        delete_document_metadata(&db, "paper").await.unwrap();
+        assert_eq!(read_document_metadata(&db, "paper").await.unwrap(), None);
+        assert_eq!(
+            read_document_metadata(&db, "other").await.unwrap(),
+            Some(DocumentMetadata::default())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_search_rejects_dimension_mismatch() {
+        let db = test_db("search_dim").await;
+        let (schema, batch) = build_chunk_batch(&sample_chunks(3, 384), 384).unwrap();
+        append_to_table(&db, "doc_paper", schema, batch).await.unwrap();
+        let table = db.open_table("doc_paper").execute().await.unwrap();
+
+        let error = search_table(&table, vec![0.5; 768], 2, DistanceType::L2, None, false)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            error,
+            VectorStoreError::SchemaMismatch(
+                "Query embedding dimension mismatch: expected 384-dim vector, got 768".to_string()
+            )
+        );
+
+        let error = search_table(&table, Vec::new(), 2, DistanceType::L2, None, false)
+            .await
+            .unwrap_err();
+        assert_eq!(error, VectorStoreError::InvalidArgument("Query embedding is empty".to_string()));
+
+        assert_eq!(
+            search_table(&table, vec![0.5; 384], 2, DistanceType::L2, None, false).await.unwrap().len(),
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn test_delete_documents_reports_each_id() {
+        let db = test_db("delete_many").await;
+        for table_name in ["doc_a", "doc_b", "doc_keep"] {
+            let (schema, batch) = build_chunk_batch(&sample_chunks(1, 4), 4).unwrap();
+            append_to_table(&db, table_name, schema, batch).await.unwrap();
+        }
+
+        let ids = vec!["a".to_string(), "missing".to_string(), "b".to_string()];
+        let results = delete_documents(&db, &ids).await.unwrap();
+        assert_eq!(results["a"], DeletionResult::Deleted);
+        assert_eq!(results["b"], DeletionResult::Deleted);
+        assert_eq!(results["missing"], DeletionResult::NotFound);
+
+        let remaining = db.table_names().execute().await.unwrap();
+        assert_eq!(remaining, vec!["doc_keep".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_error_kinds() {
+        let db = test_db("error_kinds").await;
+        let error = open_table(&db, "doc_missing").await.err().unwrap();
+        assert!(matches!(error, VectorStoreError::TableNotFound(_)), "{:?}", error);
+
+        assert_eq!(
+            serde_json::to_value(VectorStoreError::NotInitialized).unwrap(),
+            serde_json::json!({
+                "kind": "not_initialized",
+                "message": "Vector store not initialized: call vector_store_initialize first",
+            })
+        );
+        assert_eq!(
+            serde_json::to_value(VectorStoreError::Query("Search failed: boom".to_string())).unwrap(),
+            serde_json::json!({ "kind": "query", "message": "Search failed: boom" })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_connection_cache_reuses_and_replaces() {
+        let first = std::env::temp_dir().join(format!("redink_vector_store_cache_a_{}", std::process::id()));
+        let second = std::env::temp_dir().join(format!("redink_vector_store_cache_b_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&first);
+        let _ = std::fs::remove_dir_all(&second);
+        let (first, second) = (first.to_str().unwrap(), second.to_str().unwrap());
+        let cache = Mutex::new(None);
+
+        let db = cached_connection(&cache, first).await.unwrap();
+        let (schema, batch) = build_chunk_batch(&sample_chunks(1, 4), 4).unwrap();
+        append_to_table(&db, "doc_paper", schema, batch).await.unwrap();
+
+        let reused = cached_connection(&cache, first).await.unwrap();
+        assert_eq!(reused.uri(), db.uri());
+        assert!(table_exists(&reused, "doc_paper").await.unwrap());
+
+        let other = cached_connection(&cache, second).await.unwrap();
+        assert_eq!(other.uri(), second);
+        assert!(!table_exists(&other, "doc_paper").await.unwrap());
+        assert_eq!(cache.lock().await.as_ref().unwrap().0, second);
+    }
+
+    #[test]
+    fn test_batch_vector_dim_rejects_ragged_vectors() {
+        let mut chunks = sample_chunks(3, 4);
+        assert_eq!(batch_vector_dim(&chunks, None), Ok(4));
+        assert_eq!(batch_vector_dim(&chunks, Some(4)), Ok(4));
+        assert_eq!(batch_vector_dim(&[], None), Ok(384));
+        assert_eq!(batch_vector_dim(&[], Some(768)), Ok(768));
+
+        assert_eq!(
+            batch_vector_dim(&chunks, Some(8)),
+            Err(VectorStoreError::SchemaMismatch(
+                "Embedding dimension mismatch: expected 8-dim vectors, got 4".to_string()
+            ))
+        );
+
+        chunks[2].vector.pop();
+        assert_eq!(
+            batch_vector_dim(&chunks, None),
+            Err(VectorStoreError::InvalidArgument(
+                "Ragged vectors: chunk 'chunk-2' has 3 dimensions but chunk 'chunk-0' has 4".to_string()
+            ))
+        );
+
+        chunks[0].vector.clear();
+        assert_eq!(
+            batch_vector_dim(&chunks, None),
+            Err(VectorStoreError::InvalidArgument("Chunk 'chunk-0' has an empty vector".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_batch_vector_dim_rejects_non_finite_values() {
+        let mut chunks = sample_chunks(3, 4);
+        chunks[1].vector[2] = f32::NAN;
+        assert_eq!(
+            batch_vector_dim(&chunks, None),
+            Err(VectorStoreError::InvalidArgument("Chunk 'chunk-1' has a NaN or infinite vector component".to_string()))
+        );
+
+        chunks[1].vector[2] = 1.0;
+        chunks[2].vector[0] = f32::NEG_INFINITY;
+        assert_eq!(
+            batch_vector_dim(&chunks, None),
+            Err(VectorStoreError::InvalidArgument("Chunk 'chunk-2' has a NaN or infinite vector component".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_normalize_vectors() {
+        let mut chunks = sample_chunks(3, 4);
+        chunks[2].vector = vec![3.0, 0.0, -4.0, 0.0];
+        normalize_vectors(&mut chunks);
+
+        // chunk-0 is all zeros and stays that way
+        assert_eq!(chunks[0].vector, vec![0.0; 4]);
+        assert_eq!(chunks[1].vector, vec![0.5; 4]);
+        assert_eq!(chunks[2].vector, vec![0.6, 0.0, -0.8, 0.0]);
+        for chunk in &chunks[1..] {
+            let norm = chunk.vector.iter().map(|value| value * value).sum::<f32>().sqrt();
+            assert!((norm - 1.0).abs() < 1e-6);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_hybrid_search_merges_signals() {
+        let db = test_db("hybrid").await;
+        let chunks: Vec<ChunkData> = ["the Navier-Stokes equations", "fluid turbulence models", "boundary layers"]
+            .iter()
+            .enumerate()
+            .map(|(i, text)| ChunkData {
+                id: format!("chunk-{}", i),
+                text: text.to_string(),
+                // chunk-2 is nearest to the query vector, chunk-0 furthest
+                vector: vec![(2 - i) as f32; 4],
+                chunk_index: i as i32,
+                text_length: text.len() as i32,
+            })
+            .collect();
+        let (schema, batch) = build_chunk_batch(&chunks, 4).unwrap();
+        append_to_table(&db, "doc_paper", schema, batch).await.unwrap();
+        let table = db.open_table("doc_paper").execute().await.unwrap();
+
+        let results = hybrid_search_table(&table, vec![0.0; 4], "navier-stokes", 2, 0.5)
+            .await
+            .unwrap();
+        let ids: Vec<&str> = results.iter().map(|hit| hit.result.id.as_str()).collect();
+        // The exact-term hit outranks the nearer chunk-1 and even chunk-2
+        assert_eq!(ids, vec!["chunk-0", "chunk-2"]);
+        assert_eq!(results[0].matched, MatchSignal::Keyword);
+        assert_eq!(results[1].matched, MatchSignal::Vector);
+
+        // Pure vector weighting ignores the keyword hit's boost
+        let results = hybrid_search_table(&table, vec![0.0; 4], "navier-stokes", 2, 1.0)
+            .await
+            .unwrap();
+        assert_eq!(results[1].result.id, "chunk-1");
+
+        // Wildcards in the query are literals, so `_` matches no chunk
+        let results = hybrid_search_table(&table, vec![0.0; 4], "_", 3, 0.0).await.unwrap();
+        assert!(results.iter().all(|hit| hit.matched == MatchSignal::Vector));
+    }
+
+    #[test]
+    fn test_merge_hybrid_results_breaks_ties() {
+        let hit = |id: &str, chunk_index: i32| VectorSearchResult {
+            id: id.to_string(),
+            text: String::new(),
+            score: 0.5,
+            distance: 1.0,
+            chunk_index,
+            text_length: 0,
+            vector: None,
+        };
+        let merged = merge_hybrid_results(vec![hit("b", 1), hit("c", 0), hit("a", 1)], Vec::new(), &[], 1.0, 3);
+        let ids: Vec<&str> = merged.iter().map(|hit| hit.result.id.as_str()).collect();
+        assert_eq!(ids, vec!["c", "a", "b"]);
+    }
+
+    #[test]
+    fn test_keyword_helpers() {
+        let terms = keyword_terms("Navier  navier O'Brien");
+        assert_eq!(terms, vec!["navier".to_string(), "o'brien".to_string()]);
+        assert_eq!(
+            keyword_filter(&terms),
+            "lower(text) LIKE '%navier%' ESCAPE '\\' OR lower(text) LIKE '%o''brien%' ESCAPE '\\'"
+        );
+        assert_eq!(escape_like("5%_a\\b"), "5\\%\\_a\\\\b");
+        assert_eq!(keyword_score("Navier flow", &terms), 0.5);
+        assert_eq!(keyword_score("anything", &[]), 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_optimize_compacts_fragments() {
+        let db = test_db("optimize").await;
+        for _ in 0..3 {
+            let (schema, batch) = build_chunk_batch(&sample_chunks(2, 4), 4).unwrap();
+            append_to_table(&db, "doc_paper", schema, batch).await.unwrap();
+        }
+
+        let report = optimize_table(&db, "doc_paper").await.unwrap();
+        assert_eq!(report.document_id, "paper");
+        assert_eq!(report.fragments_removed, 3);
+        assert_eq!(report.fragments_added, 1);
+        let table = db.open_table("doc_paper").execute().await.unwrap();
+        assert_eq!(table.count_rows(None).await.unwrap(), 6);
+
+        // Running again on a compact table changes nothing
+        let report = optimize_table(&db, "doc_paper").await.unwrap();
+        assert_eq!(report.fragments_removed, 0);
+        assert_eq!(report.fragments_added, 0);
+    }
+
+    #[test]
+    fn test_table_name_for_is_reversible() {
+        assert_eq!(table_name_for("paper"), "doc_paper");
+        // These used to collapse to the same `doc_paper_1` table
+        assert_eq!(table_name_for("paper 1"), "doc_paper_201");
+        assert_eq!(table_name_for("paper-1"), "doc_paper_2d1");
+        assert_eq!(table_name_for("paper_1"), "doc_paper_5f1");
+        assert_ne!(table_name_for("paper 1"), table_name_for("paper-1"));
+
+        for document_id in ["paper", "paper 1", "paper-1", "paper_1", "2401.12345v2", "论文"] {
+            assert_eq!(document_id_for(&table_name_for(document_id)).as_deref(), Some(document_id));
+        }
+        assert_eq!(document_id_for("doc_old_name").as_deref(), Some("old_name"));
+        assert_eq!(document_id_for("_metadata"), None);
+    }
+
+    #[tokio::test]
+    async fn test_migrate_legacy_table() {
+        let db = test_db("migrate_legacy").await;
+        let state = StdArc::new(Mutex::new(VectorStoreState::new()));
+        let document_id = "arxiv-2311.18775";
+        assert_eq!(legacy_table_name_for(document_id), "doc_arxiv_2311_18775");
+
+        // A table written under the old naming scheme is found and moved on first access
+        let (schema, batch) = build_chunk_batch(&sample_chunks(3, 4), 4).unwrap();
+        append_to_table(&db, "doc_arxiv_2311_18775", schema, batch).await.unwrap();
+        migrate_legacy_table_locking(&state, &db, document_id).await.unwrap();
+
+        let table = open_table(&db, &table_name_for(document_id)).await.unwrap();
+        assert_eq!(table.count_rows(None).await.unwrap(), 3);
+        assert!(!table_exists(&db, "doc_arxiv_2311_18775").await.unwrap());
+        assert!(read_document_metadata(&db, document_id).await.unwrap().is_some());
+        assert!(!migrate_legacy_table(&db, document_id).await.unwrap());
+
+        // Ids that mapped to the same name under both schemes have nothing to move
+        let (schema, batch) = build_chunk_batch(&sample_chunks(2, 4), 4).unwrap();
+        append_to_table(&db, "doc_paper", schema, batch).await.unwrap();
+        assert!(!migrate_legacy_table(&db, "paper").await.unwrap());
+        assert_eq!(open_table(&db, "doc_paper").await.unwrap().count_rows(None).await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_min_score_excludes_weak_matches() {
+        let db = test_db("min_score").await;
+        let (schema, batch) = build_chunk_batch(&sample_chunks(4, 4), 4).unwrap();
+        append_to_table(&db, "doc_paper", schema, batch).await.unwrap();
+        let table = db.open_table("doc_paper").execute().await.unwrap();
+
+        // L2 scores for chunks 0..4 are 1, 1/5, 1/17 and 1/37
+        let mut results = search_table(&table, vec![0.0; 4], 4, DistanceType::L2, None, false).await.unwrap();
+        assert_eq!(results.len(), 4);
+        retain_min_score(&mut results, Some(0.1));
+        let ids: Vec<&str> = results.iter().map(|result| result.id.as_str()).collect();
+        assert_eq!(ids, vec!["chunk-0", "chunk-1"]);
+
+        let mut results = search_table(&table, vec![0.0; 4], 4, DistanceType::L2, None, false).await.unwrap();
+        retain_min_score(&mut results, None);
+        assert_eq!(results.len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_search_includes_vectors_on_request() {
+        let db = test_db("include_vectors").await;
+        let chunks = vec![
+            ChunkData {
+                id: "chunk-0".to_string(),
+                text: "first".to_string(),
+                vector: vec![0.125, -1.5, 3.0e-7, 42.0],
+                chunk_index: 0,
+                text_length: 5,
+            },
+            ChunkData {
+                id: "chunk-1".to_string(),
+                text: "second".to_string(),
+                vector: vec![f32::MIN_POSITIVE, 0.1, -0.2, 1.0e10],
+                chunk_index: 1,
+                text_length: 6,
+            },
+        ];
+        let (schema, batch) = build_chunk_batch(&chunks, 4).unwrap();
+        append_to_table(&db, "doc_paper", schema, batch).await.unwrap();
+        let table = db.open_table("doc_paper").execute().await.unwrap();
+
+        let results = search_table(&table, vec![0.0; 4], 2, DistanceType::L2, None, true).await.unwrap();
+        assert_eq!(results.len(), 2);
+        for result in &results {
+            let chunk = chunks.iter().find(|chunk| chunk.id == result.id).unwrap();
+            assert_eq!(result.vector.as_ref(), Some(&chunk.vector));
+        }
+
+        let results = search_table(&table, vec![0.0; 4], 2, DistanceType::L2, None, false).await.unwrap();
+        assert!(results.iter().all(|result| result.vector.is_none()));
+        assert!(!serde_json::to_string(&results[0]).unwrap().contains("vector"));
+    }
+
+    #[tokio::test]
+    async fn test_export_import_round_trip() {
+        let db = test_db("export_import").await;
+        let chunks = sample_chunks(5, 4);
+        let (schema, batch) = build_chunk_batch(&chunks, 4).unwrap();
+        append_to_table(&db, "doc_paper", schema, batch).await.unwrap();
+        let table = db.open_table("doc_paper").execute().await.unwrap();
+
+        let export_path = std::env::temp_dir().join(format!("redink_export_{}.arrow", std::process::id()));
+        assert_eq!(export_table(&table, &export_path).await.unwrap(), 5);
+
+        let other = test_db("export_import_target").await;
+        assert_eq!(import_document(&other, "paper copy", &export_path).await.unwrap(), 5);
+        let imported = open_table(&other, &table_name_for("paper copy")).await.unwrap();
+        let round_trip = read_chunks(&imported, true).await.unwrap();
+        assert_eq!(round_trip.len(), chunks.len());
+        for (original, copy) in chunks.iter().zip(&round_trip) {
+            assert_eq!((&original.id, &original.text, &original.vector), (&copy.id, &copy.text, &copy.vector));
+        }
+        assert_eq!(read_store_setting(&other, VECTOR_DIM_KEY).await.unwrap(), Some("4".to_string()));
+        assert_eq!(
+            read_document_metadata(&other, "paper copy").await.unwrap().and_then(|m| m.vector_dim),
+            Some(4)
+        );
+
+        // A store that already holds 8-dim vectors refuses the 4-dim export
+        let mismatched = test_db("export_import_mismatch").await;
+        write_store_setting(&mismatched, VECTOR_DIM_KEY, "8").await.unwrap();
+        let error = import_document(&mismatched, "paper", &export_path).await.unwrap_err();
+        assert_eq!(error.kind(), "schema_mismatch");
+
+        let _ = std::fs::remove_file(&export_path);
+    }
+
+    #[test]
+    fn test_read_export_file_rejects_bad_files() {
+        let dir = std::env::temp_dir().join(format!("redink_export_bad_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let corrupt = dir.join("corrupt.arrow");
+        std::fs::write(&corrupt, b"definitely not arrow").unwrap();
+        assert_eq!(read_export_file(&corrupt).unwrap_err().kind(), "invalid_argument");
+
+        let write = |path: &Path, schema: &Schema, version: Option<&str>| {
+            let mut writer = FileWriter::try_new(std::fs::File::create(path).unwrap(), schema).unwrap();
+            if let Some(version) = version {
+                writer.write_metadata(EXPORT_FORMAT_KEY, version);
+            }
+            writer.finish().unwrap();
+        };
+
+        let future = dir.join("future.arrow");
+        write(&future, &chunk_schema(4), Some("2"));
+        assert_eq!(
+            read_export_file(&future).unwrap_err(),
+            VectorStoreError::SchemaMismatch("Unsupported export format version 2 (expected 1)".to_string())
+        );
+
+        let unversioned = dir.join("unversioned.arrow");
+        write(&unversioned, &chunk_schema(4), None);
+        assert_eq!(read_export_file(&unversioned).unwrap_err().kind(), "invalid_argument");
+
+        let missing = dir.join("missing.arrow");
+        let fields: Vec<Field> = chunk_schema(4)
+            .fields()
+            .iter()
+            .filter(|field| field.name() != "text")
+            .map(|field| field.as_ref().clone())
+            .collect();
+        write(&missing, &Schema::new(fields), Some(EXPORT_FORMAT_VERSION));
+        assert_eq!(
+            read_export_file(&missing).unwrap_err(),
+            VectorStoreError::SchemaMismatch("Export file is missing column 'text'".to_string())
+        );
+
+        let empty = dir.join("empty.arrow");
+        write(&empty, &chunk_schema(4), Some(EXPORT_FORMAT_VERSION));
+        let (_, batches, vector_dim) = read_export_file(&empty).unwrap();
+        assert!(batches.is_empty());
+        assert_eq!(vector_dim, 4);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_total_stats() {
+        let db = test_db("total_stats").await;
+        assert_eq!(total_stats(&collect_document_stats(&db).await.unwrap()), TotalStats::default());
+
+        for (document_id, count) in [("a", 3), ("b", 5)] {
+            let (schema, batch) = build_chunk_batch(&sample_chunks(count, 4), 4).unwrap();
+            append_to_table(&db, &table_name_for(document_id), schema, batch).await.unwrap();
+        }
+        // Metadata tables are not documents
+        write_document_metadata(&db, "a", &DocumentMetadata::default()).await.unwrap();
+
+        let totals = total_stats(&collect_document_stats(&db).await.unwrap());
+        assert_eq!((totals.document_count, totals.chunk_count), (2, 8));
+        assert!(totals.errors.is_empty());
+
+        let broken = DocumentStats {
+            document_id: "c".to_string(),
+            row_count: 0,
+            size_bytes: 0,
+            error: Some("Failed to open table".to_string()),
+        };
+        let totals = total_stats(&[broken]);
+        assert_eq!((totals.document_count, totals.chunk_count), (1, 0));
+        assert_eq!(totals.errors, vec!["c: Failed to open table".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_reindex_swaps_chunks_and_metadata() {
+        let db = test_db("reindex").await;
+        let (schema, batch) = build_chunk_batch(&sample_chunks(3, 4), 4).unwrap();
+        append_to_table(&db, &table_name_for("paper"), schema, batch).await.unwrap();
+        write_store_setting(&db, VECTOR_DIM_KEY, "4").await.unwrap();
+        let metadata = DocumentMetadata {
+            title: Some("Attention".to_string()),
+            embedding_model: Some("old-model".to_string()),
+            vector_dim: Some(4),
+            ..Default::default()
+        };
+        write_document_metadata(&db, "paper", &metadata).await.unwrap();
+
+        let report = reindex_document(&db, "paper", &sample_chunks(5, 8), Some("new-model".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(
+            report,
+            ReindexReport { document_id: "paper".to_string(), old_count: 3, new_count: 5 }
+        );
+
+        let table = open_table(&db, &table_name_for("paper")).await.unwrap();
+        assert_eq!(table_vector_dim(&table).await.unwrap(), 8);
+        assert_eq!(read_chunks(&table, true).await.unwrap()[4].vector, vec![4.0; 8]);
+        assert_eq!(read_store_setting(&db, VECTOR_DIM_KEY).await.unwrap(), Some("8".to_string()));
+        let metadata = read_document_metadata(&db, "paper").await.unwrap().unwrap();
+        assert_eq!(metadata.embedding_model.as_deref(), Some("new-model"));
+        assert_eq!(metadata.vector_dim, Some(8));
+        assert_eq!(metadata.title.as_deref(), Some("Attention"));
+
+        // Ragged input is rejected before the old table is touched
+        let mut ragged = sample_chunks(2, 8);
+        ragged[1].vector.pop();
+        assert!(reindex_document(&db, "paper", &ragged, None).await.is_err());
+        assert_eq!(table.count_rows(None).await.unwrap(), 5);
+
+        // A document that was never indexed reports no old chunks
+        let report = reindex_document(&db, "fresh", &sample_chunks(2, 8), None).await.unwrap();
+        assert_eq!((report.old_count, report.new_count), (0, 2));
+    }
+
+    #[tokio::test]
+    async fn test_reindex_keeps_store_dim_of_other_documents() {
+        let db = test_db("reindex_two_documents").await;
+        write_store_setting(&db, VECTOR_DIM_KEY, "4").await.unwrap();
+        for document_id in ["paper", "notes"] {
+            let (schema, batch) = build_chunk_batch(&sample_chunks(3, 4), 4).unwrap();
+            append_to_table(&db, &table_name_for(document_id), schema, batch).await.unwrap();
+            let metadata = DocumentMetadata { vector_dim: Some(4), ..Default::default() };
+            write_document_metadata(&db, document_id, &metadata).await.unwrap();
+        }
+
+        // "notes" still holds 4-dim vectors, so the store keeps expecting those
+        reindex_document(&db, "paper", &sample_chunks(3, 8), None).await.unwrap();
+        assert_eq!(read_store_setting(&db, VECTOR_DIM_KEY).await.unwrap(), Some("4".to_string()));
+        assert_eq!(expected_ingest_dim(&db, "paper", Some(4)).await.unwrap(), Some(("document", 8)));
+        assert_eq!(expected_ingest_dim(&db, "notes", Some(4)).await.unwrap(), Some(("document", 4)));
+        assert_eq!(expected_ingest_dim(&db, "new", Some(4)).await.unwrap(), Some(("store", 4)));
+
+        // Once every document has moved to the new model the store follows
+        reindex_document(&db, "notes", &sample_chunks(3, 8), None).await.unwrap();
+        assert_eq!(read_store_setting(&db, VECTOR_DIM_KEY).await.unwrap(), Some("8".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_ingest_chunks_reports_progress() {
+        let db = test_db("ingest_progress").await;
+
+        for append in [false, true] {
+            let table_name = format!("doc_progress_{}", append);
+            let mut progress = Vec::new();
+            ingest_chunks(&db, &table_name, &sample_chunks(10, 4), 4, append, 4, &mut |n| progress.push(n))
+                .await
+                .unwrap();
+            assert_eq!(progress, vec![4, 8, 10]);
+            let table = open_table(&db, &table_name).await.unwrap();
+            assert_eq!(table.count_rows(None).await.unwrap(), 10);
+        }
+
+        // The second batch has a malformed vector: appending keeps the first batch
+        let mut chunks = sample_chunks(8, 4);
+        chunks[5].vector.pop();
+        let error = ingest_chunks(&db, "doc_broken", &chunks, 4, true, 4, &mut |_| {})
+            .await
+            .unwrap_err();
+        assert!(error.to_string().ends_with("(4 of 8 chunks committed)"), "{}", error);
+        let table = open_table(&db, "doc_broken").await.unwrap();
+        assert_eq!(table.count_rows(None).await.unwrap(), 4);
+
+        // ...while a replacement commits nothing and leaves the old chunks alone
+        let error = ingest_chunks(&db, "doc_broken", &chunks, 4, false, 4, &mut |_| {})
+            .await
+            .unwrap_err();
+        assert!(error.to_string().ends_with("(0 of 8 chunks committed, existing chunks kept)"), "{}", error);
+        assert_eq!(table.count_rows(None).await.unwrap(), 4);
+
+        // An empty replacement still leaves an (empty) table behind
+        ingest_chunks(&db, "doc_empty", &[], 4, false, 4, &mut |_| {}).await.unwrap();
+        assert_eq!(open_table(&db, "doc_empty").await.unwrap().count_rows(None).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_writes_to_same_document_serialize() {
+        let db = test_db("concurrent_writes").await;
+        let state = StdArc::new(Mutex::new(VectorStoreState::new()));
+
+        // Two replacements racing on one document: exactly one of them wins, whole
+        let writers: Vec<_> = [3, 5]
+            .into_iter()
+            .map(|count| {
+                let (db, state) = (db.clone(), state.clone());
+                tokio::spawn(async move {
+                    let _table_lock = lock_table(&state, "doc_paper").await;
+                    ingest_chunks(&db, "doc_paper", &sample_chunks(count, 4), 4, false, 2, &mut |_| {}).await
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.await.unwrap().unwrap();
+        }
+
+        let table = open_table(&db, "doc_paper").await.unwrap();
+        let chunks = read_chunks(&table, false).await.unwrap();
+        assert!(chunks.len() == 3 || chunks.len() == 5, "got {} chunks", chunks.len());
+        let indices: Vec<i32> = chunks.iter().map(|chunk| chunk.chunk_index).collect();
+        assert_eq!(indices, (0..chunks.len() as i32).collect::<Vec<_>>());
+        assert!(!table_exists(&db, "temp_doc_paper").await.unwrap());
+
+        // Concurrent appends all land
+        let writers: Vec<_> = (0..4)
+            .map(|_| {
+                let (db, state) = (db.clone(), state.clone());
+                tokio::spawn(async move {
+                    let _table_lock = lock_table(&state, "doc_stream").await;
+                    ingest_chunks(&db, "doc_stream", &sample_chunks(3, 4), 4, true, 2, &mut |_| {}).await
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.await.unwrap().unwrap();
+        }
+        let table = open_table(&db, "doc_stream").await.unwrap();
+        assert_eq!(table.count_rows(None).await.unwrap(), 12);
+    }
+
+    #[tokio::test]
+    async fn test_list_document_ids() {
+        let db = test_db("list_documents").await;
+        assert!(list_document_ids(&db).await.unwrap().is_empty());
+
+        let (schema, batch) = build_chunk_batch(&sample_chunks(2, 4), 4).unwrap();
+        for document_id in ["paper-2", "2401.00001v1"] {
+            append_to_table(&db, &table_name_for(document_id), schema.clone(), batch.clone()).await.unwrap();
+        }
+        // Leftover staging table, plus store and document metadata
+        append_to_table(&db, &format!("{}{}", TEMP_TABLE_PREFIX, table_name_for("paper-3")), schema, batch)
+            .await
+            .unwrap();
+        write_store_setting(&db, VECTOR_DIM_KEY, "4").await.unwrap();
+        write_document_metadata(&db, "paper-2", &DocumentMetadata::default()).await.unwrap();
+
+        assert_eq!(list_document_ids(&db).await.unwrap(), vec!["2401.00001v1".to_string(), "paper-2".to_string()]);
+    }
+
+    #[test]
+    fn test_remove_stale_temp_files() {
+        let dir = std::env::temp_dir().join(format!("redink_vector_store_temp_files_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("temp_doc_dir.arrow")).unwrap();
+        for name in ["temp_doc_paper.arrow", "temp_doc_other.arrow", "doc_paper.arrow", "temp_doc_paper.txt"] {
+            std::fs::write(dir.join(name), b"stale").unwrap();
+        }
+
+        assert_eq!(remove_stale_temp_files(&dir), 2);
+        assert!(!dir.join("temp_doc_paper.arrow").exists());
+        assert!(!dir.join("temp_doc_other.arrow").exists());
+        // Only temp Arrow files are touched
+        assert!(dir.join("doc_paper.arrow").exists());
+        assert!(dir.join("temp_doc_paper.txt").exists());
+        assert!(dir.join("temp_doc_dir.arrow").is_dir());
+
+        assert_eq!(remove_stale_temp_files(&dir.join("missing")), 0);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_prepare_storage_dir() {
+        let base = std::env::temp_dir().join(format!("redink_vector_store_storage_dir_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&base);
+
+        // Missing directories are created and the path comes back absolute
+        let nested = base.join("a").join("..").join("vectors");
+        let storage_dir = prepare_storage_dir(nested.to_str().unwrap()).unwrap();
+        assert!(storage_dir.is_absolute());
+        assert!(storage_dir.is_dir());
+        assert_eq!(storage_dir, std::fs::canonicalize(base.join("vectors")).unwrap());
+        assert!(!storage_dir.join(WRITE_PROBE_FILE).exists());
+
+        // A file in the way can't become a directory
+        let blocked = base.join("file");
+        std::fs::write(&blocked, b"").unwrap();
+        let error = prepare_storage_dir(blocked.join("vectors").to_str().unwrap()).unwrap_err();
+        assert!(matches!(error, VectorStoreError::Io(_)), "{:?}", error);
+
+        let _ = std::fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn test_canonical_storage_path() {
+        let base = std::env::temp_dir().join(format!("redink_vector_store_canonical_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&base);
+
+        // An explicit path matches the one initialize remembers for it
+        let nested = base.join("a").join("..").join("vectors");
+        let storage_dir = prepare_storage_dir(nested.to_str().unwrap()).unwrap();
+        assert_eq!(canonical_storage_path(nested.to_string_lossy().into_owned()), storage_dir.to_string_lossy());
+
+        let missing = base.join("missing").to_string_lossy().into_owned();
+        assert_eq!(canonical_storage_path(missing.clone()), missing);
+
+        let _ = std::fs::remove_dir_all(&base);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_prepare_storage_dir_rejects_read_only() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("redink_vector_store_read_only_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o555)).unwrap();
+
+        // Root ignores permission bits, so there is nothing to check there
+        if std::fs::write(dir.join("probe"), b"").is_err() {
+            let error = prepare_storage_dir(dir.to_str().unwrap()).unwrap_err();
+            assert!(matches!(&error, VectorStoreError::Io(message) if message.contains("not writable")), "{:?}", error);
+        }
+
+        std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_score_modes() {
+        assert_eq!(parse_score_mode(None).unwrap(), None);
+        assert_eq!(parse_score_mode(Some("EXP_DECAY")).unwrap(), Some(ScoreMode::ExpDecay));
+        assert!(parse_score_mode(Some("sigmoid")).is_err());
+
+        assert_eq!(ScoreMode::Inverse.score(1.0), 0.5);
+        assert_eq!(ScoreMode::Negative.score(1.5), -1.5);
+        assert_eq!(ScoreMode::ExpDecay.score(2.0), (-2.0f32).exp());
+        assert_eq!(ScoreMode::ExpDecay.score(0.0), 1.0);
+
+        let result = |distance: f32| VectorSearchResult {
+            id: "chunk".to_string(),
+            text: String::new(),
+            chunk_index: 0,
+            text_length: 0,
+            score: distance_to_score(distance, DistanceType::Cosine),
+            distance,
+            vector: None,
+        };
+        let mut results = vec![result(0.5), result(3.0)];
+        apply_score_mode(&mut results, None);
+        assert_eq!(results[0].score, 0.5);
+
+        apply_score_mode(&mut results, Some(ScoreMode::Inverse));
+        assert_eq!((results[0].score, results[1].score), (1.0 / 1.5, 0.25));
+        // The raw distance is never touched
+        assert_eq!((results[0].distance, results[1].distance), (0.5, 3.0));
+    }
+
+    #[tokio::test]
+    async fn test_export_all_import_all_round_trip() {
+        let source = test_db("export_all_source").await;
+        let (schema, batch) = build_chunk_batch(&sample_chunks(3, 4), 4).unwrap();
+        append_to_table(&source, &table_name_for("paper 1"), schema.clone(), batch).await.unwrap();
+        let (_, batch) = build_chunk_batch(&sample_chunks(2, 4), 4).unwrap();
+        append_to_table(&source, &table_name_for("paper-2"), schema, batch).await.unwrap();
+        write_store_setting(&source, VECTOR_DIM_KEY, "4").await.unwrap();
+        let metadata = DocumentMetadata {
+            title: Some("Paper One".to_string()),
+            embedding_model: Some("test-model".to_string()),
+            ..Default::default()
+        };
+        write_document_metadata(&source, "paper 1", &metadata).await.unwrap();
+
+        let dir = std::env::temp_dir().join(format!("redink_vector_store_export_all_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let manifest = export_all(&source, &dir).await.unwrap();
+        assert_eq!(manifest.vector_dim, Some(4));
+        let counts: Vec<(&str, usize)> =
+            manifest.documents.iter().map(|entry| (entry.document_id.as_str(), entry.row_count)).collect();
+        assert_eq!(counts, vec![("paper 1", 3), ("paper-2", 2)]);
+        assert_eq!(read_manifest(&dir).unwrap(), manifest);
+
+        // A file that went missing is reported without stopping the rest
+        std::fs::remove_file(dir.join(&manifest.documents[1].file)).unwrap();
+
+        let target = test_db("export_all_target").await;
+        let results = import_all(&target, &manifest, &dir).await;
+        assert_eq!(results["paper 1"], RestoreResult::Restored(3));
+        assert!(matches!(results["paper-2"], RestoreResult::Failed(_)));
+
+        assert_eq!(list_document_ids(&target).await.unwrap(), vec!["paper 1".to_string()]);
+        let restored = read_document_metadata(&target, "paper 1").await.unwrap().unwrap();
+        assert_eq!(restored.title.as_deref(), Some("Paper One"));
+        assert_eq!(restored.embedding_model.as_deref(), Some("test-model"));
+        assert_eq!(restored.vector_dim, Some(4));
+
+        // Row counts are checked against the manifest
+        let mut tampered = manifest.clone();
+        tampered.documents[0].row_count = 5;
+        let results = import_all(&target, &tampered, &dir).await;
+        assert!(matches!(&results["paper 1"], RestoreResult::Failed(message) if message.contains("manifest lists 5")));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_check_embedding_model() {
+        let db = test_db("embedding_model").await;
+        let metadata = DocumentMetadata {
+            embedding_model: Some("model-a".to_string()),
+            ..Default::default()
+        };
+        write_document_metadata(&db, "paper", &metadata).await.unwrap();
+
+        assert!(check_embedding_model(&db, "paper", Some("model-a")).await.is_ok());
+        assert!(check_embedding_model(&db, "paper", None).await.is_ok());
+        let error = check_embedding_model(&db, "paper", Some("model-b")).await.unwrap_err();
+        assert!(
+            matches!(&error, VectorStoreError::SchemaMismatch(message) if message.contains("model-a") && message.contains("model-b")),
+            "{:?}",
+            error
+        );
+
+        // Nothing recorded to compare against
+        assert!(check_embedding_model(&db, "legacy", Some("model-b")).await.is_ok());
+        write_document_metadata(&db, "untagged", &DocumentMetadata::default()).await.unwrap();
+        assert!(check_embedding_model(&db, "untagged", Some("model-b")).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_upsert_creates_then_replaces() {
+        let db = test_db("upsert").await;
+
+        let report = upsert_document(&db, "paper", &sample_chunks(3, 4)).await.unwrap();
+        assert_eq!(report, UpsertReport { action: UpsertAction::Created, row_count: 3 });
+        assert_eq!(read_store_setting(&db, VECTOR_DIM_KEY).await.unwrap().as_deref(), Some("4"));
+
+        let metadata = DocumentMetadata {
+            title: Some("Paper".to_string()),
+            ..read_document_metadata(&db, "paper").await.unwrap().unwrap()
+        };
+        write_document_metadata(&db, "paper", &metadata).await.unwrap();
+
+        let report = upsert_document(&db, "paper", &sample_chunks(2, 4)).await.unwrap();
+        assert_eq!(report, UpsertReport { action: UpsertAction::Replaced, row_count: 2 });
+        let table = db.open_table(table_name_for("paper")).execute().await.unwrap();
+        assert_eq!(table.count_rows(None).await.unwrap(), 2);
+        assert_eq!(read_document_metadata(&db, "paper").await.unwrap(), Some(metadata));
+
+        // A mismatched dimension leaves the document untouched
+        let error = upsert_document(&db, "paper", &sample_chunks(2, 8)).await.unwrap_err();
+        assert!(matches!(error, VectorStoreError::SchemaMismatch(_)), "{:?}", error);
+        assert_eq!(table.count_rows(None).await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_upsert_uses_document_and_store_dim() {
+        let db = test_db("upsert_dims").await;
+        write_store_setting(&db, VECTOR_DIM_KEY, "4").await.unwrap();
+        for document_id in ["paper", "notes"] {
+            upsert_document(&db, document_id, &sample_chunks(3, 4)).await.unwrap();
+        }
+
+        // After a reindex the document takes chunks from its new model
+        reindex_document(&db, "paper", &sample_chunks(3, 8), None).await.unwrap();
+        upsert_document(&db, "paper", &sample_chunks(2, 8)).await.unwrap();
+        let error = upsert_document(&db, "paper", &sample_chunks(2, 4)).await.unwrap_err();
+        assert!(matches!(error, VectorStoreError::SchemaMismatch(_)), "{:?}", error);
+
+        // An empty upsert keeps the dimension chunks will later be appended at
+        upsert_document(&db, "empty", &[]).await.unwrap();
+        let table = open_table(&db, &table_name_for("empty")).await.unwrap();
+        assert_eq!(table_vector_dim(&table).await.unwrap(), 4);
+        upsert_document(&db, "paper", &[]).await.unwrap();
+        let table = open_table(&db, &table_name_for("paper")).await.unwrap();
+        assert_eq!(table_vector_dim(&table).await.unwrap(), 8);
+    }
+
+    #[tokio::test]
+    async fn test_search_ipc_round_trip() {
+        let db = test_db("search_ipc").await;
+        let (schema, batch) = build_chunk_batch(&sample_chunks(5, 4), 4).unwrap();
+        replace_table(&db, "doc_paper", schema, vec![Ok(batch)]).await.unwrap();
+        let table = db.open_table("doc_paper").execute().await.unwrap();
+
+        let bytes = search_table_ipc(&table, vec![3.0; 4], 3, DistanceType::L2, None).await.unwrap();
+        let reader = arrow_ipc::reader::StreamReader::try_new(std::io::Cursor::new(bytes), None).unwrap();
+        assert_eq!(reader.schema(), search_result_schema());
+        let mut rows = Vec::new();
+        for batch in reader {
+            let batch = batch.unwrap();
+            let ids = column::<StringArray>(&batch, "id").unwrap();
+            let distances = column::<Float32Array>(&batch, "distance").unwrap();
+            let scores = column::<Float32Array>(&batch, "score").unwrap();
+            for i in 0..batch.num_rows() {
+                rows.push((ids.value(i).to_string(), distances.value(i), scores.value(i)));
+            }
+        }
+
+        let expected: Vec<(String, f32, f32)> = search_table(&table, vec![3.0; 4], 3, DistanceType::L2, None, false)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|result| (result.id, result.distance, result.score))
+            .collect();
+        assert_eq!(rows, expected);
+        assert_eq!(rows[0].0, "chunk-3");
+    }
+
+    #[test]
+    fn test_short_chunk_filtering_and_length_check() {
+        let chunk = |id: &str, text: &str, text_length: i32| ChunkData {
+            id: id.to_string(),
+            text: text.to_string(),
+            vector: vec![0.0; 4],
+            chunk_index: 0,
+            text_length,
+        };
+        let chunks = vec![
+            chunk("heading", "Intro", 5),
+            chunk("body", "A paragraph of real content.", 28),
+            // 6 chars, 7 UTF-8 bytes, 6 UTF-16 units
+            chunk("accented", "Schrö.", 7),
+            chunk("wrong", "Some text", 42),
+        ];
+
+        assert_eq!(mismatched_text_lengths(&chunks), vec!["wrong"]);
+
+        let (kept, skipped) = filter_short_chunks(chunks.clone(), Some(8));
+        let ids: Vec<&str> = kept.iter().map(|chunk| chunk.id.as_str()).collect();
+        assert_eq!(ids, vec!["body", "wrong"]);
+        assert_eq!(skipped, 2);
+
+        let (kept, skipped) = filter_short_chunks(chunks, None);
+        assert_eq!((kept.len(), skipped), (4, 0));
+    }
+
+    #[tokio::test]
+    async fn test_add_chunks_reports_inserted_and_skipped() {
+        let state = StdArc::new(Mutex::new(VectorStoreState::new()));
+        let path = std::env::temp_dir().join(format!("redink_vector_store_add_chunks_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&path);
+
+        let mut chunks = sample_chunks(4, 4);
+        chunks[2].text = "toc".to_string();
+        chunks[2].text_length = 3;
+        let mut progress = Vec::new();
+        let report = add_chunks(
+            &state,
+            "paper".to_string(),
+            chunks,
+            Some(path.to_string_lossy().into_owned()),
+            None,
+            None,
+            None,
+            None,
+            Some(5),
+            None,
+            &mut |update: &IngestProgress| progress.push(update.inserted),
+        )
+        .await
+        .unwrap();
+        assert_eq!(report, AddChunksReport { table_name: table_name_for("paper"), inserted: 3, skipped: 1 });
+        assert_eq!(progress.last(), Some(&3));
+
+        let db = connect(path.to_str().unwrap()).execute().await.unwrap();
+        assert_eq!(open_table(&db, &table_name_for("paper")).await.unwrap().count_rows(None).await.unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_preview_document() {
+        let db = test_db("preview").await;
+        let mut chunks = sample_chunks(40, 4);
+        // Stored out of order, as appends may leave them
+        chunks.reverse();
+        let (schema, batch) = build_chunk_batch(&chunks, 4).unwrap();
+        replace_table(&db, "doc_paper", schema.clone(), vec![Ok(batch)]).await.unwrap();
+        let table = db.open_table("doc_paper").execute().await.unwrap();
+
+        let preview = preview_document(&table, 13).await.unwrap();
+        assert_eq!(preview, DocumentPreview { chunk_count: 40, preview_text: "text 0\ntext 1".to_string(), truncated: true });
+
+        let preview = preview_document(&table, 10_000).await.unwrap();
+        assert!(!preview.truncated);
+        assert!(preview.preview_text.starts_with("text 0\ntext 1\ntext 2\n"));
+        assert!(preview.preview_text.ends_with("\ntext 39"));
+        assert_eq!(preview.preview_text.lines().count(), 40);
+
+        let (_, batch) = build_chunk_batch(&[], 4).unwrap();
+        replace_table(&db, "doc_empty", schema, vec![Ok(batch)]).await.unwrap();
+        let empty = db.open_table("doc_empty").execute().await.unwrap();
+        assert_eq!(preview_document(&empty, 100).await.unwrap(), DocumentPreview::default());
+    }
+
+    #[tokio::test]
+    async fn test_similar_documents() {
+        let db = test_db("similar").await;
+        let write = |document_id: &'static str, vectors: Vec<Vec<f32>>| {
+            let db = db.clone();
+            async move {
+                let chunks: Vec<ChunkData> = vectors
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, vector)| ChunkData { id: format!("{}-{}", document_id, i), text: "text".to_string(), vector, chunk_index: i as i32, text_length: 4 })
+                    .collect();
+                let dim = chunks[0].vector.len() as i32;
+                let (schema, batch) = build_chunk_batch(&chunks, dim).unwrap();
+                replace_table(&db, &table_name_for(document_id), schema, vec![Ok(batch)]).await.unwrap();
+            }
+        };
+        write("source", vec![vec![1.0, 0.2, 0.0], vec![1.0, -0.2, 0.0]]).await;
+        write("near", vec![vec![0.9, 0.1, 0.0], vec![0.8, 0.0, 0.1]]).await;
+        write("far", vec![vec![0.0, 0.1, 1.0]]).await;
+        write("flat", vec![vec![1.0, 0.0]]).await;
+
+        let ids = |similar: &[SimilarDocument]| similar.iter().map(|doc| doc.document_id.clone()).collect::<Vec<_>>();
+        let similar = similar_documents(&db, "source", 10).await.unwrap();
+        assert_eq!(ids(&similar), vec!["near", "far"]);
+        assert!(similar[0].score > 0.9 && similar[1].score < 0.2);
+        assert_eq!(ids(&similar_documents(&db, "source", 1).await.unwrap()), vec!["near"]);
+
+        // Centroids are cached per table version and recomputed after a rewrite
+        let version = db.open_table("doc_source").execute().await.unwrap().version().await.unwrap();
+        assert_eq!(read_cached_centroid(&db, "source", version).await.unwrap(), Some(vec![1.0, 0.0, 0.0]));
+        write("far", vec![vec![1.0, 0.0, 0.0]]).await;
+        assert_eq!(ids(&similar_documents(&db, "source", 10).await.unwrap()), vec!["far", "near"]);
+
+        delete_documents(&db, &["far".to_string()]).await.unwrap();
+        let far_rows = db.open_table(CENTROID_TABLE).execute().await.unwrap().count_rows(Some("document_id = 'far'".to_string())).await.unwrap();
+        assert_eq!(far_rows, 0);
+        assert!(matches!(similar_documents(&db, "missing", 10).await, Err(VectorStoreError::TableNotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_rename_document() {
+        let db = test_db("rename").await;
+        let (schema, batch) = build_chunk_batch(&sample_chunks(12, 4), 4).unwrap();
+        replace_table(&db, &table_name_for("draft"), schema.clone(), vec![Ok(batch)]).await.unwrap();
+        let metadata = DocumentMetadata { title: Some("Draft".to_string()), ..Default::default() };
+        write_document_metadata(&db, "draft", &metadata).await.unwrap();
+
+        assert_eq!(rename_document(&db, "draft", "paper/2401.12345").await.unwrap(), 12);
+        let table = open_table(&db, &table_name_for("paper/2401.12345")).await.unwrap();
+        assert_eq!(table.count_rows(None).await.unwrap(), 12);
+        assert_eq!(read_chunks(&table, true).await.unwrap().len(), 12);
+        assert_eq!(list_document_ids(&db).await.unwrap(), vec!["paper/2401.12345".to_string()]);
+        assert_eq!(read_document_metadata(&db, "paper/2401.12345").await.unwrap(), Some(metadata));
+        assert_eq!(read_document_metadata(&db, "draft").await.unwrap(), None);
+
+        // Never overwrites, and ids that differ only in escaping stay distinct
+        let (_, batch) = build_chunk_batch(&sample_chunks(2, 4), 4).unwrap();
+        replace_table(&db, &table_name_for("paper_2401.12345"), schema, vec![Ok(batch)]).await.unwrap();
+        assert!(matches!(
+            rename_document(&db, "paper/2401.12345", "paper_2401.12345").await,
+            Err(VectorStoreError::InvalidArgument(_))
+        ));
+        assert_eq!(open_table(&db, &table_name_for("paper_2401.12345")).await.unwrap().count_rows(None).await.unwrap(), 2);
+        assert!(matches!(rename_document(&db, "paper/2401.12345", "paper/2401.12345").await, Err(VectorStoreError::InvalidArgument(_))));
+        assert!(matches!(rename_document(&db, "missing", "other").await, Err(VectorStoreError::TableNotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_search_orders_ties_by_chunk_index() {
+        let db = test_db("search_ties").await;
+        // Identical vectors, stored in scrambled chunk order
+        let mut chunks: Vec<ChunkData> = sample_chunks(12, 4)
+            .into_iter()
+            .map(|chunk| ChunkData { vector: vec![1.0; 4], ..chunk })
+            .collect();
+        chunks.reverse();
+        chunks.swap(2, 9);
+        // Same chunk_index as chunk-3, so the id decides
+        chunks.push(ChunkData { id: "chunk-3b".to_string(), chunk_index: 3, ..chunks[0].clone() });
+        let (schema, batch) = build_chunk_batch(&chunks, 4).unwrap();
+        replace_table(&db, "doc_paper", schema, vec![Ok(batch)]).await.unwrap();
+        let table = db.open_table("doc_paper").execute().await.unwrap();
+
+        for _ in 0..3 {
+            let results = search_table(&table, vec![1.0; 4], 5, DistanceType::L2, None, false).await.unwrap();
+            let ids: Vec<&str> = results.iter().map(|result| result.id.as_str()).collect();
+            assert_eq!(ids, vec!["chunk-0", "chunk-1", "chunk-2", "chunk-3", "chunk-3b"]);
+
+            // The Arrow command orders ties the same way
+            let bytes = search_table_ipc(&table, vec![1.0; 4], 5, DistanceType::L2, None).await.unwrap();
+            let reader = arrow_ipc::reader::StreamReader::try_new(std::io::Cursor::new(bytes), None).unwrap();
+            let mut ipc_ids = Vec::new();
+            for batch in reader {
+                let batch = batch.unwrap();
+                let batch_ids = column::<StringArray>(&batch, "id").unwrap();
+                ipc_ids.extend((0..batch.num_rows()).map(|i| batch_ids.value(i).to_string()));
+            }
+            assert_eq!(ipc_ids, ids);
+        }
+
+        let mut results = search_table(&table, vec![1.0; 4], 20, DistanceType::L2, None, false).await.unwrap();
+        assert_eq!(results.len(), 13);
+        results.reverse();
+        sort_search_results(&mut results);
+        assert_eq!(results[0].chunk_index, 0);
+        assert_eq!(results[12].chunk_index, 11);
+    }
+
+    #[tokio::test]
+    async fn test_check_compatibility() {
+        let db = test_db("compatibility").await;
+        let report = check_compatibility(&db).await.unwrap();
+        assert_eq!(report.store_version, None);
+        assert_eq!(report.lancedb_version, LANCEDB_VERSION);
+
+        write_version_marker(&db).await.unwrap();
+        let marker = read_version_marker(&db).await.unwrap().unwrap();
+        assert_eq!(marker.format_version, STORE_FORMAT_VERSION);
+        assert_eq!(marker.lancedb_version, LANCEDB_VERSION);
+        // Initializing again keeps the original marker
+        write_version_marker(&db).await.unwrap();
+        assert_eq!(read_version_marker(&db).await.unwrap(), Some(marker.clone()));
+
+        let (schema, batch) = build_chunk_batch(&sample_chunks(3, 4), 4).unwrap();
+        replace_table(&db, &table_name_for("paper"), schema, vec![Ok(batch)]).await.unwrap();
+        // A table directory this build can't make sense of
+        let broken = Path::new(db.uri()).join(format!("{}.lance", table_name_for("broken")));
+        std::fs::create_dir_all(broken.join("_versions")).unwrap();
+        std::fs::write(broken.join("_versions").join("1.manifest"), b"not a manifest").unwrap();
+
+        let report = check_compatibility(&db).await.unwrap();
+        assert_eq!(report.store_version, Some(marker));
+        assert_eq!(report.readable, vec!["paper".to_string()]);
+        assert_eq!(report.unreadable.len(), 1);
+        assert_eq!(report.unreadable[0].document_id, "broken");
+        assert!(!report.unreadable[0].error.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_warm_tables() {
+        let db = test_db("warmup").await;
+        let state = StdArc::new(Mutex::new(VectorStoreState::new()));
+        let token = CancellationToken::new();
+
+        // An empty store has nothing to warm
+        let summary = warm_tables(&state, &db, "store".to_string(), Vec::new(), &token).await;
+        assert_eq!((summary.tables, summary.warmed, summary.cancelled), (0, 0, false));
+
+        let (schema, batch) = build_chunk_batch(&sample_chunks(3, 4), 4).unwrap();
+        replace_table(&db, &table_name_for("paper"), schema, vec![Ok(batch)]).await.unwrap();
+        let table_names = vec![table_name_for("paper"), table_name_for("missing")];
+        let summary = warm_tables(&state, &db, "store".to_string(), table_names.clone(), &token).await;
+        assert_eq!(summary.tables, 2);
+        assert_eq!(summary.warmed, 1);
+        assert_eq!(summary.failed, vec!["missing".to_string()]);
+        assert!(!summary.cancelled);
+
+        token.cancel();
+        let summary = warm_tables(&state, &db, "store".to_string(), table_names, &token).await;
+        assert_eq!(summary.warmed, 0);
+        assert!(summary.cancelled);
+    }
+
+    #[tokio::test]
+    async fn test_migrate_table_names() {
+        let db = test_db("migrate_table_names").await;
+        let state = StdArc::new(Mutex::new(VectorStoreState::new()));
+
+        // Written before table_name_for, with the id recorded in _metadata
+        let (schema, batch) = build_chunk_batch(&sample_chunks(3, 4), 4).unwrap();
+        append_to_table(&db, &legacy_table_name_for("arxiv-2311.18775"), schema, batch).await.unwrap();
+        let metadata = DocumentMetadata { title: Some("Paper".to_string()), ..Default::default() };
+        write_document_metadata(&db, "arxiv-2311.18775", &metadata).await.unwrap();
+        // Written before _metadata existed, so the original id is lost
+        let (schema, batch) = build_chunk_batch(&sample_chunks(2, 4), 4).unwrap();
+        append_to_table(&db, "doc_old_paper", schema, batch).await.unwrap();
+        // Named the same under both schemes
+        let (schema, batch) = build_chunk_batch(&sample_chunks(2, 4), 4).unwrap();
+        append_to_table(&db, "doc_current", schema, batch).await.unwrap();
+        write_document_metadata(&db, "current", &DocumentMetadata::default()).await.unwrap();
+
+        let report = migrate_table_names(&state, &db).await.unwrap();
+        assert_eq!(report.migrated, 1);
+        assert_eq!(report.migrated_documents, vec!["arxiv-2311.18775".to_string()]);
+        assert_eq!(report.unresolved, vec!["doc_old_paper".to_string()]);
+        let table = open_table(&db, &table_name_for("arxiv-2311.18775")).await.unwrap();
+        assert_eq!(table.count_rows(None).await.unwrap(), 3);
+        assert!(!table_exists(&db, "doc_arxiv_2311_18775").await.unwrap());
+        assert_eq!(read_document_metadata(&db, "arxiv-2311.18775").await.unwrap(), Some(metadata));
+
+        // Running it again finds nothing left to move
+        let report = migrate_table_names(&state, &db).await.unwrap();
+        assert_eq!(report.migrated, 0);
+        assert!(report.migrated_documents.is_empty());
+        assert_eq!(report.unresolved, vec!["doc_old_paper".to_string()]);
+        assert_eq!(open_table(&db, "doc_current").await.unwrap().count_rows(None).await.unwrap(), 2);
+    }
+}
@@ -0,0 +1,187 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager, PhysicalPosition, PhysicalSize, WebviewWindow, Window};
+use tracing::warn;
+
+/// File under the app config dir holding the last main window geometry
+const WINDOW_STATE_FILE: &str = "window-state.json";
+
+/// Geometry of the main window, in physical pixels
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WindowState {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub maximized: bool,
+}
+
+fn state_path(app: &AppHandle) -> Option<PathBuf> {
+    app.path()
+        .app_config_dir()
+        .ok()
+        .map(|dir| dir.join(WINDOW_STATE_FILE))
+}
+
+/// Read a saved state, ignoring missing, corrupt or degenerate files
+fn load_state(path: &Path) -> Option<WindowState> {
+    let contents = std::fs::read_to_string(path).ok()?;
+    let state: WindowState = serde_json::from_str(&contents).ok()?;
+    (state.width > 0 && state.height > 0).then_some(state)
+}
+
+fn save_state(path: &Path, state: &WindowState) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create config dir: {}", e))?;
+    }
+    let contents = serde_json::to_string_pretty(state)
+        .map_err(|e| format!("Failed to serialize window state: {}", e))?;
+    std::fs::write(path, contents).map_err(|e| format!("Failed to write window state: {}", e))
+}
+
+/// State to persist for the window's current geometry. A maximized window
+/// reports the screen's size, so keep the previous normal geometry to restore
+/// to once the user un-maximizes.
+fn next_state(
+    previous: Option<WindowState>,
+    position: PhysicalPosition<i32>,
+    size: PhysicalSize<u32>,
+    maximized: bool,
+) -> WindowState {
+    match previous {
+        Some(previous) if maximized => WindowState {
+            maximized,
+            ..previous
+        },
+        _ => WindowState {
+            x: position.x,
+            y: position.y,
+            width: size.width,
+            height: size.height,
+            maximized,
+        },
+    }
+}
+
+/// Apply the saved geometry to the main window, if there is one
+pub fn restore(window: &WebviewWindow) {
+    let Some(state) = state_path(window.app_handle()).and_then(|path| load_state(&path)) else {
+        return;
+    };
+
+    let _ = window.set_size(PhysicalSize::new(state.width, state.height));
+    let _ = window.set_position(PhysicalPosition::new(state.x, state.y));
+    if state.maximized {
+        let _ = window.maximize();
+    }
+}
+
+/// Remember the window's geometry for the next launch
+pub fn save(window: &Window) {
+    let Some(path) = state_path(window.app_handle()) else {
+        return;
+    };
+    let (Ok(position), Ok(size), Ok(maximized)) = (
+        window.outer_position(),
+        window.inner_size(),
+        window.is_maximized(),
+    ) else {
+        return;
+    };
+
+    let state = next_state(load_state(&path), position, size, maximized);
+    if let Err(e) = save_state(&path, &state) {
+        warn!("{}", e);
+    }
+}
+
+/// Forget the saved geometry so the next launch uses the default window size
+#[tauri::command]
+pub fn reset_window_state(app: AppHandle) -> Result<(), String> {
+    let Some(path) = state_path(&app) else {
+        return Ok(());
+    };
+    match std::fs::remove_file(&path) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(format!("Failed to remove window state: {}", e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_window_state_round_trip() {
+        let path = std::env::temp_dir()
+            .join(format!("redink_window_state_{}", std::process::id()))
+            .join(WINDOW_STATE_FILE);
+        let state = WindowState {
+            x: 10,
+            y: 20,
+            width: 1600,
+            height: 900,
+            maximized: false,
+        };
+
+        save_state(&path, &state).unwrap();
+        assert_eq!(load_state(&path), Some(state));
+
+        std::fs::write(&path, "not json").unwrap();
+        assert_eq!(load_state(&path), None);
+        std::fs::write(
+            &path,
+            r#"{"x":0,"y":0,"width":0,"height":900,"maximized":false}"#,
+        )
+        .unwrap();
+        assert_eq!(load_state(&path), None);
+
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_maximized_keeps_normal_geometry() {
+        let normal = WindowState {
+            x: 10,
+            y: 20,
+            width: 1600,
+            height: 900,
+            maximized: false,
+        };
+        let screen = (PhysicalPosition::new(0, 0), PhysicalSize::new(2560, 1440));
+
+        let maximized = next_state(Some(normal), screen.0, screen.1, true);
+        assert_eq!(
+            maximized,
+            WindowState {
+                maximized: true,
+                ..normal
+            }
+        );
+
+        // Without a previous state the maximized geometry is all there is
+        let first = next_state(None, screen.0, screen.1, true);
+        assert_eq!(
+            (first.width, first.height, first.maximized),
+            (2560, 1440, true)
+        );
+
+        let moved = next_state(
+            Some(normal),
+            PhysicalPosition::new(5, 5),
+            PhysicalSize::new(1200, 700),
+            false,
+        );
+        assert_eq!(
+            moved,
+            WindowState {
+                x: 5,
+                y: 5,
+                width: 1200,
+                height: 700,
+                maximized: false
+            }
+        );
+    }
+}
@@ -1,4 +1,5 @@
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 import { Home, Moon, Settings, Sun } from 'lucide-react';
 import React, { useCallback, useRef, useState } from 'react';
 import { useTranslation } from 'react-i18next';
@@ -57,8 +58,8 @@ export const Navbar: React.FC = () => {
           // Trigger debug mode
           loggers.ui('Debug backdoor triggered in development mode!');
           try {
-            await invoke('toggle_devtools');
-            loggers.ui('DevTools toggled successfully');
+            const isOpen = await invoke<boolean>('toggle_devtools');
+            loggers.ui(`DevTools ${isOpen ? 'opened' : 'closed'}`);
           } catch (error) {
             const errorMsg = error as string;
             loggers.ui('Failed to toggle devtools:', errorMsg);
@@ -87,6 +88,16 @@ export const Navbar: React.FC = () => {
     [navigate, clickCount, isDevelopment],
   );
 
+  // "New search" from the tray menu: go home and focus the search box
+  React.useEffect(() => {
+    const unlisten = listen('tray://new-search', () => {
+      navigate('/', { state: { newSearch: Date.now() } });
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [navigate]);
+
   // Reset click count if modifier is released
   React.useEffect(() => {
     const handleKeyUp = (event: KeyboardEvent) => {
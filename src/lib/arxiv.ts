@@ -14,6 +14,7 @@ export interface ArxivPaper {
   authors: string;
   category: string;
   publishedDate: string; // Note: Rust uses published_date, we map it here
+  updatedDate: string; // Date of the latest revision (Rust: updated_date)
   abstract: string; // Note: Rust uses abstract_text, we map it here
   downloadUrl: string; // Note: Rust uses download_url, we map it here
   pdfUrl: string; // Note: Rust uses pdf_url, we map it here
@@ -26,6 +27,7 @@ interface RustArxivPaper {
   authors: string;
   category: string;
   published_date: string;
+  updated_date: string;
   abstract_text: string;
   download_url: string;
   pdf_url: string;
@@ -46,6 +48,7 @@ function mapRustPaper(rustPaper: RustArxivPaper): ArxivPaper {
     authors: rustPaper.authors,
     category: rustPaper.category,
     publishedDate: rustPaper.published_date,
+    updatedDate: rustPaper.updated_date,
     abstract: rustPaper.abstract_text,
     downloadUrl: rustPaper.download_url,
     pdfUrl: rustPaper.pdf_url,
@@ -14,6 +14,21 @@ export interface VectorSearchResult {
   distance: number;
 }
 
+/**
+ * Error shape rejected by every vector_store_* command
+ */
+export interface VectorStoreError {
+  kind:
+    | 'not_initialized'
+    | 'connect'
+    | 'table_not_found'
+    | 'schema_mismatch'
+    | 'invalid_argument'
+    | 'query'
+    | 'io';
+  message: string;
+}
+
 interface RustVectorSearchResult {
   id: string;
   text: string;
@@ -21,6 +36,15 @@ interface RustVectorSearchResult {
   distance: number;
 }
 
+/**
+ * Result of vector_store_add_chunks
+ */
+export interface AddChunksReport {
+  table_name: string;
+  inserted: number;
+  skipped: number;
+}
+
 interface RustChunkData {
   id: string;
   text: string;
@@ -76,6 +100,12 @@ export class VectorStore {
 
       loggers.app('[VectorStore]', result);
       this.initialized = true;
+
+      // Load tables and indexes in the background so the first search is fast
+      const warmup = invoke<string>('vector_store_warmup', { storagePath: this.storagePath });
+      warmup.catch((warmupError) => {
+        loggers.app('[VectorStore] Warm-up failed:', warmupError);
+      });
     } catch (error) {
       loggers.app('[VectorStore] Failed to initialize:', error);
       throw error;
@@ -85,7 +115,11 @@ export class VectorStore {
   /**
    * Add chunks with embeddings to a table
    */
-  async addChunks(documentId: string, chunks: TextChunk[], embeddings: number[][]): Promise<void> {
+  async addChunks(
+    documentId: string,
+    chunks: TextChunk[],
+    embeddings: number[][],
+  ): Promise<AddChunksReport> {
     if (chunks.length !== embeddings.length) {
       throw new Error('Chunks and embeddings length mismatch');
     }
@@ -104,13 +138,17 @@ export class VectorStore {
         text_length: chunk.text.length,
       }));
 
-      const result = await invoke<string>('vector_store_add_chunks', {
+      const report = await invoke<AddChunksReport>('vector_store_add_chunks', {
         documentId,
         chunks: rustChunks,
         storagePath: this.storagePath,
       });
 
-      loggers.app(`[VectorStore] ${result}`);
+      loggers.app(
+        `[VectorStore] Added ${report.inserted} chunks to table ${report.table_name}` +
+          (report.skipped > 0 ? ` (${report.skipped} short chunks skipped)` : ''),
+      );
+      return report;
     } catch (error) {
       loggers.app('[VectorStore] Failed to add chunks:', error);
       throw error;
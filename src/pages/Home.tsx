@@ -15,7 +15,7 @@ import {
   X,
 } from 'lucide-react';
 import React, { useCallback, useRef, useState } from 'react';
-import { useNavigate } from 'react-router-dom';
+import { useLocation, useNavigate } from 'react-router-dom';
 import { Button } from '../components/ui/button';
 import { Input } from '../components/ui/input';
 import { ToastContainer, useToast } from '../components/ui/toast';
@@ -36,7 +36,9 @@ import { useAppStore } from '../store';
 
 export const Home: React.FC = () => {
   const navigate = useNavigate();
+  const location = useLocation();
   const fileInputRef = useRef<HTMLInputElement>(null);
+  const searchInputRef = useRef<HTMLInputElement>(null);
 
   // Store hooks
   const recentFiles = useAppStore((s) => s.recentFiles);
@@ -70,6 +72,15 @@ export const Home: React.FC = () => {
   const arxivPapersRef = useRef(arxivPapers);
   arxivPapersRef.current = arxivPapers;
 
+  // Start a fresh search when asked from the tray menu
+  const newSearchRequest = (location.state as { newSearch?: number } | null)?.newSearch;
+  React.useEffect(() => {
+    if (newSearchRequest) {
+      setArxivQuery('');
+      searchInputRef.current?.focus();
+    }
+  }, [newSearchRequest]);
+
   // Load recent files, preferences, and initialize storage on mount
   React.useEffect(() => {
     const initialize = async () => {
@@ -834,6 +845,7 @@ export const Home: React.FC = () => {
         <div className='relative mb-6'>
           <Search className='absolute top-1/2 left-3 h-4 w-4 -translate-y-1/2 transform text-gray-400' />
           <Input
+            ref={searchInputRef}
             placeholder='Search papers by title, author, or category...'
             value={arxivQuery}
             onChange={(e) => setArxivQuery(e.target.value)}